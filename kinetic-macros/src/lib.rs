@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: MIT
+
+//! Procedural macros for kinetic-rs
+//!
+//! `#[tool]` turns an async function into a `Tool` implementation:
+//!
+//! ```ignore
+//! use kinetic_rs::adk::tool::tool;
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct GetIssueArgs {
+//!     /// The issue key (e.g. PROJ-123)
+//!     issue_key: String,
+//! }
+//!
+//! /// Gets detailed information about a Jira issue by its key.
+//! #[tool(name = "get_jira_issue")]
+//! async fn get_issue(client: &JiraClient, args: GetIssueArgs) -> Result<Value, Box<dyn Error + Send + Sync>> {
+//!     ...
+//! }
+//!
+//! let tool = GetIssueTool::new(client);
+//! ```
+//!
+//! The function takes up to two parameters: an optional `&State` the tool
+//! holds (a client, config, ...) and an optional arguments type implementing
+//! `Deserialize` and `JsonSchema`. It returns a `Result` whose value is
+//! `Serialize`. The generated struct is named after the function in
+//! `PascalCase` with a `Tool` suffix, unless `struct_name` is given.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, ExprLit, FnArg, Ident, ItemFn, Lit, Meta, MetaNameValue, Pat, Token, Type};
+
+/// Generate a `Tool` implementation from an async function
+///
+/// Attribute arguments (all optional):
+/// - `name = "..."`: tool name, defaults to the function name
+/// - `description = "..."`: defaults to the function's doc comment
+/// - `struct_name = "..."`: name of the generated struct
+#[proc_macro_attribute]
+pub fn tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr.into(), item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct ToolAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    struct_name: Option<Ident>,
+}
+
+fn parse_attrs(attr: proc_macro2::TokenStream) -> syn::Result<ToolAttrs> {
+    let mut attrs = ToolAttrs {
+        name: None,
+        description: None,
+        struct_name: None,
+    };
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(attr)?;
+    for arg in args {
+        let value = match &arg.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => s.clone(),
+            other => return Err(Error::new_spanned(other, "expected a string literal")),
+        };
+        if arg.path.is_ident("name") {
+            attrs.name = Some(value.value());
+        } else if arg.path.is_ident("description") {
+            attrs.description = Some(value.value());
+        } else if arg.path.is_ident("struct_name") {
+            attrs.struct_name = Some(value.parse()?);
+        } else {
+            return Err(Error::new_spanned(
+                &arg.path,
+                "unknown tool attribute, expected `name`, `description` or `struct_name`",
+            ));
+        }
+    }
+    Ok(attrs)
+}
+
+fn expand(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = parse_attrs(attr)?;
+    let func: ItemFn = syn::parse2(item)?;
+    let sig = &func.sig;
+
+    if sig.asyncness.is_none() {
+        return Err(Error::new_spanned(
+            sig.fn_token,
+            "#[tool] functions must be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "#[tool] functions cannot be generic",
+        ));
+    }
+
+    let (state, args) = split_params(sig)?;
+
+    let vis = &func.vis;
+    let fn_name = &sig.ident;
+    let tool_name = attrs.name.unwrap_or_else(|| fn_name.to_string());
+    let description = match attrs.description.or_else(|| doc_comment(&func)) {
+        Some(d) => d,
+        None => {
+            return Err(Error::new_spanned(
+                fn_name,
+                "#[tool] needs a doc comment or a `description = \"...\"` argument",
+            ))
+        }
+    };
+    let struct_name = attrs
+        .struct_name
+        .unwrap_or_else(|| format_ident!("{}Tool", pascal_case(&fn_name.to_string())));
+
+    let krate = quote!(::kinetic_rs::adk::tool);
+    let private = quote!(#krate::__private);
+
+    let (definition, constructor, state_arg) = match &state {
+        Some(state) => (
+            quote! {
+                #vis struct #struct_name {
+                    state: #state,
+                }
+            },
+            quote! {
+                impl #struct_name {
+                    #vis fn new(state: #state) -> Self {
+                        Self { state }
+                    }
+                }
+            },
+            quote!(&self.state,),
+        ),
+        None => (
+            quote! {
+                #[derive(Default)]
+                #vis struct #struct_name;
+            },
+            quote! {
+                impl #struct_name {
+                    #vis fn new() -> Self {
+                        Self
+                    }
+                }
+            },
+            quote!(),
+        ),
+    };
+
+    let (schema, parse_args, call_args) = match &args {
+        Some(args) => (
+            quote!(#krate::schema_for::<#args>()),
+            quote! {
+                let args: #args = #private::serde_json::from_value(input)?;
+            },
+            quote!(args),
+        ),
+        None => (
+            quote!(#private::serde_json::json!({"type": "object", "properties": {}})),
+            quote! {
+                let _ = input;
+            },
+            quote!(),
+        ),
+    };
+
+    let doc = format!("Tool generated from `{}`", fn_name);
+
+    Ok(quote! {
+        #func
+
+        #[doc = #doc]
+        #definition
+
+        #constructor
+
+        #[#private::async_trait::async_trait]
+        impl #krate::Tool for #struct_name {
+            fn name(&self) -> &str {
+                #tool_name
+            }
+
+            fn description(&self) -> &str {
+                #description
+            }
+
+            fn schema(&self) -> &#private::serde_json::Value {
+                static SCHEMA: #private::Lazy<#private::serde_json::Value> =
+                    #private::Lazy::new(|| #schema);
+                &SCHEMA
+            }
+
+            async fn execute(
+                &self,
+                input: #private::serde_json::Value,
+            ) -> ::std::result::Result<
+                #private::serde_json::Value,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>,
+            > {
+                #parse_args
+                let output = #fn_name(#state_arg #call_args).await?;
+                Ok(#private::serde_json::to_value(output)?)
+            }
+        }
+    })
+}
+
+/// Split the parameters into the state type (behind `&`) and the args type
+fn split_params(sig: &syn::Signature) -> syn::Result<(Option<Type>, Option<Type>)> {
+    let mut types = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            FnArg::Receiver(r) => {
+                return Err(Error::new_spanned(
+                    r,
+                    "#[tool] functions cannot take `self`",
+                ))
+            }
+            FnArg::Typed(pat) => {
+                if !matches!(*pat.pat, Pat::Ident(_) | Pat::Wild(_)) {
+                    return Err(Error::new_spanned(
+                        &pat.pat,
+                        "#[tool] parameters must be plain identifiers",
+                    ));
+                }
+                types.push((*pat.ty).clone());
+            }
+        }
+    }
+
+    let mut types = types.into_iter().peekable();
+    let state = match types.peek() {
+        Some(Type::Reference(r)) => {
+            if r.mutability.is_some() {
+                return Err(Error::new_spanned(
+                    r,
+                    "tool state must be a shared reference",
+                ));
+            }
+            let ty = (*r.elem).clone();
+            types.next();
+            Some(ty)
+        }
+        _ => None,
+    };
+    let args = types.next();
+
+    if let Some(extra) = types.next() {
+        return Err(Error::new_spanned(
+            extra,
+            "#[tool] functions take at most a `&State` and an arguments type",
+        ));
+    }
+    if let Some(Type::Reference(r)) = &args {
+        return Err(Error::new_spanned(
+            r,
+            "the arguments type must be taken by value",
+        ));
+    }
+    Ok((state, args))
+}
+
+/// Join the `///` lines of the function into one description
+fn doc_comment(func: &ItemFn) -> Option<String> {
+    let lines: Vec<String> = func
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }),
+                ..
+            }) => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let doc = lines.join(" ").trim().to_string();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pascal_case() {
+        assert_eq!(pascal_case("get_issue"), "GetIssue");
+        assert_eq!(pascal_case("fetch_pr"), "FetchPr");
+        assert_eq!(pascal_case("_private_helper"), "PrivateHelper");
+    }
+
+    #[test]
+    fn test_rejects_sync_functions() {
+        let err = expand(
+            quote!(),
+            quote!(
+                fn sync_tool(args: Args) {}
+            ),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must be async"));
+    }
+
+    #[test]
+    fn test_requires_description() {
+        let err = expand(
+            quote!(),
+            quote!(
+                async fn no_docs(args: Args) {}
+            ),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("doc comment"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_attribute() {
+        let err = expand(
+            quote!(title = "x"),
+            quote!(
+                /// Docs
+                async fn t(args: Args) {}
+            ),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown tool attribute"));
+    }
+
+    #[test]
+    fn test_rejects_extra_parameters() {
+        let err = expand(
+            quote!(),
+            quote!(
+                /// Docs
+                async fn t(state: &State, args: Args, more: Args) {}
+            ),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("at most"));
+    }
+}
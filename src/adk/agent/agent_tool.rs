@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: MIT
+
+//! Agent-as-tool adapter
+//!
+//! `AgentTool` exposes any `Agent` (including whole workflows) as a `Tool`, so
+//! one agent can call another. Nested calls are tracked per task and refused
+//! beyond a maximum depth, which stops agents that (directly or indirectly)
+//! call themselves from recursing forever.
+
+use super::{Agent, AgentMessage};
+use crate::adk::tool::Tool;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Default maximum nesting of agent tool calls
+pub const DEFAULT_MAX_DEPTH: u32 = 3;
+
+tokio::task_local! {
+    /// Number of agent tool calls enclosing the current one
+    static DEPTH: u32;
+}
+
+/// Tool that runs an agent
+pub struct AgentTool {
+    name: String,
+    description: String,
+    schema: Value,
+    max_depth: u32,
+    agent: Arc<dyn Agent>,
+}
+
+impl AgentTool {
+    /// Wrap `agent` as a tool; `name` is sanitized to a valid tool name
+    pub fn new(name: &str, description: String, agent: Arc<dyn Agent>) -> Self {
+        Self {
+            name: tool_name(name),
+            description,
+            schema: default_input_schema(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            agent,
+        }
+    }
+
+    /// Accept structured arguments matching `schema` instead of `task`/`context`
+    pub fn with_input_schema(mut self, schema: Value) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Maximum nesting of agent tool calls, counting this one
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for AgentTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let output = run_nested(
+            &self.name,
+            self.agent.as_ref(),
+            input_message(input),
+            self.max_depth,
+        )
+        .await?;
+        Ok(output.to_value())
+    }
+}
+
+/// Run `agent` one nesting level deeper, failing if `max_depth` is exceeded
+pub(crate) async fn run_nested(
+    name: &str,
+    agent: &dyn Agent,
+    input: AgentMessage,
+    max_depth: u32,
+) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+    let depth = DEPTH.try_with(|d| *d).unwrap_or(0);
+    if depth >= max_depth {
+        return Err(format!(
+            "Agent tool '{}' exceeds the maximum nesting depth of {}",
+            name, max_depth
+        )
+        .into());
+    }
+    log::debug!("Running agent tool {} at depth {}", name, depth + 1);
+    DEPTH.scope(depth + 1, agent.run_message(input)).await
+}
+
+/// Default tool arguments: a task and optional context
+pub(crate) fn default_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "task": {
+                "type": "string",
+                "description": "What the agent should do, with all details it needs"
+            },
+            "context": {
+                "type": "string",
+                "description": "Relevant results from earlier steps"
+            }
+        },
+        "required": ["task"]
+    })
+}
+
+/// Turn tool arguments into the agent's input message
+///
+/// `task`/`context` arguments become plain text; anything else is passed as JSON.
+pub(crate) fn input_message(input: Value) -> AgentMessage {
+    let task = input.get("task").and_then(Value::as_str);
+    let is_default_shape = input
+        .as_object()
+        .is_some_and(|o| o.keys().all(|k| k == "task" || k == "context"));
+
+    match task {
+        Some(task) if is_default_shape => match input.get("context").and_then(Value::as_str) {
+            Some(context) if !context.is_empty() => {
+                AgentMessage::text(format!("{}\n\nContext:\n{}", task, context))
+            }
+            _ => AgentMessage::text(task),
+        },
+        _ => AgentMessage::json(input),
+    }
+}
+
+/// Agent names as tool names: providers only accept `[A-Za-z0-9_-]`
+pub(crate) fn tool_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::OnceLock;
+
+    /// Agent that calls itself through an `AgentTool` until refused
+    struct RecursiveAgent {
+        tool: OnceLock<AgentTool>,
+    }
+
+    #[async_trait]
+    impl Agent for RecursiveAgent {
+        fn name(&self) -> &str {
+            "recursive"
+        }
+
+        async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+            let tool = self.tool.get().unwrap();
+            match tool.execute(json!({"task": format!("{}+", input)})).await {
+                Ok(result) => Ok(result.as_str().unwrap_or_default().to_string()),
+                Err(e) => Ok(format!("{} stopped: {}", input, e)),
+            }
+        }
+    }
+
+    struct EchoAgent;
+
+    #[async_trait]
+    impl Agent for EchoAgent {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+            Ok(input)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_runs_agent() {
+        let tool = AgentTool::new("Echo Agent", "Echoes".to_string(), Arc::new(EchoAgent));
+        assert_eq!(tool.name(), "Echo_Agent");
+        assert_eq!(tool.schema()["required"], json!(["task"]));
+
+        let result = tool.execute(json!({"task": "hello"})).await.unwrap();
+        assert_eq!(result, json!("hello"));
+    }
+
+    #[tokio::test]
+    async fn test_recursion_stops_at_max_depth() {
+        let agent = Arc::new(RecursiveAgent {
+            tool: OnceLock::new(),
+        });
+        let tool = AgentTool::new("recursive", "Calls itself".to_string(), agent.clone())
+            .with_max_depth(2);
+        let _ = agent.tool.set(tool);
+
+        let result = agent
+            .tool
+            .get()
+            .unwrap()
+            .execute(json!({"task": "x"}))
+            .await;
+        let output = result.unwrap();
+        let text = output.as_str().unwrap();
+        // Depth 1 and 2 run, the third nested call is refused
+        assert!(text.starts_with("x+ stopped"));
+        assert!(text.contains("maximum nesting depth of 2"));
+    }
+
+    #[test]
+    fn test_input_message_shapes() {
+        let msg = input_message(json!({"task": "Do it", "context": "ctx"}));
+        assert_eq!(msg.text, "Do it\n\nContext:\nctx");
+        assert!(msg.data.is_none());
+
+        let msg = input_message(json!({"pr_number": 42}));
+        assert_eq!(msg.data, Some(json!({"pr_number": 42})));
+    }
+}
@@ -0,0 +1,559 @@
+// SPDX-License-Identifier: MIT
+
+//! Chain-of-Thought Agent
+//!
+//! Asks the model to reason step by step before answering and separates the
+//! reasoning from the final answer. With self-consistency enabled, several
+//! reasoning paths are sampled and the final answer is chosen by majority
+//! vote or by an LLM judge.
+
+use super::react_parser::parse_args;
+use super::{emit, run_inner_message, Agent, AgentEvent, AgentMessage, LLMAgent};
+use crate::adk::model::{Content, GenerationConfig, Model, Part};
+use crate::adk::tool::Tool;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// How the final answer is chosen among sampled reasoning paths
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingStrategy {
+    /// The most common answer wins; ties go to the earliest path
+    #[default]
+    Majority,
+    /// The model picks the best answer after seeing all paths
+    Judge,
+}
+
+/// Default sampling temperature for self-consistency paths; above 0 so the
+/// paths can differ
+pub const DEFAULT_SAMPLING_TEMPERATURE: f32 = 0.7;
+
+/// Self-consistency settings for the CoT executor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SelfConsistency {
+    /// Number of reasoning paths to sample
+    pub samples: u32,
+    /// How to pick the final answer
+    #[serde(default)]
+    pub voting: VotingStrategy,
+    /// Temperature the paths are sampled with when there is more than one
+    #[serde(default = "default_sampling_temperature")]
+    pub temperature: f32,
+}
+
+fn default_sampling_temperature() -> f32 {
+    DEFAULT_SAMPLING_TEMPERATURE
+}
+
+impl Default for SelfConsistency {
+    fn default() -> Self {
+        Self {
+            samples: 1,
+            voting: VotingStrategy::default(),
+            temperature: DEFAULT_SAMPLING_TEMPERATURE,
+        }
+    }
+}
+
+/// One sampled reasoning path
+#[derive(Debug, Clone)]
+struct ReasoningPath {
+    reasoning: String,
+    answer: String,
+}
+
+/// Chain-of-Thought agent
+pub struct CoTAgent {
+    pub name: String,
+    pub description: String,
+    pub instruction: String,
+    pub model: Arc<dyn Model>,
+    pub self_consistency: SelfConsistency,
+    /// Agent that produces each reasoning path
+    executor: LLMAgent,
+}
+
+impl CoTAgent {
+    pub fn new(
+        name: String,
+        description: String,
+        instruction: String,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+    ) -> Self {
+        let executor = LLMAgent::new(
+            name.clone(),
+            description.clone(),
+            Self::reasoning_instruction(&instruction),
+            model.clone(),
+            tools,
+        );
+
+        Self {
+            name,
+            description,
+            instruction,
+            model,
+            self_consistency: SelfConsistency::default(),
+            executor,
+        }
+    }
+
+    /// Use a custom agent (e.g. with different turn limits) to produce paths
+    pub fn with_executor(mut self, executor: LLMAgent) -> Self {
+        self.executor = executor;
+        self.apply_sampling_temperature();
+        self
+    }
+
+    /// Sample several reasoning paths and vote on the answer
+    pub fn with_self_consistency(mut self, self_consistency: SelfConsistency) -> Self {
+        self.self_consistency = self_consistency;
+        self.apply_sampling_temperature();
+        self
+    }
+
+    /// Sample paths with the self-consistency temperature when there are
+    /// several, since identical paths make the vote pointless
+    fn apply_sampling_temperature(&mut self) {
+        if self.self_consistency.samples <= 1 {
+            return;
+        }
+        let config = self.executor.generation_config.take().unwrap_or_default();
+        self.executor.generation_config = Some(GenerationConfig {
+            temperature: Some(self.self_consistency.temperature),
+            ..config
+        });
+    }
+
+    /// Instructions with the reasoning scaffold appended
+    pub fn reasoning_instruction(instruction: &str) -> String {
+        format!(
+            r#"{}
+
+Think through the problem step by step before answering:
+1. Restate what is being asked
+2. Work through the relevant facts and intermediate steps, using tools if needed
+3. Check your reasoning for mistakes
+
+Write your reasoning first, then end with a line starting with "Final Answer:"
+followed by only the answer."#,
+            instruction
+        )
+    }
+
+    /// Sample one reasoning path
+    async fn sample(
+        &self,
+        input: &str,
+        attachments: &[Part],
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> Result<ReasoningPath, Box<dyn Error + Send + Sync>> {
+        let message = AgentMessage::text(input).with_attachments(attachments.to_vec());
+        let output = run_inner_message(&self.executor, message, tx).await?;
+        Ok(split_reasoning(&output.text))
+    }
+
+    /// Ask the model to pick the best path; `None` if its reply is unusable
+    async fn judge(
+        &self,
+        input: &str,
+        paths: &[ReasoningPath],
+    ) -> Result<Option<usize>, Box<dyn Error + Send + Sync>> {
+        let candidates: Vec<String> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                format!(
+                    "Candidate {}:\nReasoning: {}\nAnswer: {}",
+                    i + 1,
+                    p.reasoning,
+                    p.answer
+                )
+            })
+            .collect();
+        let history = vec![
+            Content {
+                role: "system".to_string(),
+                parts: vec![Part::Text(format!(
+                    "{}\n\nYou are judging candidate answers to the question below. Pick the \
+                     candidate whose reasoning is sound and whose answer is correct.\n\
+                     Respond ONLY with JSON: {{\"choice\": <candidate number>}}",
+                    self.instruction
+                ))],
+            },
+            Content {
+                role: "user".to_string(),
+                parts: vec![Part::Text(format!(
+                    "Question: {}\n\n{}",
+                    input,
+                    candidates.join("\n\n")
+                ))],
+            },
+        ];
+
+        let response = self.model.generate_content(&history, None, None).await?;
+        let text: String = response
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        let choice = parse_args(&text)["choice"].as_u64().map(|c| c as usize);
+        Ok(choice
+            .filter(|c| (1..=paths.len()).contains(c))
+            .map(|c| c - 1))
+    }
+
+    /// Sample the reasoning paths, each given `attachments` with the task,
+    /// and choose the answer
+    async fn execute(
+        &self,
+        input: &str,
+        attachments: &[Part],
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let samples = self.self_consistency.samples.max(1) as usize;
+        log::info!(
+            "CoTAgent {} sampling {} reasoning path(s)",
+            self.name,
+            samples
+        );
+
+        let results = join_all((0..samples).map(|_| self.sample(input, attachments, tx))).await;
+        let mut paths = Vec::with_capacity(samples);
+        let mut first_error = None;
+        for result in results {
+            match result {
+                Ok(path) => {
+                    emit(
+                        tx,
+                        AgentEvent::ReasoningPath {
+                            index: paths.len(),
+                            reasoning: path.reasoning.clone(),
+                            answer: path.answer.clone(),
+                        },
+                    )
+                    .await;
+                    paths.push(path);
+                }
+                Err(e) => {
+                    log::error!("CoTAgent {} reasoning path failed: {}", self.name, e);
+                    emit(
+                        tx,
+                        AgentEvent::Error(format!("Reasoning path failed: {}", e)),
+                    )
+                    .await;
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if paths.is_empty() {
+            return Err(first_error.unwrap_or_else(|| "No reasoning paths produced".into()));
+        }
+
+        let tally = tally_answers(&paths);
+        let (method, winner) = if paths.len() == 1 {
+            ("single", 0)
+        } else {
+            match self.self_consistency.voting {
+                VotingStrategy::Majority => ("majority", tally[0].0),
+                VotingStrategy::Judge => match self.judge(input, &paths).await? {
+                    Some(choice) => ("judge", choice),
+                    None => {
+                        log::warn!("Judge gave no usable choice, falling back to majority vote");
+                        ("majority", tally[0].0)
+                    }
+                },
+            }
+        };
+
+        let answer = paths[winner].answer.clone();
+        emit(
+            tx,
+            AgentEvent::Vote {
+                method: method.to_string(),
+                answer: answer.clone(),
+                tally: tally
+                    .iter()
+                    .map(|(i, votes)| (paths[*i].answer.clone(), *votes))
+                    .collect(),
+            },
+        )
+        .await;
+        emit(tx, AgentEvent::Answer(answer.clone())).await;
+        Ok(answer)
+    }
+}
+
+/// Split model output into reasoning and the text after the last "Final Answer:"
+fn split_reasoning(output: &str) -> ReasoningPath {
+    const MARKER: &str = "final answer:";
+    let lower = output.to_ascii_lowercase();
+    match lower.rfind(MARKER) {
+        Some(pos) => ReasoningPath {
+            reasoning: output[..pos]
+                .trim()
+                .trim_end_matches('*')
+                .trim()
+                .to_string(),
+            answer: output[pos + MARKER.len()..]
+                .trim()
+                .trim_start_matches('*')
+                .trim()
+                .to_string(),
+        },
+        None => ReasoningPath {
+            reasoning: String::new(),
+            answer: output.trim().to_string(),
+        },
+    }
+}
+
+/// Normalize an answer for voting: case, whitespace and trailing punctuation
+fn normalize_answer(answer: &str) -> String {
+    answer
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '!'])
+        .to_lowercase()
+}
+
+/// Count votes per distinct answer as `(first path index, votes)`, most votes first
+fn tally_answers(paths: &[ReasoningPath]) -> Vec<(usize, usize)> {
+    let mut tally: Vec<(String, usize, usize)> = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let key = normalize_answer(&path.answer);
+        match tally.iter_mut().find(|(k, _, _)| *k == key) {
+            Some(entry) => entry.2 += 1,
+            None => tally.push((key, i, 1)),
+        }
+    }
+    // Stable sort keeps the earliest path first among ties
+    tally.sort_by_key(|entry| std::cmp::Reverse(entry.2));
+    tally.into_iter().map(|(_, i, votes)| (i, votes)).collect()
+}
+
+#[async_trait]
+impl Agent for CoTAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.execute(&input, &[], None).await
+    }
+
+    async fn run_stream(
+        &self,
+        input: String,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.execute(&input, &[], Some(&tx)).await
+    }
+
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self.execute(&input.text, &input.attachments, None).await?;
+        Ok(AgentMessage::from_output(output))
+    }
+
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self
+            .execute(&input.text, &input.attachments, Some(&tx))
+            .await?;
+        Ok(AgentMessage::from_output(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Mock model returning scripted responses and recording system prompts
+    struct ScriptedModel {
+        responses: Mutex<Vec<&'static str>>,
+        systems: Mutex<Vec<String>>,
+        temperatures: Mutex<Vec<Option<f32>>>,
+        user_parts: Mutex<Vec<Vec<Part>>>,
+    }
+
+    #[async_trait]
+    impl Model for ScriptedModel {
+        async fn generate_content(
+            &self,
+            history: &[Content],
+            config: Option<&GenerationConfig>,
+            _tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            self.temperatures
+                .lock()
+                .unwrap()
+                .push(config.and_then(|c| c.temperature));
+            if let Some(user) = history.iter().find(|c| c.role == "user") {
+                self.user_parts.lock().unwrap().push(user.parts.clone());
+            }
+            if let Some(Part::Text(t)) = history.first().and_then(|c| c.parts.first()) {
+                self.systems.lock().unwrap().push(t.clone());
+            }
+            Ok(Content {
+                role: "model".to_string(),
+                parts: vec![Part::Text(
+                    self.responses.lock().unwrap().remove(0).to_string(),
+                )],
+            })
+        }
+    }
+
+    fn agent(responses: Vec<&'static str>, sc: SelfConsistency) -> (CoTAgent, Arc<ScriptedModel>) {
+        let model = Arc::new(ScriptedModel {
+            responses: Mutex::new(responses),
+            systems: Mutex::new(vec![]),
+            temperatures: Mutex::new(vec![]),
+            user_parts: Mutex::new(vec![]),
+        });
+        let agent = CoTAgent::new(
+            "cot".to_string(),
+            "test".to_string(),
+            "Solve the problem".to_string(),
+            model.clone(),
+            vec![],
+        )
+        .with_self_consistency(sc);
+        (agent, model)
+    }
+
+    #[test]
+    fn test_split_reasoning() {
+        let path = split_reasoning("17 + 25 = 42\n**Final Answer:** 42");
+        assert_eq!(path.reasoning, "17 + 25 = 42");
+        assert_eq!(path.answer, "42");
+
+        let path = split_reasoning("just an answer");
+        assert_eq!(path.reasoning, "");
+        assert_eq!(path.answer, "just an answer");
+    }
+
+    #[test]
+    fn test_tally_normalizes_and_breaks_ties_by_order() {
+        let path = |a: &str| ReasoningPath {
+            reasoning: String::new(),
+            answer: a.to_string(),
+        };
+        let paths = vec![path("Paris"), path("Lyon"), path("paris."), path("Lyon")];
+        assert_eq!(tally_answers(&paths), vec![(0, 2), (1, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_single_path_separates_reasoning() {
+        let (agent, model) = agent(
+            vec!["Two apples plus three is five.\nFinal Answer: 5"],
+            SelfConsistency::default(),
+        );
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let answer = agent.run_stream("2 + 3?".to_string(), tx).await.unwrap();
+        assert_eq!(answer, "5");
+        assert!(model.systems.lock().unwrap()[0].contains("step by step"));
+        assert_eq!(model.temperatures.lock().unwrap()[0], None);
+
+        let mut reasoning = None;
+        while let Ok(event) = rx.try_recv() {
+            if let AgentEvent::ReasoningPath { reasoning: r, .. } = event {
+                reasoning = Some(r);
+            }
+        }
+        assert_eq!(reasoning.as_deref(), Some("Two apples plus three is five."));
+    }
+
+    #[tokio::test]
+    async fn test_run_message_sends_attachments_to_each_path() {
+        let (agent, model) = agent(
+            vec!["a\nFinal Answer: cat", "b\nFinal Answer: cat"],
+            SelfConsistency {
+                samples: 2,
+                ..Default::default()
+            },
+        );
+
+        let image = Part::Image(crate::adk::model::Media::from_bytes("image/png", b"png"));
+        let input = AgentMessage::text("What animal is this?").with_attachments(vec![image]);
+        assert_eq!(agent.run_message(input).await.unwrap().text, "cat");
+
+        let turns = model.user_parts.lock().unwrap();
+        assert_eq!(turns.len(), 2);
+        assert!(turns
+            .iter()
+            .all(|parts| matches!(&parts[..], [Part::Text(_), Part::Image(_)])));
+    }
+
+    #[tokio::test]
+    async fn test_majority_vote() {
+        let (agent, model) = agent(
+            vec![
+                "a\nFinal Answer: 42",
+                "b\nFinal Answer: 41",
+                "c\nFinal Answer: 42.",
+            ],
+            SelfConsistency {
+                samples: 3,
+                voting: VotingStrategy::Majority,
+                temperature: 0.9,
+            },
+        );
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let answer = agent.run_stream("?".to_string(), tx).await.unwrap();
+        assert_eq!(answer, "42");
+
+        let mut paths = 0;
+        let mut vote = None;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                AgentEvent::ReasoningPath { .. } => paths += 1,
+                AgentEvent::Vote { method, tally, .. } => vote = Some((method, tally)),
+                _ => {}
+            }
+        }
+        assert_eq!(paths, 3);
+        let (method, tally) = vote.unwrap();
+        assert_eq!(method, "majority");
+        assert_eq!(tally[0], ("42".to_string(), 2));
+        assert_eq!(*model.temperatures.lock().unwrap(), vec![Some(0.9); 3]);
+    }
+
+    #[tokio::test]
+    async fn test_judge_vote() {
+        let (agent, model) = agent(
+            vec![
+                "a\nFinal Answer: 42",
+                "b\nFinal Answer: 42",
+                "c\nFinal Answer: 41",
+                r#"{"choice": 3}"#,
+            ],
+            SelfConsistency {
+                samples: 3,
+                voting: VotingStrategy::Judge,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(agent.run("?".to_string()).await.unwrap(), "41");
+        assert!(model.systems.lock().unwrap()[3].contains("judging"));
+    }
+}
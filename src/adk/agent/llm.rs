@@ -5,13 +5,17 @@
 //! This agent sends prompts to an LLM and handles tool calls in a loop
 //! until a text response is received.
 
-use super::{Agent, AgentEvent};
-use crate::adk::model::{Content, Model, Part};
-use crate::adk::tool::Tool;
+use super::{emit, Agent, AgentEvent, AgentMessage};
+use crate::adk::error::KineticError;
+use crate::adk::model::{Content, GenerationConfig, Model, Part};
+use crate::adk::tool::{self, Tool};
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 /// Standard LLM agent with tool calling support
 pub struct LLMAgent {
@@ -20,10 +24,45 @@ pub struct LLMAgent {
     pub instruction: String,
     pub model: Arc<dyn Model>,
     pub tools: Vec<Arc<dyn Tool>>,
+    /// Maximum number of model turns before the exhaustion strategy applies
+    pub max_turns: u32,
+    /// What to do when `max_turns` is reached without a final answer
+    pub on_max_turns: ExhaustionStrategy,
+    /// Maximum number of tool calls from one turn executed concurrently
+    pub max_parallel_tools: usize,
+    /// Sampling settings sent with every model request
+    pub generation_config: Option<GenerationConfig>,
     /// HashMap for O(1) tool lookups
     tool_map: HashMap<String, usize>,
 }
 
+/// Default limit on model turns per run
+pub const DEFAULT_MAX_TURNS: u32 = 10;
+
+/// Default limit on concurrent tool calls per turn
+pub const DEFAULT_MAX_PARALLEL_TOOLS: usize = 4;
+
+/// Behaviour when an agent runs out of turns while still calling tools
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExhaustionStrategy {
+    /// Fail with `KineticError::MaxIterations`
+    #[default]
+    Error,
+    /// Ask the model for one last answer from what it has gathered
+    FinalAnswer,
+    /// Return a summary of the tool results collected so far
+    Summary,
+}
+
+/// Prompt used by `ExhaustionStrategy::FinalAnswer`
+const FINAL_ANSWER_PROMPT: &str = "You have reached the limit on tool calls. \
+Do not call any more tools. Using only the information gathered so far, \
+give your final answer now.";
+
+/// Maximum characters of each tool result included in a partial summary
+const SUMMARY_RESULT_CHARS: usize = 500;
+
 impl LLMAgent {
     pub fn new(
         name: String,
@@ -45,40 +84,85 @@ impl LLMAgent {
             instruction,
             model,
             tools,
+            max_turns: DEFAULT_MAX_TURNS,
+            on_max_turns: ExhaustionStrategy::default(),
+            max_parallel_tools: DEFAULT_MAX_PARALLEL_TOOLS,
+            generation_config: None,
             tool_map,
         }
     }
 
+    /// Set the maximum number of model turns per run
+    pub fn with_max_turns(mut self, max_turns: u32) -> Self {
+        self.max_turns = max_turns.max(1);
+        self
+    }
+
+    /// Set the behaviour when `max_turns` is reached
+    pub fn with_exhaustion_strategy(mut self, strategy: ExhaustionStrategy) -> Self {
+        self.on_max_turns = strategy;
+        self
+    }
+
+    /// Limit how many tool calls from a single turn run concurrently (1 = sequential)
+    pub fn with_max_parallel_tools(mut self, limit: usize) -> Self {
+        self.max_parallel_tools = limit.max(1);
+        self
+    }
+
+    /// Send these sampling settings (temperature etc.) with every model request
+    pub fn with_generation_config(mut self, config: GenerationConfig) -> Self {
+        self.generation_config = Some(config);
+        self
+    }
+
     /// O(1) tool lookup by name
     fn get_tool(&self, name: &str) -> Option<&Arc<dyn Tool>> {
         self.tool_map.get(name).map(|&i| &self.tools[i])
     }
-}
 
-#[async_trait]
-impl Agent for LLMAgent {
-    fn name(&self) -> &str {
-        &self.name
+    /// Build the system message for this agent
+    fn system_content(&self) -> Content {
+        Content {
+            role: "system".to_string(),
+            parts: vec![Part::Text(self.instruction.clone())],
+        }
     }
 
-    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
-        let mut history = vec![
-            Content {
-                role: "system".to_string(),
-                parts: vec![Part::Text(self.instruction.clone())],
-            },
+    /// Build the initial history for a single run: system prompt plus user message
+    fn initial_history(&self, input: AgentMessage) -> Vec<Content> {
+        let mut parts = Vec::with_capacity(1 + input.attachments.len());
+        if !input.text.is_empty() || input.attachments.is_empty() {
+            parts.push(Part::Text(input.text));
+        }
+        parts.extend(input.attachments);
+
+        vec![
+            self.system_content(),
             Content {
                 role: "user".to_string(),
-                parts: vec![Part::Text(input)],
+                parts,
             },
-        ];
+        ]
+    }
 
-        let max_turns = 10;
+    /// Run the tool-calling loop over `history` until the model answers in text.
+    ///
+    /// Model responses and tool results are appended to `history`, including the
+    /// final answer, so callers can persist the full exchange. Events are only
+    /// emitted when `tx` is provided.
+    async fn execute(
+        &self,
+        history: &mut Vec<Content>,
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let start = history.len();
+        let max_turns = self.max_turns;
         for turn in 0..max_turns {
             log::info!("Agent {} turn {}/{}", self.name, turn + 1, max_turns);
             let response = self
                 .model
-                .generate_content(&history, None, Some(&self.tools))
+                .generate_content(history, self.generation_config.as_ref(), Some(&self.tools))
                 .await?;
 
             log::info!(
@@ -87,195 +171,598 @@ impl Agent for LLMAgent {
                 response.parts.len()
             );
 
-            // Check for text response BEFORE adding to history
-            // This avoids cloning when we can return early
+            // Analyze response parts
+            let mut text_content = String::new();
+            let mut function_calls = Vec::new();
+
             for part in &response.parts {
-                if let Part::Text(text) = part {
-                    if !text.is_empty() {
-                        log::info!(
-                            "Agent {} returning text response (length: {}, preview: '{}')",
-                            self.name,
-                            text.len(),
-                            if text.len() > 100 { &text[..100] } else { text }
-                        );
-                        return Ok(text.clone());
+                match part {
+                    Part::Text(text) => text_content.push_str(text),
+                    Part::FunctionCall { id, name, args, .. } => {
+                        function_calls.push((id.clone(), name.clone(), args.clone()))
                     }
+                    _ => {}
                 }
             }
 
-            // Collect function calls using references
-            let function_calls: Vec<(&str, &serde_json::Value)> = response
-                .parts
-                .iter()
-                .filter_map(|part| {
-                    if let Part::FunctionCall { name, args, .. } = part {
-                        Some((name.as_str(), args))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
             if function_calls.is_empty() {
-                log::warn!(
-                    "Agent {} received empty response with no function calls",
-                    self.name
-                );
-                return Ok(String::new());
+                if text_content.is_empty() {
+                    log::warn!(
+                        "Agent {} received empty response with no function calls",
+                        self.name
+                    );
+                } else {
+                    log::info!(
+                        "Agent {} returning text response (length: {}, preview: '{}')",
+                        self.name,
+                        text_content.len(),
+                        text_content.chars().take(100).collect::<String>()
+                    );
+                    emit(tx, AgentEvent::Answer(text_content.clone())).await;
+                }
+                history.push(response);
+                return Ok(text_content);
             }
 
-            // Execute function calls and build responses
-            let mut function_responses = Vec::with_capacity(function_calls.len());
-            for (name, args) in function_calls {
-                log::info!("Tool call: {} {:?}", name, args);
-
-                // Use O(1) HashMap lookup
-                let tool_response = if let Some(t) = self.get_tool(name) {
-                    match t.execute(args.clone()).await {
-                        Ok(res) => res,
-                        Err(e) => {
-                            log::error!("Tool {} failed: {}", name, e);
-                            serde_json::json!({ "error": e.to_string() })
-                        }
-                    }
-                } else {
-                    log::error!("Tool {} not found", name);
-                    serde_json::json!({ "error": format!("Tool {} not found", name) })
-                };
-
-                log::info!(
-                    "Tool {} response: {}",
-                    name,
-                    serde_json::to_string(&tool_response).unwrap_or_default()
-                );
-
-                function_responses.push(Part::FunctionResponse {
-                    name: name.to_string(),
-                    response: tool_response,
-                });
+            // Has function calls, treat text as Thought
+            if !text_content.is_empty() {
+                emit(tx, AgentEvent::Thought(text_content)).await;
             }
 
-            // Add model response to history
+            // Execute function calls concurrently, keeping responses in call order
+            let results: Vec<(Part, Vec<Part>)> = stream::iter(function_calls)
+                .map(|(id, name, args)| self.call_tool(id, name, args, tx))
+                .buffered(self.max_parallel_tools.max(1))
+                .collect()
+                .await;
+            // Media returned by tools follows all the function responses
+            let (mut parts, media): (Vec<Part>, Vec<Vec<Part>>) = results.into_iter().unzip();
+            parts.extend(media.into_iter().flatten());
+
+            // Add model response and tool responses to history
             history.push(response);
-
-            // Add tool responses to history
             history.push(Content {
                 role: "user".to_string(),
-                parts: function_responses,
+                parts,
             });
 
             log::info!("Continuing to next turn to get model summary...");
         }
 
-        log::error!(
-            "Agent {} reached max turns without text response",
-            self.name
+        log::warn!(
+            "Agent {} reached {} turns without a text response, applying {:?}",
+            self.name,
+            max_turns,
+            self.on_max_turns
         );
-        Err("Max turns reached".into())
+        let answer = match self.on_max_turns {
+            ExhaustionStrategy::Error => {
+                return Err(KineticError::MaxIterations {
+                    kind: "turns".to_string(),
+                    limit: max_turns,
+                }
+                .into());
+            }
+            ExhaustionStrategy::FinalAnswer => match self.final_answer(history).await? {
+                Some(answer) => answer,
+                None => summarize_tool_results(&history[start..], max_turns),
+            },
+            ExhaustionStrategy::Summary => summarize_tool_results(&history[start..], max_turns),
+        };
+
+        history.push(Content {
+            role: "model".to_string(),
+            parts: vec![Part::Text(answer.clone())],
+        });
+        emit(tx, AgentEvent::Answer(answer.clone())).await;
+        Ok(answer)
+    }
+
+    /// Ask the model for a final answer without further tool use
+    ///
+    /// Tools are still passed so providers accept the tool blocks in the
+    /// history; any calls in the reply are ignored. Returns `None` if the
+    /// model gives no text.
+    async fn final_answer(
+        &self,
+        history: &mut Vec<Content>,
+    ) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        history.push(Content {
+            role: "user".to_string(),
+            parts: vec![Part::Text(FINAL_ANSWER_PROMPT.to_string())],
+        });
+        let response = self
+            .model
+            .generate_content(history, self.generation_config.as_ref(), Some(&self.tools))
+            .await?;
+
+        let text: String = response
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        Ok((!text.is_empty()).then_some(text))
     }
+
+    /// Execute a single tool call and wrap the result as a `FunctionResponse`
+    ///
+    /// Failures are reported to the model as `{"error": ...}` so it can recover.
+    /// Media attached to the result with `tool::with_media` is returned
+    /// separately, to be sent as parts of the same message.
+    async fn call_tool(
+        &self,
+        id: Option<String>,
+        name: String,
+        args: serde_json::Value,
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> (Part, Vec<Part>) {
+        log::info!("Tool call: {} {:?}", name, args);
+        emit(
+            tx,
+            AgentEvent::ToolCall {
+                name: name.clone(),
+                args: args.clone(),
+            },
+        )
+        .await;
+
+        // Use O(1) HashMap lookup
+        let (tool_response, media) = if let Some(t) = self.get_tool(&name) {
+            let call = tool::with_events(tx.cloned(), tool::call(t.as_ref(), args));
+            match tool::with_model(self.model.clone(), call).await {
+                Ok(res) => tool::split_media(res),
+                Err(e) => {
+                    log::error!("{}", e);
+                    emit(tx, AgentEvent::Error(e.to_string())).await;
+                    (e.to_value(t.schema()), Vec::new())
+                }
+            }
+        } else {
+            log::error!("Tool {} not found", name);
+            emit(tx, AgentEvent::Error(format!("Tool {} not found", name))).await;
+            (
+                serde_json::json!({ "error": format!("Tool {} not found", name) }),
+                Vec::new(),
+            )
+        };
+
+        log::info!(
+            "Tool {} response: {}",
+            name,
+            serde_json::to_string(&tool_response).unwrap_or_default()
+        );
+        emit(
+            tx,
+            AgentEvent::ToolResult {
+                name: name.clone(),
+                result: tool_response.clone(),
+            },
+        )
+        .await;
+
+        let response = Part::FunctionResponse {
+            id,
+            name,
+            response: tool_response,
+        };
+        (response, media)
+    }
+}
+
+/// Describe the tool results in `history` for `ExhaustionStrategy::Summary`
+fn summarize_tool_results(history: &[Content], max_turns: u32) -> String {
+    let mut summary = format!(
+        "Reached the limit of {} turns before a final answer. Tool results so far:",
+        max_turns
+    );
+    let results = history
+        .iter()
+        .flat_map(|c| &c.parts)
+        .filter_map(|p| match p {
+            Part::FunctionResponse { name, response, .. } => Some((name, response)),
+            _ => None,
+        });
+    for (name, response) in results {
+        let text = serde_json::to_string(response).unwrap_or_default();
+        let mut preview: String = text.chars().take(SUMMARY_RESULT_CHARS).collect();
+        if preview.len() < text.len() {
+            preview.push_str("...");
+        }
+        summary.push_str(&format!("\n- {}: {}", name, preview));
+    }
+    summary
+}
+
+#[async_trait]
+impl Agent for LLMAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let mut history = self.initial_history(AgentMessage::text(input));
+        self.execute(&mut history, None).await
+    }
+
     async fn run_stream(
         &self,
         input: String,
-        tx: tokio::sync::mpsc::Sender<AgentEvent>,
+        tx: mpsc::Sender<AgentEvent>,
     ) -> Result<String, Box<dyn Error + Send + Sync>> {
-        let mut history = vec![
-            Content {
-                role: "system".to_string(),
-                parts: vec![Part::Text(self.instruction.clone())],
-            },
-            Content {
-                role: "user".to_string(),
-                parts: vec![Part::Text(input)],
-            },
-        ];
+        let mut history = self.initial_history(AgentMessage::text(input));
+        self.execute(&mut history, Some(&tx)).await
+    }
 
-        let max_turns = 10;
-        for turn in 0..max_turns {
-            log::info!("Agent {} turn {}/{}", self.name, turn + 1, max_turns);
-            let response = self
-                .model
-                .generate_content(&history, None, Some(&self.tools))
-                .await?;
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let mut history = self.initial_history(input);
+        let output = self.execute(&mut history, None).await?;
+        Ok(AgentMessage::from_output(output))
+    }
 
-            // Analyze response parts
-            let mut text_content = String::new();
-            let mut function_calls = Vec::new();
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let mut history = self.initial_history(input);
+        let output = self.execute(&mut history, Some(&tx)).await?;
+        Ok(AgentMessage::from_output(output))
+    }
 
-            for part in &response.parts {
-                match part {
-                    Part::Text(text) => text_content.push_str(text),
-                    Part::FunctionCall { name, args, .. } => {
-                        function_calls.push((name.as_str(), args))
-                    }
-                    _ => {}
-                }
-            }
+    async fn run_with_history(
+        &self,
+        history: &mut Vec<Content>,
+        input: String,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        // The system prompt is not stored in sessions, so it always reflects
+        // the current agent definition.
+        let mut full_history = Vec::with_capacity(history.len() + 2);
+        full_history.push(self.system_content());
+        full_history.extend(history.iter().cloned());
+        full_history.push(Content {
+            role: "user".to_string(),
+            parts: vec![Part::Text(input)],
+        });
+
+        let result = self.execute(&mut full_history, None).await;
+        if result.is_ok() {
+            full_history.remove(0);
+            *history = full_history;
+        }
+        result
+    }
+}
 
-            if function_calls.is_empty() {
-                // No function calls, treat text as final answer
-                if !text_content.is_empty() {
-                    let _ = tx.send(AgentEvent::Answer(text_content.clone())).await;
-                    return Ok(text_content);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::model::GenerationConfig;
+    use std::sync::Mutex;
+
+    /// Mock model that records the history it was called with
+    struct RecordingModel {
+        responses: Mutex<Vec<Content>>,
+        seen: Mutex<Vec<Vec<Content>>>,
+    }
+
+    #[async_trait]
+    impl Model for RecordingModel {
+        async fn generate_content(
+            &self,
+            history: &[Content],
+            _config: Option<&GenerationConfig>,
+            _tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            self.seen.lock().unwrap().push(history.to_vec());
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    fn text(role: &str, text: &str) -> Content {
+        Content {
+            role: role.to_string(),
+            parts: vec![Part::Text(text.to_string())],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_with_history_continues_conversation() {
+        let model = Arc::new(RecordingModel {
+            responses: Mutex::new(vec![text("model", "first"), text("model", "second")]),
+            seen: Mutex::new(vec![]),
+        });
+        let agent = LLMAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "Be helpful".to_string(),
+            model.clone(),
+            vec![],
+        );
+
+        let mut history = Vec::new();
+        let first = agent
+            .run_with_history(&mut history, "hello".to_string())
+            .await
+            .unwrap();
+        assert_eq!(first, "first");
+        assert_eq!(history.len(), 2);
+
+        let second = agent
+            .run_with_history(&mut history, "and again".to_string())
+            .await
+            .unwrap();
+        assert_eq!(second, "second");
+        assert_eq!(history.len(), 4);
+
+        // Second call sees the system prompt, the earlier turn, and the new input
+        let seen = model.seen.lock().unwrap();
+        let last = &seen[1];
+        assert_eq!(last.len(), 4);
+        assert_eq!(last[0].role, "system");
+        assert!(matches!(&last[2].parts[0], Part::Text(t) if t == "first"));
+        assert!(matches!(&last[3].parts[0], Part::Text(t) if t == "and again"));
+    }
+
+    #[tokio::test]
+    async fn test_run_message_sends_attachments_and_parses_json() {
+        let model = Arc::new(RecordingModel {
+            responses: Mutex::new(vec![text("model", r#"{"severity": "high"}"#)]),
+            seen: Mutex::new(vec![]),
+        });
+        let agent = LLMAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "Classify".to_string(),
+            model.clone(),
+            vec![],
+        );
+
+        let input = AgentMessage::text("Classify this")
+            .with_attachments(vec![Part::Text("attached log".to_string())]);
+        let output = agent.run_message(input).await.unwrap();
+        assert_eq!(output.data, Some(serde_json::json!({"severity": "high"})));
+
+        let seen = model.seen.lock().unwrap();
+        assert_eq!(seen[0][1].parts.len(), 2);
+    }
+
+    /// Tool that tracks how many calls are in flight at once
+    struct SlowTool {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_seen: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps briefly and echoes its input"
+        }
+
+        fn schema(&self) -> &serde_json::Value {
+            static SCHEMA: once_cell::sync::Lazy<serde_json::Value> =
+                once_cell::sync::Lazy::new(|| serde_json::json!({"type": "object"}));
+            &SCHEMA
+        }
+
+        async fn execute(
+            &self,
+            input: serde_json::Value,
+        ) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_seen.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(input)
+        }
+    }
+
+    fn slow_calls(n: usize) -> Content {
+        Content {
+            role: "model".to_string(),
+            parts: (0..n)
+                .map(|i| Part::FunctionCall {
+                    id: Some(format!("call_{}", i)),
+                    name: "slow".to_string(),
+                    args: serde_json::json!({ "i": i }),
+                    thought_signature: None,
+                })
+                .collect(),
+        }
+    }
+
+    async fn run_slow_calls(limit: usize) -> (usize, Vec<Content>) {
+        let tool = Arc::new(SlowTool {
+            in_flight: Default::default(),
+            max_seen: Default::default(),
+        });
+        let model = Arc::new(RecordingModel {
+            responses: Mutex::new(vec![slow_calls(3), text("model", "done")]),
+            seen: Mutex::new(vec![]),
+        });
+        let agent = LLMAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "Use tools".to_string(),
+            model.clone(),
+            vec![tool.clone()],
+        )
+        .with_max_parallel_tools(limit);
+
+        assert_eq!(agent.run("go".to_string()).await.unwrap(), "done");
+        let max_seen = tool.max_seen.load(std::sync::atomic::Ordering::SeqCst);
+        let seen = model.seen.lock().unwrap().clone();
+        (max_seen, seen[1].clone())
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_run_concurrently_in_call_order() {
+        let (max_seen, history) = run_slow_calls(4).await;
+        assert_eq!(max_seen, 3);
+
+        // Responses follow call order and echo the provider call ids
+        let responses = &history.last().unwrap().parts;
+        for (i, part) in responses.iter().enumerate() {
+            match part {
+                Part::FunctionResponse { id, response, .. } => {
+                    assert_eq!(id.as_deref(), Some(format!("call_{}", i).as_str()));
+                    assert_eq!(response["i"], i);
                 }
-                // If both empty, it's weird, but we continue or return empty?
-                // Usually retry or return empty string? Original code returned empty string.
-                return Ok(String::new());
+                _ => panic!("Expected FunctionResponse part"),
             }
+        }
+    }
 
-            // Has function calls, treat text as Thought
-            if !text_content.is_empty() {
-                let _ = tx.send(AgentEvent::Thought(text_content.clone())).await;
-            }
+    #[tokio::test]
+    async fn test_max_parallel_tools_limits_concurrency() {
+        let (max_seen, _) = run_slow_calls(1).await;
+        assert_eq!(max_seen, 1);
+    }
 
-            // Execute function calls
-            let mut function_responses = Vec::with_capacity(function_calls.len());
-            for (name, args) in function_calls {
-                // Emit ToolCall event
-                let _ = tx
-                    .send(AgentEvent::ToolCall {
-                        name: name.to_string(),
-                        args: args.clone(),
-                    })
-                    .await;
-
-                let tool_response = if let Some(t) = self.get_tool(name) {
-                    match t.execute(args.clone()).await {
-                        Ok(res) => res,
-                        Err(e) => {
-                            let _ = tx
-                                .send(AgentEvent::Error(format!("Tool {} failed: {}", name, e)))
-                                .await;
-                            serde_json::json!({ "error": e.to_string() })
-                        }
-                    }
-                } else {
-                    let _ = tx
-                        .send(AgentEvent::Error(format!("Tool {} not found", name)))
-                        .await;
-                    serde_json::json!({ "error": format!("Tool {} not found", name) })
-                };
-
-                // Emit ToolResult event
-                let _ = tx
-                    .send(AgentEvent::ToolResult {
-                        name: name.to_string(),
-                        result: tool_response.clone(),
-                    })
-                    .await;
+    /// Tool with a strict schema that must never run with invalid arguments
+    struct StrictTool;
+
+    #[async_trait]
+    impl Tool for StrictTool {
+        fn name(&self) -> &str {
+            "strict"
+        }
+
+        fn description(&self) -> &str {
+            "Needs an integer pr_number"
+        }
 
-                function_responses.push(Part::FunctionResponse {
-                    name: name.to_string(),
-                    response: tool_response,
+        fn schema(&self) -> &serde_json::Value {
+            static SCHEMA: once_cell::sync::Lazy<serde_json::Value> =
+                once_cell::sync::Lazy::new(|| {
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {"pr_number": {"type": "integer"}},
+                        "required": ["pr_number"]
+                    })
                 });
-            }
+            &SCHEMA
+        }
 
-            history.push(response);
-            history.push(Content {
-                role: "user".to_string(),
-                parts: function_responses,
-            });
+        async fn execute(
+            &self,
+            _input: serde_json::Value,
+        ) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
+            panic!("executed with invalid arguments");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tool_args_return_validation_errors() {
+        let bad_call = Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionCall {
+                id: Some("call_0".to_string()),
+                name: "strict".to_string(),
+                args: serde_json::json!({"pr_number": "42"}),
+                thought_signature: None,
+            }],
+        };
+        let model = Arc::new(RecordingModel {
+            responses: Mutex::new(vec![bad_call, text("model", "retrying")]),
+            seen: Mutex::new(vec![]),
+        });
+        let agent = LLMAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "Use tools".to_string(),
+            model.clone(),
+            vec![Arc::new(StrictTool)],
+        );
+
+        assert_eq!(agent.run("go".to_string()).await.unwrap(), "retrying");
+        let seen = model.seen.lock().unwrap();
+        match &seen[1].last().unwrap().parts[0] {
+            Part::FunctionResponse { response, .. } => {
+                assert_eq!(response["validation_errors"][0]["path"], "/pr_number");
+                assert!(response["expected_schema"].is_object());
+            }
+            other => panic!("Expected FunctionResponse, got {:?}", other),
         }
+    }
+
+    async fn exhaust_turns(
+        strategy: ExhaustionStrategy,
+        final_reply: Content,
+    ) -> (
+        Result<String, Box<dyn Error + Send + Sync>>,
+        Arc<RecordingModel>,
+    ) {
+        let tool = Arc::new(SlowTool {
+            in_flight: Default::default(),
+            max_seen: Default::default(),
+        });
+        let model = Arc::new(RecordingModel {
+            responses: Mutex::new(vec![slow_calls(1), slow_calls(1), final_reply]),
+            seen: Mutex::new(vec![]),
+        });
+        let agent = LLMAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "Use tools".to_string(),
+            model.clone(),
+            vec![tool],
+        )
+        .with_max_turns(2)
+        .with_exhaustion_strategy(strategy);
+
+        (agent.run("go".to_string()).await, model)
+    }
+
+    #[tokio::test]
+    async fn test_max_turns_error_is_typed() {
+        let (result, _) = exhaust_turns(ExhaustionStrategy::Error, text("model", "")).await;
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KineticError>(),
+            Some(KineticError::MaxIterations { limit: 2, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_max_turns_final_answer() {
+        let (result, model) = exhaust_turns(
+            ExhaustionStrategy::FinalAnswer,
+            text("model", "best effort"),
+        )
+        .await;
+        assert_eq!(result.unwrap(), "best effort");
+
+        let seen = model.seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        let last = seen[2].last().unwrap();
+        assert!(matches!(&last.parts[0], Part::Text(t) if t == FINAL_ANSWER_PROMPT));
+    }
+
+    #[tokio::test]
+    async fn test_max_turns_final_answer_falls_back_to_summary() {
+        // The model keeps calling tools instead of answering
+        let (result, _) = exhaust_turns(ExhaustionStrategy::FinalAnswer, slow_calls(1)).await;
+        assert!(result.unwrap().contains("Tool results so far"));
+    }
 
-        Err("Max turns reached".into())
+    #[tokio::test]
+    async fn test_max_turns_summary() {
+        let (result, model) = exhaust_turns(ExhaustionStrategy::Summary, text("model", "")).await;
+        let summary = result.unwrap();
+        assert!(summary.starts_with("Reached the limit of 2 turns"));
+        assert_eq!(summary.matches("- slow: {\"i\":0}").count(), 2);
+        // No extra model call is made
+        assert_eq!(model.seen.lock().unwrap().len(), 2);
     }
 }
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+
+//! Structured agent input/output
+//!
+//! `AgentMessage` carries text together with an optional JSON value,
+//! attached content parts and free-form metadata, so structured data can
+//! flow between agents without being stringified and re-parsed.
+
+use crate::adk::model::Part;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Input to or output from an agent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentMessage {
+    /// Textual form of the message, used as the prompt for LLM agents
+    #[serde(default)]
+    pub text: String,
+    /// Structured value, when the message carries JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Additional content parts sent alongside the text
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Part>,
+    /// Free-form metadata (not shown to models)
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub metadata: Map<String, Value>,
+}
+
+impl AgentMessage {
+    /// Create a plain text message
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Create a message carrying a JSON value
+    ///
+    /// Strings become plain text; other values keep their structure in `data`
+    /// and are pretty-printed into `text`.
+    pub fn json(value: Value) -> Self {
+        match value {
+            Value::String(s) => Self::text(s),
+            other => Self {
+                text: serde_json::to_string_pretty(&other).unwrap_or_default(),
+                data: Some(other),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Create a message from raw agent output, keeping JSON output structured
+    pub fn from_output(output: String) -> Self {
+        match serde_json::from_str::<Value>(&output) {
+            Ok(value) if value.is_object() || value.is_array() => Self {
+                text: output,
+                data: Some(value),
+                ..Default::default()
+            },
+            _ => Self::text(output),
+        }
+    }
+
+    /// Attach content parts to the message
+    pub fn with_attachments(mut self, attachments: Vec<Part>) -> Self {
+        self.attachments.extend(attachments);
+        self
+    }
+
+    /// Add a metadata entry
+    pub fn with_metadata(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// The message as a JSON value: `data` if present, otherwise the text
+    pub fn to_value(&self) -> Value {
+        self.data
+            .clone()
+            .unwrap_or_else(|| Value::String(self.text.clone()))
+    }
+}
+
+impl From<String> for AgentMessage {
+    fn from(text: String) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<&str> for AgentMessage {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<Value> for AgentMessage {
+    fn from(value: Value) -> Self {
+        Self::json(value)
+    }
+}
+
+impl From<AgentMessage> for String {
+    fn from(message: AgentMessage) -> Self {
+        message.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_text_message() {
+        let msg = AgentMessage::from("hello");
+        assert_eq!(msg.text, "hello");
+        assert!(msg.data.is_none());
+        assert_eq!(msg.to_value(), json!("hello"));
+    }
+
+    #[test]
+    fn test_json_message_keeps_structure() {
+        let msg = AgentMessage::json(json!({"pr": 42}));
+        assert_eq!(msg.data, Some(json!({"pr": 42})));
+        assert!(msg.text.contains("\"pr\": 42"));
+
+        // JSON strings are just text
+        let msg = AgentMessage::json(json!("plain"));
+        assert_eq!(msg.text, "plain");
+        assert!(msg.data.is_none());
+    }
+
+    #[test]
+    fn test_from_output_parses_json_objects_only() {
+        let msg = AgentMessage::from_output(r#"{"intent": "bug"}"#.to_string());
+        assert_eq!(msg.data, Some(json!({"intent": "bug"})));
+        assert_eq!(msg.text, r#"{"intent": "bug"}"#);
+
+        let msg = AgentMessage::from_output("42".to_string());
+        assert!(msg.data.is_none());
+
+        let msg = AgentMessage::from_output("not json".to_string());
+        assert_eq!(msg.text, "not json");
+        assert!(msg.data.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_from_partial_json() {
+        let msg: AgentMessage =
+            serde_json::from_value(json!({"text": "hi", "metadata": {"k": 1}})).unwrap();
+        assert_eq!(msg.text, "hi");
+        assert_eq!(msg.metadata["k"], json!(1));
+    }
+}
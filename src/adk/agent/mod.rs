@@ -5,13 +5,37 @@
 //! This module provides the core Agent trait and implementations:
 //! - `LLMAgent` - Standard LLM agent with tool calling
 //! - `ReActAgent` - Reasoning + Acting pattern agent
+//! - `PlanExecuteAgent` - Plans steps up front and re-plans after each one
+//! - `CoTAgent` - Chain-of-thought reasoning with optional self-consistency
+//! - `ReflectAgent` - Wraps another agent with a critic/revision loop
+//! - `SupervisorAgent` - Delegates to sub-agents exposed as tools, with handoffs
+//!
+//! `AgentTool` exposes any agent as a tool so agents can call each other.
+//!
+//! Agents exchange plain strings via `run`, or structured `AgentMessage`s
+//! via `run_message`.
 
+mod agent_tool;
+mod cot;
 mod llm;
+mod message;
+mod plan_execute;
 mod react;
+mod react_parser;
+mod reflect;
+mod supervisor;
 
-pub use llm::LLMAgent;
-pub use react::ReActAgent;
+pub(crate) use agent_tool::{default_input_schema, input_message, tool_name};
+pub use agent_tool::{AgentTool, DEFAULT_MAX_DEPTH};
+pub use cot::{CoTAgent, SelfConsistency, VotingStrategy, DEFAULT_SAMPLING_TEMPERATURE};
+pub use llm::{ExhaustionStrategy, LLMAgent};
+pub use message::AgentMessage;
+pub use plan_execute::PlanExecuteAgent;
+pub use react::{ReActAgent, ReActMode};
+pub use reflect::{ReflectAgent, Reflection};
+pub use supervisor::{SubAgent, SupervisorAgent, DEFAULT_MAX_HANDOFFS};
 
+use crate::adk::model::{Content, Part};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -28,6 +52,31 @@ pub enum AgentEvent {
         name: String,
         result: serde_json::Value,
     },
+    /// A plan of steps was created or revised
+    Plan(Vec<String>),
+    /// A plan step finished
+    StepResult {
+        step: String,
+        result: String,
+    },
+    /// A sampled chain-of-thought reasoning path
+    ReasoningPath {
+        index: usize,
+        reasoning: String,
+        answer: String,
+    },
+    /// Votes over sampled answers, as `(answer, votes)` pairs
+    Vote {
+        method: String,
+        answer: String,
+        tally: Vec<(String, usize)>,
+    },
+    /// A critic verdict on a draft
+    Critique {
+        round: u32,
+        approved: bool,
+        critique: String,
+    },
     Answer(String),
     Error(String),
     Log(String),
@@ -60,6 +109,121 @@ pub trait Agent: Send + Sync {
             }
         }
     }
+
+    /// Run the agent with a structured message
+    ///
+    /// The default implementation runs on the message text and parses JSON
+    /// output back into `data`.
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self.run(input.text).await?;
+        Ok(AgentMessage::from_output(output))
+    }
+
+    /// Run the agent with a structured message and streaming events
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self.run_stream(input.text, tx).await?;
+        Ok(AgentMessage::from_output(output))
+    }
+
+    /// Continue a conversation, appending this exchange to `history`
+    ///
+    /// `history` holds the earlier user/model turns of a session (without a
+    /// system prompt). The default implementation folds the transcript into
+    /// the input text for agents that don't keep native message history.
+    async fn run_with_history(
+        &self,
+        history: &mut Vec<Content>,
+        input: String,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let prompt = render_transcript(history, &input);
+        let output = self.run(prompt).await?;
+
+        history.push(Content {
+            role: "user".to_string(),
+            parts: vec![Part::Text(input)],
+        });
+        history.push(Content {
+            role: "model".to_string(),
+            parts: vec![Part::Text(output.clone())],
+        });
+        Ok(output)
+    }
+}
+
+/// Send an event if a channel is attached
+pub(crate) async fn emit(tx: Option<&mpsc::Sender<AgentEvent>>, event: AgentEvent) {
+    if let Some(tx) = tx {
+        let _ = tx.send(event).await;
+    }
+}
+
+/// Run `agent` as part of a larger agent, forwarding its events to `tx`
+///
+/// The inner agent's `Answer` is not forwarded, since it is an intermediate
+/// result for the outer agent rather than the final answer.
+pub(crate) async fn run_inner_message(
+    agent: &dyn Agent,
+    input: AgentMessage,
+    tx: Option<&mpsc::Sender<AgentEvent>>,
+) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+    let Some(tx) = tx else {
+        return agent.run_message(input).await;
+    };
+
+    let (inner_tx, mut inner_rx) = mpsc::channel(32);
+    let forward = async {
+        while let Some(event) = inner_rx.recv().await {
+            if !matches!(event, AgentEvent::Answer(_)) {
+                let _ = tx.send(event).await;
+            }
+        }
+    };
+    let (result, _) = tokio::join!(agent.run_message_stream(input, inner_tx), forward);
+    result
+}
+
+/// Render prior conversation turns and the new input as a single prompt
+fn render_transcript(history: &[Content], input: &str) -> String {
+    let turns: Vec<String> = history
+        .iter()
+        .filter_map(|content| {
+            let text: String = content
+                .parts
+                .iter()
+                .filter_map(|p| match p {
+                    Part::Text(t) => Some(t.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if text.is_empty() {
+                return None;
+            }
+            let speaker = if content.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            Some(format!("{}: {}", speaker, text))
+        })
+        .collect();
+
+    if turns.is_empty() {
+        input.to_string()
+    } else {
+        format!(
+            "Previous conversation:\n{}\n\nCurrent message:\n{}",
+            turns.join("\n\n"),
+            input
+        )
+    }
 }
 
 #[cfg(test)]
@@ -100,4 +264,26 @@ mod tests {
         let result = agent.run("input".to_string()).await.unwrap();
         assert_eq!(result, "input-transformed");
     }
+
+    #[tokio::test]
+    async fn test_default_run_with_history_includes_transcript() {
+        let agent = MockAgent::new("echo", |s| s);
+        let mut history = Vec::new();
+
+        let first = agent
+            .run_with_history(&mut history, "hello".to_string())
+            .await
+            .unwrap();
+        assert_eq!(first, "hello");
+        assert_eq!(history.len(), 2);
+
+        let second = agent
+            .run_with_history(&mut history, "again".to_string())
+            .await
+            .unwrap();
+        assert!(second.contains("User: hello"));
+        assert!(second.contains("Assistant: hello"));
+        assert!(second.ends_with("again"));
+        assert_eq!(history.len(), 4);
+    }
 }
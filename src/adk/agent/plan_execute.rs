@@ -0,0 +1,556 @@
+// SPDX-License-Identifier: MIT
+
+//! Plan-and-Execute Agent
+//!
+//! A planner call breaks the task into a list of steps. Each step is carried
+//! out by a tool-calling `LLMAgent`, and after every step a re-planner revises
+//! the remaining plan (or finishes early) based on what was observed.
+
+use super::react_parser::parse_args;
+use super::{emit, run_inner_message, Agent, AgentEvent, AgentMessage, LLMAgent};
+use crate::adk::model::{Content, Model, Part};
+use crate::adk::tool::Tool;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Plan-and-Execute agent
+pub struct PlanExecuteAgent {
+    pub name: String,
+    pub description: String,
+    pub instruction: String,
+    pub model: Arc<dyn Model>,
+    pub tools: Vec<Arc<dyn Tool>>,
+    /// Maximum number of steps executed before answering with what is known
+    pub max_steps: u32,
+    /// Agent that carries out individual steps
+    executor: LLMAgent,
+}
+
+/// Re-planner decision after a step
+#[derive(Debug, PartialEq)]
+enum Replan {
+    /// Continue with these remaining steps (empty means the plan is done)
+    Steps(Vec<String>),
+    /// The objective is met
+    FinalAnswer(String),
+}
+
+impl PlanExecuteAgent {
+    pub fn new(
+        name: String,
+        description: String,
+        instruction: String,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+        max_steps: u32,
+    ) -> Self {
+        let executor = LLMAgent::new(
+            Self::executor_name(&name),
+            description.clone(),
+            Self::step_instruction(&instruction),
+            model.clone(),
+            tools.clone(),
+        );
+
+        Self {
+            name,
+            description,
+            instruction,
+            model,
+            tools,
+            max_steps,
+            executor,
+        }
+    }
+
+    /// Use a custom agent (e.g. with different turn limits) to execute steps
+    pub fn with_executor(mut self, executor: LLMAgent) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// Name of the step executor for an agent called `name`
+    pub fn executor_name(name: &str) -> String {
+        format!("{}-executor", name)
+    }
+
+    /// Instructions for the step executor, derived from the agent's own
+    pub fn step_instruction(instruction: &str) -> String {
+        format!(
+            "{}\n\nYou are carrying out one step of a larger plan. Complete only the \
+             current step using the available tools, then report what you found.",
+            instruction
+        )
+    }
+
+    fn tool_section(&self) -> String {
+        if self.tools.is_empty() {
+            "No tools are available.".to_string()
+        } else {
+            let tools: Vec<String> = self
+                .tools
+                .iter()
+                .map(|t| format!("- {}: {}", t.name(), t.description()))
+                .collect();
+            format!("Tools available to each step:\n{}", tools.join("\n"))
+        }
+    }
+
+    fn planner_prompt(&self) -> String {
+        format!(
+            r#"{}
+
+You are the planner. Break the user's task into a short list of concrete steps
+that can each be completed with the tools below. Use at most {} steps.
+
+{}
+
+Respond ONLY with JSON of the form: {{"steps": ["first step", "second step"]}}"#,
+            self.instruction,
+            self.max_steps,
+            self.tool_section()
+        )
+    }
+
+    fn replanner_prompt(&self) -> String {
+        format!(
+            r#"{}
+
+You are the re-planner. Given the objective, the steps completed so far with
+their results, and the remaining plan, decide what to do next.
+
+{}
+
+Respond ONLY with JSON, either:
+- {{"steps": ["next step", ...]}} with the revised remaining steps, or
+- {{"final_answer": "..."}} if the objective is already met"#,
+            self.instruction,
+            self.tool_section()
+        )
+    }
+
+    /// Call the model without tools and return its text
+    async fn ask(
+        &self,
+        system: String,
+        user: String,
+        attachments: &[Part],
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let mut user_parts = vec![Part::Text(user)];
+        user_parts.extend_from_slice(attachments);
+        let history = vec![
+            Content {
+                role: "system".to_string(),
+                parts: vec![Part::Text(system)],
+            },
+            Content {
+                role: "user".to_string(),
+                parts: user_parts,
+            },
+        ];
+        let response = self.model.generate_content(&history, None, None).await?;
+        Ok(response
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Plan, run and re-plan; the planner and every step get `attachments`
+    /// with the task
+    async fn execute(
+        &self,
+        input: &str,
+        attachments: &[Part],
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let plan_text = self
+            .ask(self.planner_prompt(), input.to_string(), attachments)
+            .await?;
+        let mut plan = parse_plan(&plan_text).unwrap_or_else(|| {
+            log::warn!("Planner returned no usable plan, running the task as one step");
+            vec![input.to_string()]
+        });
+        plan.truncate(self.max_steps as usize);
+        log::info!("PlanExecuteAgent {} plan: {:?}", self.name, plan);
+        emit(tx, AgentEvent::Plan(plan.clone())).await;
+
+        let mut completed: Vec<(String, String)> = Vec::new();
+        while !plan.is_empty() {
+            if completed.len() >= self.max_steps as usize {
+                log::warn!(
+                    "PlanExecuteAgent {} reached max steps ({})",
+                    self.name,
+                    self.max_steps
+                );
+                break;
+            }
+
+            let step = plan.remove(0);
+            log::info!(
+                "PlanExecuteAgent {} step {}: {}",
+                self.name,
+                completed.len() + 1,
+                step
+            );
+            let message = AgentMessage::text(step_input(input, &completed, &step))
+                .with_attachments(attachments.to_vec());
+            let result = match run_inner_message(&self.executor, message, tx).await {
+                Ok(result) => result.text,
+                Err(e) => {
+                    emit(
+                        tx,
+                        AgentEvent::Error(format!("Step '{}' failed: {}", step, e)),
+                    )
+                    .await;
+                    format!("Step failed: {}", e)
+                }
+            };
+            emit(
+                tx,
+                AgentEvent::StepResult {
+                    step: step.clone(),
+                    result: result.clone(),
+                },
+            )
+            .await;
+            completed.push((step, result));
+
+            let replan_text = self
+                .ask(
+                    self.replanner_prompt(),
+                    replan_input(input, &completed, &plan),
+                    &[],
+                )
+                .await?;
+            match parse_replan(&replan_text) {
+                Some(Replan::FinalAnswer(answer)) => {
+                    emit(tx, AgentEvent::Answer(answer.clone())).await;
+                    return Ok(answer);
+                }
+                Some(Replan::Steps(steps)) => {
+                    if steps != plan {
+                        plan = steps;
+                        emit(tx, AgentEvent::Plan(plan.clone())).await;
+                    }
+                }
+                None => log::warn!("Re-planner returned no usable plan, keeping remaining steps"),
+            }
+        }
+
+        let answer = self
+            .ask(
+                format!(
+                    "{}\n\nWrite the final answer to the objective using the step results.",
+                    self.instruction
+                ),
+                format!("Objective: {}\n\n{}", input, format_completed(&completed)),
+                &[],
+            )
+            .await?;
+        emit(tx, AgentEvent::Answer(answer.clone())).await;
+        Ok(answer)
+    }
+}
+
+fn format_completed(completed: &[(String, String)]) -> String {
+    if completed.is_empty() {
+        return "Completed steps: none".to_string();
+    }
+    let steps: Vec<String> = completed
+        .iter()
+        .enumerate()
+        .map(|(i, (step, result))| format!("{}. {}\nResult: {}", i + 1, step, result))
+        .collect();
+    format!("Completed steps:\n{}", steps.join("\n\n"))
+}
+
+fn step_input(objective: &str, completed: &[(String, String)], step: &str) -> String {
+    format!(
+        "Objective: {}\n\n{}\n\nCurrent step: {}",
+        objective,
+        format_completed(completed),
+        step
+    )
+}
+
+fn replan_input(objective: &str, completed: &[(String, String)], remaining: &[String]) -> String {
+    let remaining = if remaining.is_empty() {
+        "none".to_string()
+    } else {
+        remaining
+            .iter()
+            .map(|s| format!("- {}", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    format!(
+        "Objective: {}\n\n{}\n\nRemaining plan:\n{}",
+        objective,
+        format_completed(completed),
+        remaining
+    )
+}
+
+/// Extract step descriptions from a JSON array of strings or step objects
+fn steps_from_value(value: &Value) -> Option<Vec<String>> {
+    let steps = value.as_array()?;
+    Some(
+        steps
+            .iter()
+            .filter_map(|s| match s {
+                Value::String(s) => Some(s.clone()),
+                Value::Object(o) => ["description", "step", "task"]
+                    .iter()
+                    .find_map(|k| o.get(*k).and_then(|v| v.as_str()))
+                    .map(|s| s.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.trim().is_empty())
+            .collect(),
+    )
+}
+
+/// Parse a planner response: `{"steps": [...]}`, a bare array, or a numbered list
+fn parse_plan(text: &str) -> Option<Vec<String>> {
+    let value = parse_args(text);
+    let steps = steps_from_value(&value["steps"])
+        .or_else(|| steps_from_value(&value))
+        .or_else(|| {
+            let numbered: Vec<String> = text
+                .lines()
+                .filter_map(|line| numbered_item(line.trim()))
+                .collect();
+            (!numbered.is_empty()).then_some(numbered)
+        })?;
+    (!steps.is_empty()).then_some(steps)
+}
+
+/// The text of a `1. step` or `1) step` list item
+fn numbered_item(line: &str) -> Option<String> {
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    if rest.len() == line.len() {
+        return None;
+    }
+    let item = rest.strip_prefix(['.', ')'])?;
+    if !item.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let item = item.trim();
+    (!item.is_empty()).then(|| item.to_string())
+}
+
+/// Parse a re-planner response
+fn parse_replan(text: &str) -> Option<Replan> {
+    let value = parse_args(text);
+    if let Some(answer) = value.get("final_answer").and_then(|a| a.as_str()) {
+        return Some(Replan::FinalAnswer(answer.to_string()));
+    }
+    steps_from_value(&value["steps"]).map(Replan::Steps)
+}
+
+#[async_trait]
+impl Agent for PlanExecuteAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.execute(&input, &[], None).await
+    }
+
+    async fn run_stream(
+        &self,
+        input: String,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.execute(&input, &[], Some(&tx)).await
+    }
+
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self.execute(&input.text, &input.attachments, None).await?;
+        Ok(AgentMessage::from_output(output))
+    }
+
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self
+            .execute(&input.text, &input.attachments, Some(&tx))
+            .await?;
+        Ok(AgentMessage::from_output(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::model::GenerationConfig;
+    use std::sync::Mutex;
+
+    /// Mock model returning scripted responses and recording prompts
+    struct ScriptedModel {
+        responses: Mutex<Vec<&'static str>>,
+        prompts: Mutex<Vec<String>>,
+        user_parts: Mutex<Vec<Vec<Part>>>,
+    }
+
+    #[async_trait]
+    impl Model for ScriptedModel {
+        async fn generate_content(
+            &self,
+            history: &[Content],
+            _config: Option<&GenerationConfig>,
+            _tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            if let Some(user) = history.iter().find(|c| c.role == "user") {
+                self.user_parts.lock().unwrap().push(user.parts.clone());
+            }
+            if let Some(Part::Text(t)) = history.last().and_then(|c| c.parts.first()) {
+                self.prompts.lock().unwrap().push(t.clone());
+            }
+            Ok(Content {
+                role: "model".to_string(),
+                parts: vec![Part::Text(
+                    self.responses.lock().unwrap().remove(0).to_string(),
+                )],
+            })
+        }
+    }
+
+    fn agent(responses: Vec<&'static str>) -> (PlanExecuteAgent, Arc<ScriptedModel>) {
+        let model = Arc::new(ScriptedModel {
+            responses: Mutex::new(responses),
+            prompts: Mutex::new(vec![]),
+            user_parts: Mutex::new(vec![]),
+        });
+        let agent = PlanExecuteAgent::new(
+            "planner".to_string(),
+            "test".to_string(),
+            "Investigate".to_string(),
+            model.clone(),
+            vec![],
+            5,
+        );
+        (agent, model)
+    }
+
+    #[tokio::test]
+    async fn test_run_message_sends_attachments_to_planner_and_steps() {
+        let (agent, model) = agent(vec![
+            r#"{"steps": ["describe the image"]}"#,
+            "A cat on a mat",
+            r#"{"final_answer": "a cat"}"#,
+        ]);
+
+        let image = Part::Image(crate::adk::model::Media::from_bytes("image/png", b"png"));
+        let input = AgentMessage::text("What is in the picture?").with_attachments(vec![image]);
+        assert_eq!(agent.run_message(input).await.unwrap().text, "a cat");
+
+        let turns = model.user_parts.lock().unwrap();
+        assert_eq!(turns.len(), 3);
+        // Planner and step see the image; the re-planner works from the results
+        assert!(matches!(&turns[0][..], [Part::Text(_), Part::Image(_)]));
+        assert!(matches!(&turns[1][..], [Part::Text(_), Part::Image(_)]));
+        assert!(matches!(&turns[2][..], [Part::Text(_)]));
+    }
+
+    #[test]
+    fn test_parse_plan_formats() {
+        let expected = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            parse_plan(r#"{"steps": ["a", "b"]}"#),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            parse_plan("```json\n[{\"description\": \"a\"}, {\"step\": \"b\"}]\n```"),
+            Some(expected.clone())
+        );
+        assert_eq!(parse_plan("Plan:\n1. a\n2) b"), Some(expected));
+        assert_eq!(parse_plan("no plan here"), None);
+        assert_eq!(
+            parse_plan("2024 was busy.\n3 files changed\n1.5 hours\n1. a\n2) b"),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(parse_plan("3 files changed\n2024 was busy."), None);
+    }
+
+    #[test]
+    fn test_parse_replan() {
+        assert_eq!(
+            parse_replan(r#"{"final_answer": "done"}"#),
+            Some(Replan::FinalAnswer("done".to_string()))
+        );
+        assert_eq!(
+            parse_replan(r#"{"steps": []}"#),
+            Some(Replan::Steps(vec![]))
+        );
+        assert_eq!(parse_replan("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_plan_execute_replans_and_streams() {
+        let (agent, model) = agent(vec![
+            r#"{"steps": ["list repos", "check each repo"]}"#,
+            "found repo-a and repo-b",
+            r#"{"steps": ["check repo-a", "check repo-b"]}"#,
+            "repo-a is fine",
+            r#"{"steps": ["check repo-b"]}"#,
+            "repo-b has the bug",
+            r#"{"steps": []}"#,
+            "The bug is in repo-b",
+        ]);
+
+        let (tx, mut rx) = mpsc::channel(64);
+        let answer = agent
+            .run_stream("Find the bug".to_string(), tx)
+            .await
+            .unwrap();
+        assert_eq!(answer, "The bug is in repo-b");
+
+        let mut plans = Vec::new();
+        let mut steps = Vec::new();
+        let mut answers = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                AgentEvent::Plan(plan) => plans.push(plan),
+                AgentEvent::StepResult { step, .. } => steps.push(step),
+                AgentEvent::Answer(a) => answers.push(a),
+                _ => {}
+            }
+        }
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[1], vec!["check repo-a", "check repo-b"]);
+        assert_eq!(steps, vec!["list repos", "check repo-a", "check repo-b"]);
+        // Step answers are not forwarded as final answers
+        assert_eq!(answers, vec!["The bug is in repo-b"]);
+
+        // Later steps see earlier results
+        let prompts = model.prompts.lock().unwrap();
+        assert!(prompts[3].contains("found repo-a and repo-b"));
+        assert!(prompts[3].contains("Current step: check repo-a"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_execute_finishes_early() {
+        let (agent, model) = agent(vec![
+            r#"{"steps": ["look", "look again"]}"#,
+            "the answer is 7",
+            r#"{"final_answer": "7"}"#,
+        ]);
+
+        assert_eq!(agent.run("What?".to_string()).await.unwrap(), "7");
+        assert!(model.responses.lock().unwrap().is_empty());
+    }
+}
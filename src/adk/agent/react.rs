@@ -6,12 +6,29 @@
 //! what to do, takes actions (tool calls), and observes the results in
 //! a structured Thought → Action → Observation loop.
 
-use super::Agent;
+use super::react_parser::parse_react_text;
+use super::{emit, Agent, AgentEvent, AgentMessage};
+use crate::adk::error::ToolError;
 use crate::adk::model::{Content, Model, Part};
-use crate::adk::tool::Tool;
+use crate::adk::tool::{self, Tool};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// How a ReAct agent asks the model for actions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReActMode {
+    /// Use the provider's native function calling; text-format actions are
+    /// still accepted
+    #[default]
+    Native,
+    /// Describe tools in the prompt and parse `Action:` lines from text, for
+    /// models without tool support
+    Text,
+}
 
 /// ReAct (Reasoning + Acting) Agent
 ///
@@ -25,6 +42,7 @@ pub struct ReActAgent {
     pub model: Arc<dyn Model>,
     pub tools: Vec<Arc<dyn Tool>>,
     pub max_iterations: u32,
+    pub mode: ReActMode,
 }
 
 /// ReAct step types
@@ -32,8 +50,9 @@ pub struct ReActAgent {
 enum ReActStep {
     /// Model is thinking/reasoning
     Thought(String),
-    /// Model wants to call a tool
+    /// Model wants to call a tool, optionally after reasoning about it
     Action {
+        thought: Option<String>,
         tool: String,
         args: serde_json::Value,
     },
@@ -57,9 +76,16 @@ impl ReActAgent {
             model,
             tools,
             max_iterations,
+            mode: ReActMode::default(),
         }
     }
 
+    /// Select native function calling or the text protocol
+    pub fn with_mode(mut self, mode: ReActMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     /// Build the ReAct system prompt with tool descriptions
     fn build_react_system_prompt(&self) -> String {
         let tool_section = if self.tools.is_empty() {
@@ -68,11 +94,38 @@ impl ReActAgent {
             let tool_descriptions: Vec<String> = self
                 .tools
                 .iter()
-                .map(|t| format!("- {}: {}", t.name(), t.description()))
+                .map(|t| match self.mode {
+                    ReActMode::Native => format!("- {}: {}", t.name(), t.description()),
+                    ReActMode::Text => format!(
+                        "- {}: {}\n  Arguments (JSON Schema): {}",
+                        t.name(),
+                        t.description(),
+                        t.schema()
+                    ),
+                })
                 .collect();
             format!("Available tools:\n{}", tool_descriptions.join("\n"))
         };
 
+        let format_section = match self.mode {
+            ReActMode::Native => {
+                r#"Response format:
+- To use a tool, respond with a function call (only use tools listed above)
+- To provide a final answer, respond with text starting with "Final Answer:" followed by your answer"#
+            }
+            ReActMode::Text => {
+                r#"Response format:
+- To use a tool, respond with exactly:
+  Thought: <your reasoning>
+  Action: <tool name>
+  Action Input: <JSON object with the tool arguments>
+  Then stop and wait for the Observation.
+- To provide a final answer, respond with:
+  Thought: <your reasoning>
+  Final Answer: <your answer>"#
+            }
+        };
+
         format!(
             r#"{}
 
@@ -83,12 +136,10 @@ You are using the ReAct (Reasoning + Acting) pattern. For each step:
 
 {}
 
-Response format:
-- To use a tool, respond with a function call (only use tools listed above)
-- To provide a final answer, respond with text starting with "Final Answer:" followed by your answer
+{}
 
 Always think step by step. After receiving tool results (Observations), continue reasoning until you can provide a final answer."#,
-            self.instruction, tool_section
+            self.instruction, tool_section, format_section
         )
     }
 
@@ -106,71 +157,105 @@ Always think step by step. After receiving tool results (Observations), continue
     }
 
     /// Parse the model response to determine the ReAct step type
+    ///
+    /// Native function calls take precedence; otherwise the text is parsed for
+    /// `Action:` and `Final Answer:` sections. Thinking parts and any thought
+    /// text are kept alongside an action.
     fn parse_response(&self, response: &Content) -> ReActStep {
+        let mut thoughts = Vec::new();
+        let mut text = String::new();
+        let mut call = None;
+
         for part in &response.parts {
             match part {
-                Part::Thinking(thought) => {
-                    return ReActStep::Thought(thought.clone());
-                }
-                Part::FunctionCall { name, args, .. } => {
-                    return ReActStep::Action {
-                        tool: name.clone(),
-                        args: args.clone(),
-                    };
+                Part::Thinking(thought) if !thought.trim().is_empty() => {
+                    thoughts.push(thought.trim().to_string())
                 }
-                Part::Text(text) => {
-                    let text_trimmed = text.trim();
-                    // Check if this is a final answer
-                    if text_trimmed.to_lowercase().starts_with("final answer:") {
-                        let answer = text_trimmed
-                            .strip_prefix("Final Answer:")
-                            .or_else(|| text_trimmed.strip_prefix("final answer:"))
-                            .or_else(|| text_trimmed.strip_prefix("FINAL ANSWER:"))
-                            .unwrap_or(text_trimmed)
-                            .trim()
-                            .to_string();
-                        return ReActStep::FinalAnswer(answer);
-                    }
-                    // Otherwise treat as thought/reasoning
-                    if !text_trimmed.is_empty() {
-                        return ReActStep::Thought(text_trimmed.to_string());
-                    }
+                Part::Text(t) => text.push_str(t),
+                Part::FunctionCall { name, args, .. } if call.is_none() => {
+                    call = Some((name.clone(), args.clone()))
                 }
                 _ => {}
             }
         }
+
+        let parsed = parse_react_text(&text);
+        thoughts.extend(parsed.thought);
+        let thought = (!thoughts.is_empty()).then(|| thoughts.join("\n"));
+
+        if let Some((tool, args)) = call.or(parsed.action) {
+            return ReActStep::Action {
+                thought,
+                tool,
+                args,
+            };
+        }
+        if let Some(answer) = parsed.final_answer {
+            return ReActStep::FinalAnswer(answer);
+        }
         // Default to empty thought if nothing parsed
-        ReActStep::Thought(String::new())
+        ReActStep::Thought(thought.unwrap_or_default())
     }
 
-    /// Execute a tool and return the result
-    async fn execute_tool(
+    /// Execute a tool, returning the observation text and the raw result
+    ///
+    /// The scratchpad is text only, so media attached to a result is
+    /// dropped and only counted in the observation.
+    async fn call_tool(
         &self,
         tool_name: &str,
         args: serde_json::Value,
-    ) -> Result<String, Box<dyn Error + Send + Sync>> {
-        let tool = self.tools.iter().find(|t| t.name() == tool_name);
-
-        if let Some(t) = tool {
-            match t.execute(args).await {
-                Ok(result) => Ok(serde_json::to_string_pretty(&result).unwrap_or_default()),
-                Err(e) => Ok(format!("Error: {}", e)),
-            }
-        } else {
-            Ok(format!("Error: Tool '{}' not found", tool_name))
+    ) -> (String, serde_json::Value) {
+        match self.tools.iter().find(|t| t.name() == tool_name) {
+            Some(t) => match tool::call(t.as_ref(), args).await {
+                Ok(result) => {
+                    let (result, media) = tool::split_media(result);
+                    let mut observation = serde_json::to_string_pretty(&result).unwrap_or_default();
+                    if !media.is_empty() {
+                        observation.push_str(&format!(
+                            "\n[{} media attachment(s) not shown]",
+                            media.len()
+                        ));
+                    }
+                    (observation, result)
+                }
+                Err(e @ ToolError::InvalidArguments { .. }) => {
+                    let value = e.to_value(t.schema());
+                    (
+                        format!(
+                            "Error: {}\n{}",
+                            e,
+                            serde_json::to_string_pretty(&value).unwrap_or_default()
+                        ),
+                        value,
+                    )
+                }
+                Err(e) => (format!("Error: {}", e), e.to_value(t.schema())),
+            },
+            None => (
+                format!("Error: Tool '{}' not found", tool_name),
+                serde_json::json!({"error": "Tool not found"}),
+            ),
         }
     }
-}
 
-#[async_trait]
-impl Agent for ReActAgent {
-    fn name(&self) -> &str {
-        &self.name
-    }
-
-    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+    /// Run the Thought → Action → Observation loop, emitting events if `tx` is set
+    ///
+    /// `attachments` are sent with the task on every turn, since the prompt is
+    /// rebuilt from the scratchpad each time.
+    async fn execute(
+        &self,
+        input: &str,
+        attachments: &[Part],
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
         let system_prompt = self.build_react_system_prompt();
         let mut scratchpad: Vec<String> = Vec::new();
+        // Tools are only offered natively in native mode
+        let tools = match self.mode {
+            ReActMode::Native => Some(self.tools.as_slice()),
+            ReActMode::Text => None,
+        };
 
         for iteration in 0..self.max_iterations {
             log::info!(
@@ -181,7 +266,9 @@ impl Agent for ReActAgent {
             );
 
             // Build conversation with current scratchpad
-            let current_prompt = self.build_prompt_with_scratchpad(&input, &scratchpad);
+            let current_prompt = self.build_prompt_with_scratchpad(input, &scratchpad);
+            let mut user_parts = vec![Part::Text(current_prompt)];
+            user_parts.extend_from_slice(attachments);
 
             let history = vec![
                 Content {
@@ -190,15 +277,12 @@ impl Agent for ReActAgent {
                 },
                 Content {
                     role: "user".to_string(),
-                    parts: vec![Part::Text(current_prompt)],
+                    parts: user_parts,
                 },
             ];
 
             // Get model response
-            let response = self
-                .model
-                .generate_content(&history, None, Some(&self.tools))
-                .await?;
+            let response = self.model.generate_content(&history, None, tools).await?;
 
             // Parse the response
             let step = self.parse_response(&response);
@@ -209,19 +293,41 @@ impl Agent for ReActAgent {
                     if !thought.is_empty() {
                         scratchpad.push(format!("Thought: {}", thought));
                         log::info!("Thought: {}", thought);
+                        emit(tx, AgentEvent::Thought(thought)).await;
                     }
                 }
-                ReActStep::Action { tool, args } => {
+                ReActStep::Action {
+                    thought,
+                    tool,
+                    args,
+                } => {
+                    if let Some(thought) = thought {
+                        scratchpad.push(format!("Thought: {}", thought));
+                        log::info!("Thought: {}", thought);
+                        emit(tx, AgentEvent::Thought(thought)).await;
+                    }
+
                     scratchpad.push(format!("Action: {}({})", tool, args));
                     log::info!("Action: {}({})", tool, args);
+                    emit(
+                        tx,
+                        AgentEvent::ToolCall {
+                            name: tool.clone(),
+                            args: args.clone(),
+                        },
+                    )
+                    .await;
 
                     // Execute the tool
-                    let observation = self.execute_tool(&tool, args).await?;
+                    let call = tool::with_events(tx.cloned(), self.call_tool(&tool, args));
+                    let (observation, result) = tool::with_model(self.model.clone(), call).await;
+                    emit(tx, AgentEvent::ToolResult { name: tool, result }).await;
                     scratchpad.push(format!("Observation: {}", observation));
                     log::info!("Observation: {}", observation);
                 }
                 ReActStep::FinalAnswer(answer) => {
                     log::info!("Final Answer: {}", answer);
+                    emit(tx, AgentEvent::Answer(answer.clone())).await;
                     return Ok(answer);
                 }
             }
@@ -233,6 +339,7 @@ impl Agent for ReActAgent {
             self.name,
             self.max_iterations
         );
+        emit(tx, AgentEvent::Error("Max iterations reached".into())).await;
 
         // Return the last meaningful content from scratchpad
         let summary = format!(
@@ -241,103 +348,43 @@ impl Agent for ReActAgent {
         );
         Ok(summary)
     }
+}
+
+#[async_trait]
+impl Agent for ReActAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.execute(&input, &[], None).await
+    }
 
     async fn run_stream(
         &self,
         input: String,
-        tx: tokio::sync::mpsc::Sender<crate::adk::agent::AgentEvent>,
+        tx: mpsc::Sender<AgentEvent>,
     ) -> Result<String, Box<dyn Error + Send + Sync>> {
-        use crate::adk::agent::AgentEvent;
-        let system_prompt = self.build_react_system_prompt();
-        let mut scratchpad: Vec<String> = Vec::new();
-
-        for iteration in 0..self.max_iterations {
-            log::info!(
-                "ReActAgent {} iteration {}/{}",
-                self.name,
-                iteration + 1,
-                self.max_iterations
-            );
-
-            let current_prompt = self.build_prompt_with_scratchpad(&input, &scratchpad);
-            let history = vec![
-                Content {
-                    role: "system".to_string(),
-                    parts: vec![Part::Text(system_prompt.clone())],
-                },
-                Content {
-                    role: "user".to_string(),
-                    parts: vec![Part::Text(current_prompt)],
-                },
-            ];
-
-            let response = self
-                .model
-                .generate_content(&history, None, Some(&self.tools))
-                .await?;
-
-            let step = self.parse_response(&response);
-            log::debug!("ReActAgent step: {:?}", step);
+        self.execute(&input, &[], Some(&tx)).await
+    }
 
-            match step {
-                ReActStep::Thought(thought) => {
-                    if !thought.is_empty() {
-                        scratchpad.push(format!("Thought: {}", thought));
-                        let _ = tx.send(AgentEvent::Thought(thought)).await;
-                    }
-                }
-                ReActStep::Action { tool, args } => {
-                    scratchpad.push(format!("Action: {}({})", tool, args));
-                    let _ = tx
-                        .send(AgentEvent::ToolCall {
-                            name: tool.clone(),
-                            args: args.clone(),
-                        })
-                        .await;
-
-                    // Execute tool
-                    let tool_obj = self.tools.iter().find(|t| t.name() == tool);
-                    let (observation, result_val) = if let Some(t) = tool_obj {
-                        match t.execute(args).await {
-                            Ok(result) => (
-                                serde_json::to_string_pretty(&result).unwrap_or_default(),
-                                result,
-                            ),
-                            Err(e) => (
-                                format!("Error: {}", e),
-                                serde_json::json!({"error": e.to_string()}),
-                            ),
-                        }
-                    } else {
-                        (
-                            format!("Error: Tool '{}' not found", tool),
-                            serde_json::json!({"error": "Tool not found"}),
-                        )
-                    };
-
-                    let _ = tx
-                        .send(AgentEvent::ToolResult {
-                            name: tool.clone(),
-                            result: result_val,
-                        })
-                        .await;
-                    scratchpad.push(format!("Observation: {}", observation));
-                }
-                ReActStep::FinalAnswer(answer) => {
-                    let _ = tx.send(AgentEvent::Answer(answer.clone())).await;
-                    return Ok(answer);
-                }
-            }
-        }
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self.execute(&input.text, &input.attachments, None).await?;
+        Ok(AgentMessage::from_output(output))
+    }
 
-        let summary = format!(
-            "Reached maximum iterations. Here's what I found:\n\n{}",
-            scratchpad.join("\n")
-        );
-        let _ = tx
-            .send(AgentEvent::Error("Max iterations reached".into()))
-            .await;
-        Ok(summary)
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let output = self
+            .execute(&input.text, &input.attachments, Some(&tx))
+            .await?;
+        Ok(AgentMessage::from_output(output))
     }
 }
 
@@ -506,13 +553,14 @@ mod tests {
         let response = Content {
             role: "model".to_string(),
             parts: vec![Part::FunctionCall {
+                id: None,
                 name: "search".to_string(),
                 args: json!({"query": "rust"}),
                 thought_signature: None,
             }],
         };
         match agent.parse_response(&response) {
-            ReActStep::Action { tool, args } => {
+            ReActStep::Action { tool, args, .. } => {
                 assert_eq!(tool, "search");
                 assert_eq!(args, json!({"query": "rust"}));
             }
@@ -595,6 +643,7 @@ mod tests {
             Content {
                 role: "model".to_string(),
                 parts: vec![Part::FunctionCall {
+                    id: None,
                     name: "search".to_string(),
                     args: json!({"q": "test"}),
                     thought_signature: None,
@@ -662,13 +711,201 @@ mod tests {
             10,
         );
 
-        let result = agent
-            .execute_tool("test_tool", json!({"x": 1}))
-            .await
-            .unwrap();
+        let (result, _) = agent.call_tool("test_tool", json!({"x": 1})).await;
         assert!(result.contains("Mock result"));
 
-        let result = agent.execute_tool("nonexistent", json!({})).await.unwrap();
+        let (result, _) = agent.call_tool("nonexistent", json!({})).await;
         assert!(result.contains("not found"));
     }
+
+    #[test]
+    fn test_react_parse_thought_and_text_action() {
+        let model = Arc::new(MockModel::new(vec![]));
+        let agent = ReActAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "test".to_string(),
+            model,
+            vec![],
+            10,
+        );
+
+        let response = Content {
+            role: "model".to_string(),
+            parts: vec![Part::Text(
+                "Thought: I should search\nAction: search({\"query\": \"rust\"})".to_string(),
+            )],
+        };
+        match agent.parse_response(&response) {
+            ReActStep::Action {
+                thought,
+                tool,
+                args,
+            } => {
+                assert_eq!(thought.as_deref(), Some("I should search"));
+                assert_eq!(tool, "search");
+                assert_eq!(args, json!({"query": "rust"}));
+            }
+            other => panic!("Expected Action, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_react_parse_thinking_with_function_call() {
+        let model = Arc::new(MockModel::new(vec![]));
+        let agent = ReActAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "test".to_string(),
+            model,
+            vec![],
+            10,
+        );
+
+        let response = Content {
+            role: "model".to_string(),
+            parts: vec![
+                Part::Thinking("Need data first".to_string()),
+                Part::FunctionCall {
+                    id: None,
+                    name: "search".to_string(),
+                    args: json!({}),
+                    thought_signature: None,
+                },
+            ],
+        };
+        match agent.parse_response(&response) {
+            ReActStep::Action { thought, tool, .. } => {
+                assert_eq!(thought.as_deref(), Some("Need data first"));
+                assert_eq!(tool, "search");
+            }
+            other => panic!("Expected Action, got {:?}", other),
+        }
+    }
+
+    /// Model that records whether tools were offered natively
+    struct ToolCheckingModel {
+        inner: MockModel,
+        saw_tools: std::sync::Mutex<Vec<bool>>,
+    }
+
+    #[async_trait]
+    impl Model for ToolCheckingModel {
+        async fn generate_content(
+            &self,
+            history: &[Content],
+            config: Option<&GenerationConfig>,
+            tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            self.saw_tools.lock().unwrap().push(tools.is_some());
+            self.inner.generate_content(history, config, tools).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_react_agent_text_mode() {
+        let model = Arc::new(ToolCheckingModel {
+            inner: MockModel::new(vec![
+                Content {
+                    role: "model".to_string(),
+                    parts: vec![Part::Text(
+                        "Thought: look it up\nAction: search\nAction Input: {'query': 'rust',}"
+                            .to_string(),
+                    )],
+                },
+                Content {
+                    role: "model".to_string(),
+                    parts: vec![Part::Text(
+                        "Thought: done\nFinal Answer: Rust is a language".to_string(),
+                    )],
+                },
+            ]),
+            saw_tools: std::sync::Mutex::new(vec![]),
+        });
+        let tools: Vec<Arc<dyn Tool>> = vec![Arc::new(MockTool::new("search"))];
+        let agent = ReActAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "test".to_string(),
+            model.clone(),
+            tools,
+            10,
+        )
+        .with_mode(ReActMode::Text);
+
+        assert!(agent.build_react_system_prompt().contains("Action Input:"));
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let result = agent.run_stream("What is Rust?".to_string(), tx).await;
+        assert_eq!(result.unwrap(), "Rust is a language");
+        assert_eq!(*model.saw_tools.lock().unwrap(), vec![false, false]);
+
+        let mut tool_args = None;
+        while let Ok(event) = rx.try_recv() {
+            if let AgentEvent::ToolCall { args, .. } = event {
+                tool_args = Some(args);
+            }
+        }
+        assert_eq!(tool_args, Some(json!({"query": "rust"})));
+    }
+
+    /// Model that records the parts of the user message on each turn
+    struct UserPartsModel {
+        inner: MockModel,
+        user_parts: std::sync::Mutex<Vec<Vec<Part>>>,
+    }
+
+    #[async_trait]
+    impl Model for UserPartsModel {
+        async fn generate_content(
+            &self,
+            history: &[Content],
+            config: Option<&GenerationConfig>,
+            tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            if let Some(user) = history.iter().find(|c| c.role == "user") {
+                self.user_parts.lock().unwrap().push(user.parts.clone());
+            }
+            self.inner.generate_content(history, config, tools).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_message_sends_attachments() {
+        let model = Arc::new(UserPartsModel {
+            inner: MockModel::new(vec![
+                Content {
+                    role: "model".to_string(),
+                    parts: vec![Part::Text(
+                        "Thought: check\nAction: search\nAction Input: {}".to_string(),
+                    )],
+                },
+                Content {
+                    role: "model".to_string(),
+                    parts: vec![Part::Text("Final Answer: a cat".to_string())],
+                },
+            ]),
+            user_parts: std::sync::Mutex::new(vec![]),
+        });
+        let agent = ReActAgent::new(
+            "test".to_string(),
+            "test".to_string(),
+            "test".to_string(),
+            model.clone(),
+            vec![Arc::new(MockTool::new("search"))],
+            10,
+        )
+        .with_mode(ReActMode::Text);
+
+        let image = Part::Image(crate::adk::model::Media::from_bytes("image/png", b"png"));
+        let input = AgentMessage::text("What is in the picture?").with_attachments(vec![image]);
+        let output = agent.run_message(input).await.unwrap();
+        assert_eq!(output.text, "a cat");
+
+        let turns = model.user_parts.lock().unwrap();
+        assert_eq!(turns.len(), 2);
+        assert!(turns
+            .iter()
+            .all(|parts| matches!(&parts[..], [Part::Text(_), Part::Image(_)])));
+    }
 }
@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: MIT
+
+//! ReAct output parser
+//!
+//! Parses text responses in the ReAct format:
+//!
+//! ```text
+//! Thought: I need the PR details
+//! Action: fetch_pull_request
+//! Action Input: {"pr_number": 42}
+//! ```
+//!
+//! Actions may also be written inline as `Action: tool({"key": "value"})` or
+//! `Action: tool {"key": "value"}`. Slightly malformed JSON arguments (code
+//! fences, single quotes, trailing commas, missing closing braces) are repaired
+//! where possible.
+
+use serde_json::{json, Value};
+
+/// Sections found in a ReAct text response
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ReActOutput {
+    pub thought: Option<String>,
+    pub action: Option<(String, Value)>,
+    pub final_answer: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Thought,
+    Action,
+    ActionInput,
+    FinalAnswer,
+    Observation,
+}
+
+/// Match a section header at the start of a line, returning the section and
+/// the rest of the line
+fn section_header(line: &str) -> Option<(Section, &str)> {
+    // Tolerate markdown emphasis such as "**Thought:**"
+    let trimmed = line.trim_start().trim_start_matches(['*', '#', ' ']);
+    const HEADERS: [(&str, Section); 5] = [
+        ("final answer:", Section::FinalAnswer),
+        ("action input:", Section::ActionInput),
+        ("action:", Section::Action),
+        ("thought:", Section::Thought),
+        ("observation:", Section::Observation),
+    ];
+
+    for (prefix, section) in HEADERS {
+        if trimmed.len() >= prefix.len()
+            && trimmed.is_char_boundary(prefix.len())
+            && trimmed[..prefix.len()].eq_ignore_ascii_case(prefix)
+        {
+            let rest = trimmed[prefix.len()..].trim_start_matches('*');
+            return Some((section, rest.trim()));
+        }
+    }
+    None
+}
+
+/// Parse a ReAct-formatted text response
+///
+/// Text before any header is treated as a thought. Anything the model writes
+/// after an `Observation:` header is ignored, since observations come from
+/// tool execution, not from the model.
+pub(crate) fn parse_react_text(text: &str) -> ReActOutput {
+    let mut sections: Vec<(Section, String)> = Vec::new();
+    let mut current = (Section::Thought, String::new());
+
+    for line in text.lines() {
+        match section_header(line) {
+            Some((Section::Observation, _)) => break,
+            Some((section, rest)) => {
+                sections.push(std::mem::replace(&mut current, (section, rest.to_string())));
+            }
+            None => {
+                if !current.1.is_empty() {
+                    current.1.push('\n');
+                }
+                current.1.push_str(line);
+            }
+        }
+    }
+    sections.push(current);
+
+    let mut output = ReActOutput::default();
+    let mut action_line: Option<String> = None;
+    let mut action_input: Option<String> = None;
+
+    for (section, body) in sections {
+        let body = body.trim().to_string();
+        match section {
+            Section::Thought if !body.is_empty() => {
+                output.thought = Some(match output.thought.take() {
+                    Some(prev) => format!("{}\n{}", prev, body),
+                    None => body,
+                });
+            }
+            // Only the first action counts; ReAct takes one step at a time
+            Section::Action if action_line.is_none() => action_line = Some(body),
+            Section::ActionInput if action_input.is_none() => action_input = Some(body),
+            Section::FinalAnswer if output.final_answer.is_none() => {
+                output.final_answer = Some(body)
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(line) = action_line {
+        output.action = parse_action(&line, action_input.as_deref());
+    }
+    output
+}
+
+/// Parse an action line, with arguments inline or in a separate `Action Input:`
+fn parse_action(line: &str, input: Option<&str>) -> Option<(String, Value)> {
+    let line = strip_code_fence(line).trim().trim_matches('`');
+    let name_end = line
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+        .unwrap_or(line.len());
+    let name = line[..name_end].trim_end_matches(':');
+    if name.is_empty() || name.eq_ignore_ascii_case("none") {
+        return None;
+    }
+
+    let mut inline = line[name_end..].trim();
+    if let Some(inner) = inline.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        inline = inner.trim();
+    }
+
+    let raw_args = if inline.is_empty() {
+        input.unwrap_or_default()
+    } else {
+        inline
+    };
+    Some((name.to_string(), parse_args(raw_args)))
+}
+
+/// Parse tool arguments, repairing common JSON mistakes
+///
+/// Non-JSON text is passed as `{"input": text}` so single-argument tools still work.
+pub(crate) fn parse_args(raw: &str) -> Value {
+    let raw = strip_code_fence(raw).trim();
+    if raw.is_empty() {
+        return json!({});
+    }
+    if let Ok(value) = serde_json::from_str::<Value>(raw) {
+        return value;
+    }
+    if let Some(value) = repair_json(raw) {
+        return value;
+    }
+    log::warn!("Could not parse action input as JSON: {}", raw);
+    json!({ "input": raw })
+}
+
+/// Best-effort repair of malformed JSON objects
+fn repair_json(raw: &str) -> Option<Value> {
+    let start = raw.find('{')?;
+    let mut candidate = raw[start..].to_string();
+    if let Some(end) = candidate.rfind('}') {
+        candidate.truncate(end + 1);
+    }
+
+    // Single-quoted strings, as written by some models
+    let candidate = convert_single_quotes(&candidate);
+
+    // Trailing commas before closing brackets, leaving string contents alone
+    let mut cleaned = String::with_capacity(candidate.len());
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                if matches!(next, Some('}') | Some(']') | None) {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        cleaned.push(c);
+    }
+
+    // Missing closing braces/brackets (e.g. truncated output)
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in cleaned.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => stack.push('}'),
+            '[' if !in_string => stack.push(']'),
+            '}' | ']' if !in_string => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    if in_string {
+        cleaned.push('"');
+    }
+    while let Some(close) = stack.pop() {
+        cleaned.push(close);
+    }
+
+    serde_json::from_str(&cleaned).ok()
+}
+
+/// Rewrite single-quoted strings as double-quoted ones
+///
+/// Double-quoted strings are copied as they are, so quotes of either kind
+/// inside a string are left alone. A `'` ends a single-quoted string only
+/// when it is followed by `,`, `:`, `}`, `]` or the end, so apostrophes in
+/// words survive.
+fn convert_single_quotes(candidate: &str) -> String {
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut out = String::with_capacity(candidate.len());
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        match (quote, c) {
+            (Some(_), _) if escaped => {
+                escaped = false;
+                // `\'` needs no escape once the string is double-quoted
+                if c != '\'' {
+                    out.push('\\');
+                }
+            }
+            (Some(_), '\\') => {
+                escaped = true;
+                continue;
+            }
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                out.push('"');
+                continue;
+            }
+            (Some('"'), '"') => quote = None,
+            (Some('\''), '\'') => {
+                let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                if matches!(next, Some(',' | ':' | '}' | ']') | None) {
+                    quote = None;
+                    out.push('"');
+                    continue;
+                }
+            }
+            (Some('\''), '"') => out.push('\\'),
+            _ => {}
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Remove a surrounding markdown code fence, if any
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    match trimmed.strip_prefix("```") {
+        Some(rest) => {
+            // Skip an optional language tag on the opening fence
+            let rest = rest.split_once('\n').map_or(rest, |(first, body)| {
+                if first.trim().chars().all(|c| c.is_alphanumeric()) {
+                    body
+                } else {
+                    rest
+                }
+            });
+            rest.trim_end().strip_suffix("```").unwrap_or(rest)
+        }
+        None => trimmed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thought_and_action_with_input() {
+        let output = parse_react_text(
+            "Thought: I need the PR details\nAction: fetch_pull_request\nAction Input: {\"pr_number\": 42}",
+        );
+        assert_eq!(output.thought.as_deref(), Some("I need the PR details"));
+        assert_eq!(
+            output.action,
+            Some(("fetch_pull_request".to_string(), json!({"pr_number": 42})))
+        );
+        assert!(output.final_answer.is_none());
+    }
+
+    #[test]
+    fn test_inline_action_forms() {
+        let output = parse_react_text("Action: search({\"query\": \"rust\"})");
+        assert_eq!(
+            output.action,
+            Some(("search".to_string(), json!({"query": "rust"})))
+        );
+
+        let output = parse_react_text("**Action:** search {\"query\": \"rust\"}");
+        assert_eq!(
+            output.action,
+            Some(("search".to_string(), json!({"query": "rust"})))
+        );
+    }
+
+    #[test]
+    fn test_final_answer_with_thought() {
+        let output = parse_react_text("Thought: I know enough.\nFinal Answer: 42\nsecond line");
+        assert_eq!(output.thought.as_deref(), Some("I know enough."));
+        assert_eq!(output.final_answer.as_deref(), Some("42\nsecond line"));
+        assert!(output.action.is_none());
+    }
+
+    #[test]
+    fn test_plain_text_is_thought() {
+        let output = parse_react_text("Let me think about this.");
+        assert_eq!(output.thought.as_deref(), Some("Let me think about this."));
+        assert!(output.action.is_none());
+    }
+
+    #[test]
+    fn test_ignores_hallucinated_observation() {
+        let output = parse_react_text(
+            "Action: search\nAction Input: {}\nObservation: made up\nFinal Answer: wrong",
+        );
+        assert_eq!(output.action, Some(("search".to_string(), json!({}))));
+        assert!(output.final_answer.is_none());
+    }
+
+    #[test]
+    fn test_repairs_malformed_json() {
+        assert_eq!(parse_args("{'query': 'rust'}"), json!({"query": "rust"}));
+        assert_eq!(
+            parse_args("{\"a\": 1, \"b\": [1, 2,],}"),
+            json!({"a": 1, "b": [1, 2]})
+        );
+        assert_eq!(parse_args("{\"a\": {\"b\": 1"), json!({"a": {"b": 1}}));
+        assert_eq!(parse_args("```json\n{\"a\": 1}\n```"), json!({"a": 1}));
+        assert_eq!(parse_args("plain words"), json!({"input": "plain words"}));
+    }
+
+    #[test]
+    fn test_repair_keeps_commas_inside_strings() {
+        assert_eq!(
+            parse_args(r#"{"a": "a,}", "b": "say \"x,]\"",}"#),
+            json!({"a": "a,}", "b": "say \"x,]\""})
+        );
+    }
+
+    #[test]
+    fn test_repair_mixed_quotes() {
+        assert_eq!(parse_args(r#"{'q': "a b"}"#), json!({"q": "a b"}));
+        assert_eq!(
+            parse_args(r#"{'q': 'say "hi"', "r": "it's"}"#),
+            json!({"q": "say \"hi\"", "r": "it's"})
+        );
+        assert_eq!(
+            parse_args(r"{'q': 'don't', 'r': 'a\'b'}"),
+            json!({"q": "don't", "r": "a'b"})
+        );
+    }
+}
@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: MIT
+
+//! Reflection Agent
+//!
+//! Wraps another agent with a critic loop: the inner agent writes a draft, a
+//! critic call returns a structured verdict, and the draft is revised with the
+//! critique until the critic approves or the round limit is reached. Every
+//! draft and critique is recorded in the output metadata under `reflection`.
+
+use super::react_parser::parse_args;
+use super::{emit, run_inner_message, Agent, AgentEvent, AgentMessage};
+use crate::adk::model::{Content, Model, Part};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Default number of critique rounds
+pub const DEFAULT_MAX_ROUNDS: u32 = 3;
+
+/// Reflection settings for the reflect executor
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reflection {
+    /// Maximum critique rounds (default: 3)
+    #[serde(default)]
+    pub max_rounds: Option<u32>,
+    /// What the critic should look for; a generic review prompt if unset
+    #[serde(default)]
+    pub critic_instructions: Option<String>,
+    /// Executor for the drafting agent (default: "default")
+    #[serde(default)]
+    pub executor: Option<String>,
+}
+
+/// Critic decision on one draft
+#[derive(Debug, Clone, PartialEq)]
+struct Verdict {
+    approved: bool,
+    critique: String,
+}
+
+/// Reflection agent
+pub struct ReflectAgent {
+    pub name: String,
+    pub instruction: String,
+    pub model: Arc<dyn Model>,
+    pub max_rounds: u32,
+    pub critic_instructions: Option<String>,
+    /// Agent that writes and revises drafts
+    inner: Arc<dyn Agent>,
+}
+
+impl ReflectAgent {
+    pub fn new(
+        name: String,
+        instruction: String,
+        model: Arc<dyn Model>,
+        inner: Arc<dyn Agent>,
+    ) -> Self {
+        Self {
+            name,
+            instruction,
+            model,
+            max_rounds: DEFAULT_MAX_ROUNDS,
+            critic_instructions: None,
+            inner,
+        }
+    }
+
+    /// Apply reflection settings from an agent definition
+    pub fn with_reflection(mut self, reflection: Reflection) -> Self {
+        self.max_rounds = reflection.max_rounds.unwrap_or(DEFAULT_MAX_ROUNDS);
+        self.critic_instructions = reflection.critic_instructions;
+        self
+    }
+
+    fn critic_prompt(&self) -> String {
+        let criteria = self.critic_instructions.as_deref().unwrap_or(
+            "Check that the draft fully and correctly completes the task, is clear, \
+             and contains nothing unsupported or irrelevant.",
+        );
+        format!(
+            r#"You are a critic reviewing a draft written by an assistant with these instructions:
+{}
+
+Review criteria:
+{}
+
+Respond ONLY with JSON:
+{{"approved": true|false, "critique": "specific, actionable feedback (empty if approved)"}}"#,
+            self.instruction, criteria
+        )
+    }
+
+    /// Ask the critic for a verdict on `draft`
+    async fn critique(
+        &self,
+        task: &str,
+        draft: &str,
+    ) -> Result<Verdict, Box<dyn Error + Send + Sync>> {
+        let history = vec![
+            Content {
+                role: "system".to_string(),
+                parts: vec![Part::Text(self.critic_prompt())],
+            },
+            Content {
+                role: "user".to_string(),
+                parts: vec![Part::Text(format!("Task:\n{}\n\nDraft:\n{}", task, draft))],
+            },
+        ];
+
+        let response = self.model.generate_content(&history, None, None).await?;
+        let text: String = response
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        Ok(parse_verdict(&text))
+    }
+
+    fn revision_prompt(task: &str, draft: &str, critique: &str) -> String {
+        format!(
+            "Task:\n{}\n\nYour previous draft:\n{}\n\nReviewer feedback:\n{}\n\n\
+             Revise the draft to address the feedback. Output the complete revised version.",
+            task, draft, critique
+        )
+    }
+
+    async fn execute(
+        &self,
+        input: AgentMessage,
+        tx: Option<&mpsc::Sender<AgentEvent>>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        let task = input.text.clone();
+        let attachments = input.attachments.clone();
+        let mut output = run_inner_message(self.inner.as_ref(), input, tx).await?;
+        let mut rounds = Vec::new();
+        let mut approved = false;
+
+        for round in 1..=self.max_rounds.max(1) {
+            let verdict = self.critique(&task, &output.text).await?;
+            log::info!(
+                "ReflectAgent {} round {}: approved={}",
+                self.name,
+                round,
+                verdict.approved
+            );
+            emit(
+                tx,
+                AgentEvent::Critique {
+                    round,
+                    approved: verdict.approved,
+                    critique: verdict.critique.clone(),
+                },
+            )
+            .await;
+            rounds.push(json!({
+                "draft": output.text,
+                "critique": verdict.critique,
+                "approved": verdict.approved,
+            }));
+
+            if verdict.approved {
+                approved = true;
+                break;
+            }
+            if round == self.max_rounds.max(1) {
+                log::warn!(
+                    "ReflectAgent {} reached {} rounds without approval",
+                    self.name,
+                    round
+                );
+                break;
+            }
+
+            let revision = AgentMessage::text(Self::revision_prompt(
+                &task,
+                &output.text,
+                &verdict.critique,
+            ))
+            .with_attachments(attachments.clone());
+            output = run_inner_message(self.inner.as_ref(), revision, tx).await?;
+        }
+
+        emit(tx, AgentEvent::Answer(output.text.clone())).await;
+        Ok(output.with_metadata(
+            "reflection",
+            json!({ "approved": approved, "rounds": rounds }),
+        ))
+    }
+}
+
+/// Parse the critic's reply, accepting plain-text "APPROVED" verdicts too
+fn parse_verdict(text: &str) -> Verdict {
+    let value = parse_args(text);
+    match value.get("approved") {
+        Some(Value::Bool(approved)) => Verdict {
+            approved: *approved,
+            critique: value["critique"].as_str().unwrap_or_default().to_string(),
+        },
+        _ => {
+            let upper = text.trim().to_uppercase();
+            Verdict {
+                approved: upper.starts_with("APPROVED"),
+                critique: text.trim().to_string(),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Agent for ReflectAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(self.execute(AgentMessage::text(input), None).await?.text)
+    }
+
+    async fn run_stream(
+        &self,
+        input: String,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .execute(AgentMessage::text(input), Some(&tx))
+            .await?
+            .text)
+    }
+
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        self.execute(input, None).await
+    }
+
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        self.execute(input, Some(&tx)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::agent::LLMAgent;
+    use crate::adk::model::GenerationConfig;
+    use crate::adk::tool::Tool;
+    use std::sync::Mutex;
+
+    /// Mock model returning scripted responses
+    struct ScriptedModel {
+        responses: Mutex<Vec<&'static str>>,
+    }
+
+    #[async_trait]
+    impl Model for ScriptedModel {
+        async fn generate_content(
+            &self,
+            _history: &[Content],
+            _config: Option<&GenerationConfig>,
+            _tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            Ok(Content {
+                role: "model".to_string(),
+                parts: vec![Part::Text(
+                    self.responses.lock().unwrap().remove(0).to_string(),
+                )],
+            })
+        }
+    }
+
+    fn agent(responses: Vec<&'static str>, max_rounds: u32) -> ReflectAgent {
+        let model: Arc<dyn Model> = Arc::new(ScriptedModel {
+            responses: Mutex::new(responses),
+        });
+        let inner = Arc::new(LLMAgent::new(
+            "writer".to_string(),
+            "test".to_string(),
+            "Write a haiku".to_string(),
+            model.clone(),
+            vec![],
+        ));
+        ReflectAgent::new(
+            "writer".to_string(),
+            "Write a haiku".to_string(),
+            model,
+            inner,
+        )
+        .with_reflection(Reflection {
+            max_rounds: Some(max_rounds),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(
+            parse_verdict(r#"{"approved": false, "critique": "Too long"}"#),
+            Verdict {
+                approved: false,
+                critique: "Too long".to_string()
+            }
+        );
+        assert!(parse_verdict("APPROVED: looks good").approved);
+        assert!(!parse_verdict("Needs a stronger ending").approved);
+    }
+
+    #[tokio::test]
+    async fn test_revises_until_approved() {
+        let agent = agent(
+            vec![
+                "draft one",
+                r#"{"approved": false, "critique": "Needs imagery"}"#,
+                "draft two",
+                r#"{"approved": true, "critique": ""}"#,
+            ],
+            3,
+        );
+
+        let (tx, mut rx) = mpsc::channel(32);
+        let output = agent
+            .run_message_stream(AgentMessage::text("autumn"), tx)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "draft two");
+
+        let reflection = &output.metadata["reflection"];
+        assert_eq!(reflection["approved"], json!(true));
+        assert_eq!(reflection["rounds"][0]["draft"], json!("draft one"));
+        assert_eq!(reflection["rounds"][0]["critique"], json!("Needs imagery"));
+        assert_eq!(reflection["rounds"][1]["draft"], json!("draft two"));
+
+        let mut critiques = 0;
+        let mut answers = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                AgentEvent::Critique { .. } => critiques += 1,
+                AgentEvent::Answer(a) => answers.push(a),
+                _ => {}
+            }
+        }
+        assert_eq!(critiques, 2);
+        assert_eq!(answers, vec!["draft two".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_max_rounds() {
+        let agent = agent(
+            vec![
+                "draft one",
+                r#"{"approved": false, "critique": "Worse"}"#,
+                "draft two",
+                r#"{"approved": false, "critique": "Still worse"}"#,
+            ],
+            2,
+        );
+
+        let output = agent
+            .run_message(AgentMessage::text("autumn"))
+            .await
+            .unwrap();
+        assert_eq!(output.text, "draft two");
+        assert_eq!(output.metadata["reflection"]["approved"], json!(false));
+        assert_eq!(
+            output.metadata["reflection"]["rounds"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
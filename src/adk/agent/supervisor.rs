@@ -0,0 +1,517 @@
+// SPDX-License-Identifier: MIT
+
+//! Supervisor Agent
+//!
+//! A coordinating `LLMAgent` that sees its sub-agents as tools and decides at
+//! runtime which of them to delegate work to. A sub-agent can hand control to
+//! a peer or back to the supervisor by answering with a handoff object:
+//!
+//! ```json
+//! {"handoff": "PrReviewer", "message": "Review PR #42 mentioned in the ticket"}
+//! ```
+
+use super::agent_tool::{
+    default_input_schema, input_message, run_nested, tool_name, DEFAULT_MAX_DEPTH,
+};
+use super::{Agent, AgentEvent, AgentMessage, LLMAgent};
+use crate::adk::model::{Content, Model};
+use crate::adk::tool::Tool;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Default number of peer handoffs allowed within one delegation
+pub const DEFAULT_MAX_HANDOFFS: u32 = 3;
+
+/// Handoff target meaning "return control to the supervisor"
+const SUPERVISOR_TARGET: &str = "supervisor";
+
+/// An agent the supervisor can delegate to
+#[derive(Clone)]
+pub struct SubAgent {
+    /// Tool name the supervisor calls (sanitized agent name)
+    pub name: String,
+    pub description: String,
+    /// JSON schema for delegation arguments; a `task`/`context` schema if unset
+    pub input_schema: Option<Value>,
+    pub agent: Arc<dyn Agent>,
+}
+
+impl SubAgent {
+    pub fn new(name: &str, description: String, agent: Arc<dyn Agent>) -> Self {
+        Self {
+            name: tool_name(name),
+            description,
+            input_schema: None,
+            agent,
+        }
+    }
+
+    pub fn with_input_schema(mut self, schema: Option<Value>) -> Self {
+        self.input_schema = schema;
+        self
+    }
+}
+
+/// Supervisor agent
+pub struct SupervisorAgent {
+    pub name: String,
+    pub description: String,
+    pub instruction: String,
+    pub sub_agents: Vec<SubAgent>,
+    /// Agent that plans and delegates, with the sub-agents as tools
+    coordinator: LLMAgent,
+}
+
+impl SupervisorAgent {
+    /// Fails if two sub-agents have the same tool name
+    pub fn new(
+        name: String,
+        description: String,
+        instruction: String,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+        sub_agents: Vec<SubAgent>,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut all_tools = tools;
+        all_tools.extend(Self::delegation_tools(
+            &sub_agents,
+            DEFAULT_MAX_HANDOFFS,
+            DEFAULT_MAX_DEPTH,
+        )?);
+        let coordinator = LLMAgent::new(
+            name.clone(),
+            description.clone(),
+            Self::coordinator_instruction(&instruction, &sub_agents),
+            model,
+            all_tools,
+        );
+
+        Ok(Self {
+            name,
+            description,
+            instruction,
+            sub_agents,
+            coordinator,
+        })
+    }
+
+    /// Use a custom coordinator (e.g. with different turn limits)
+    ///
+    /// The coordinator should carry `delegation_tools` and
+    /// `coordinator_instruction` for the same sub-agents.
+    pub fn with_coordinator(mut self, coordinator: LLMAgent) -> Self {
+        self.coordinator = coordinator;
+        self
+    }
+
+    /// Instructions for the coordinator, listing the available sub-agents
+    pub fn coordinator_instruction(instruction: &str, sub_agents: &[SubAgent]) -> String {
+        let roster: Vec<String> = sub_agents
+            .iter()
+            .map(|s| format!("- {}: {}", s.name, s.description))
+            .collect();
+        format!(
+            r#"{}
+
+You are a supervisor. Delegate work to these agents by calling them as tools:
+{}
+
+Break the request into parts, delegate each part to the best-suited agent,
+and combine their results into the final answer. Only do work yourself when
+no agent fits."#,
+            instruction,
+            roster.join("\n")
+        )
+    }
+
+    /// One delegation tool per sub-agent
+    ///
+    /// `max_depth` limits how deeply delegations and agent tool calls nest.
+    /// Fails if two sub-agents have the same tool name, since the supervisor
+    /// could only ever reach one of them, or if one is named `supervisor`,
+    /// which handoffs reserve for returning control.
+    pub fn delegation_tools(
+        sub_agents: &[SubAgent],
+        max_handoffs: u32,
+        max_depth: u32,
+    ) -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
+        if sub_agents.iter().any(|s| s.name == SUPERVISOR_TARGET) {
+            return Err(format!(
+                "A sub-agent may not be named '{}'; the name is reserved for handoffs back to the supervisor",
+                SUPERVISOR_TARGET
+            )
+            .into());
+        }
+        let mut seen = HashSet::new();
+        if let Some(dup) = sub_agents.iter().find(|s| !seen.insert(s.name.as_str())) {
+            return Err(format!(
+                "Two sub-agents share the tool name '{}'; rename one of them",
+                dup.name
+            )
+            .into());
+        }
+
+        let peers: Arc<[SubAgent]> = sub_agents.into();
+        Ok((0..sub_agents.len())
+            .map(|index| {
+                let sub = &peers[index];
+                Arc::new(DelegateTool {
+                    name: sub.name.clone(),
+                    description: sub.description.clone(),
+                    schema: sub
+                        .input_schema
+                        .clone()
+                        .unwrap_or_else(default_input_schema),
+                    index,
+                    peers: peers.clone(),
+                    max_handoffs,
+                    max_depth,
+                }) as Arc<dyn Tool>
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Agent for SupervisorAgent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.coordinator.run(input).await
+    }
+
+    async fn run_stream(
+        &self,
+        input: String,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.coordinator.run_stream(input, tx).await
+    }
+
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        self.coordinator.run_message(input).await
+    }
+
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        self.coordinator.run_message_stream(input, tx).await
+    }
+
+    async fn run_with_history(
+        &self,
+        history: &mut Vec<Content>,
+        input: String,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.coordinator.run_with_history(history, input).await
+    }
+}
+
+/// Tool that runs a sub-agent and follows its handoffs
+struct DelegateTool {
+    name: String,
+    description: String,
+    schema: Value,
+    /// Position of this tool's agent in `peers`
+    index: usize,
+    peers: Arc<[SubAgent]>,
+    max_handoffs: u32,
+    /// Maximum nesting of delegations, counting this one
+    max_depth: u32,
+}
+
+impl DelegateTool {
+    /// Instructions appended to a delegated task describing how to hand off
+    fn handoff_note(&self, current: usize) -> String {
+        let peers: Vec<String> = self
+            .peers
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != current)
+            .map(|(_, p)| format!("- {}: {}", p.name, p.description))
+            .collect();
+        let mut note = String::from(
+            "If this task should be handled by another agent, reply ONLY with JSON \
+             {\"handoff\": \"<agent>\", \"message\": \"<what they should do>\"}.",
+        );
+        if !peers.is_empty() {
+            note.push_str("\nAgents:\n");
+            note.push_str(&peers.join("\n"));
+        }
+        note.push_str(&format!(
+            "\nUse \"{}\" to hand control back without finishing.",
+            SUPERVISOR_TARGET
+        ));
+        note
+    }
+}
+
+#[async_trait]
+impl Tool for DelegateTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let mut message = input_message(input);
+        let mut current = self.index;
+        let mut chain = vec![self.peers[current].name.clone()];
+
+        loop {
+            let sub = &self.peers[current];
+            log::info!("Delegating to sub-agent {}", sub.name);
+            let mut request = message.clone();
+            request.text = format!("{}\n\n{}", request.text, self.handoff_note(current));
+            let output = run_nested(&sub.name, sub.agent.as_ref(), request, self.max_depth).await?;
+
+            let Some((target, handoff_message)) = parse_handoff(&output) else {
+                return Ok(delegation_result(&chain, output.to_value()));
+            };
+
+            if target == SUPERVISOR_TARGET {
+                log::info!("Sub-agent {} handed control back", sub.name);
+                let mut result = delegation_result(&chain, Value::String(handoff_message));
+                result["handed_back"] = json!(true);
+                return Ok(result);
+            }
+
+            let Some(next) = self.peers.iter().position(|p| p.name == target) else {
+                log::warn!(
+                    "Sub-agent {} handed off to unknown agent {}",
+                    sub.name,
+                    target
+                );
+                let mut result = delegation_result(&chain, Value::String(handoff_message));
+                result["handed_back"] = json!(true);
+                result["error"] = json!(format!("Unknown handoff target: {}", target));
+                return Ok(result);
+            };
+
+            if chain.len() > self.max_handoffs as usize {
+                log::warn!("Handoff limit of {} reached", self.max_handoffs);
+                let mut result = delegation_result(&chain, Value::String(handoff_message));
+                result["handed_back"] = json!(true);
+                result["error"] = json!(format!(
+                    "Handoff limit of {} reached before {} could run",
+                    self.max_handoffs, target
+                ));
+                return Ok(result);
+            }
+
+            log::info!("Sub-agent {} handed off to {}", sub.name, target);
+            message = AgentMessage::text(format!(
+                "{}\n\n(Handed off from {}. Original task: {})",
+                handoff_message, sub.name, message.text
+            ));
+            current = next;
+            chain.push(target);
+        }
+    }
+}
+
+/// Extract a `{"handoff": ..., "message": ...}` reply
+fn parse_handoff(output: &AgentMessage) -> Option<(String, String)> {
+    let data = output.data.as_ref()?;
+    let target = data.get("handoff")?.as_str()?;
+    let message = data
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    Some((tool_name(target), message.to_string()))
+}
+
+fn delegation_result(chain: &[String], response: Value) -> Value {
+    let mut result = json!({
+        "agent": chain.last().cloned().unwrap_or_default(),
+        "response": response,
+    });
+    if chain.len() > 1 {
+        result["handoffs"] = json!(chain);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::model::{GenerationConfig, Part};
+    use std::sync::Mutex;
+
+    /// Agent that returns a fixed reply and records its inputs
+    struct FixedAgent {
+        reply: &'static str,
+        inputs: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Agent for FixedAgent {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+            self.inputs.lock().unwrap().push(input);
+            Ok(self.reply.to_string())
+        }
+    }
+
+    fn sub(name: &str, reply: &'static str) -> (SubAgent, Arc<FixedAgent>) {
+        let agent = Arc::new(FixedAgent {
+            reply,
+            inputs: Mutex::new(vec![]),
+        });
+        (
+            SubAgent::new(name, format!("{} agent", name), agent.clone()),
+            agent,
+        )
+    }
+
+    /// Model that delegates once to `Jira Fetcher`, then answers with the tool result
+    struct DelegatingModel;
+
+    #[async_trait]
+    impl Model for DelegatingModel {
+        async fn generate_content(
+            &self,
+            history: &[Content],
+            _config: Option<&GenerationConfig>,
+            _tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            let last = history.last().unwrap();
+            let part = match &last.parts[0] {
+                Part::FunctionResponse { response, .. } => Part::Text(response.to_string()),
+                _ => Part::FunctionCall {
+                    id: Some("call_1".to_string()),
+                    name: "Jira_Fetcher".to_string(),
+                    args: json!({"task": "Fetch PROJ-1"}),
+                    thought_signature: None,
+                },
+            };
+            Ok(Content {
+                role: "model".to_string(),
+                parts: vec![part],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_delegates_to_sub_agent() {
+        let (jira, jira_agent) = sub("Jira Fetcher", "PROJ-1: Fix login");
+        let supervisor = SupervisorAgent::new(
+            "router".to_string(),
+            "test".to_string(),
+            "Route requests".to_string(),
+            Arc::new(DelegatingModel),
+            vec![],
+            vec![jira],
+        )
+        .unwrap();
+
+        let answer = supervisor.run("What is PROJ-1?".to_string()).await.unwrap();
+        assert!(answer.contains("PROJ-1: Fix login"));
+        assert!(jira_agent.inputs.lock().unwrap()[0].starts_with("Fetch PROJ-1"));
+    }
+
+    #[tokio::test]
+    async fn test_handoff_to_peer() {
+        let (jira, _) = sub(
+            "jira",
+            r#"{"handoff": "pr", "message": "Review PR #7 linked from PROJ-1"}"#,
+        );
+        let (pr, pr_agent) = sub("pr", "PR #7 looks good");
+        let tools =
+            SupervisorAgent::delegation_tools(&[jira, pr], DEFAULT_MAX_HANDOFFS, DEFAULT_MAX_DEPTH)
+                .unwrap();
+
+        let result = tools[0]
+            .execute(json!({"task": "Check PROJ-1", "context": "from user"}))
+            .await
+            .unwrap();
+        assert_eq!(result["agent"], json!("pr"));
+        assert_eq!(result["response"], json!("PR #7 looks good"));
+        assert_eq!(result["handoffs"], json!(["jira", "pr"]));
+        assert!(pr_agent.inputs.lock().unwrap()[0].starts_with("Review PR #7"));
+    }
+
+    #[tokio::test]
+    async fn test_hand_back_and_handoff_limit() {
+        let (jira, _) = sub(
+            "jira",
+            r#"{"handoff": "supervisor", "message": "Need a project key"}"#,
+        );
+        let tools =
+            SupervisorAgent::delegation_tools(&[jira], DEFAULT_MAX_HANDOFFS, DEFAULT_MAX_DEPTH)
+                .unwrap();
+        let result = tools[0]
+            .execute(json!({"task": "Summarize"}))
+            .await
+            .unwrap();
+        assert_eq!(result["handed_back"], json!(true));
+        assert_eq!(result["response"], json!("Need a project key"));
+
+        // Two agents that keep handing off to each other
+        let (a, _) = sub("a", r#"{"handoff": "b", "message": "you do it"}"#);
+        let (b, _) = sub("b", r#"{"handoff": "a", "message": "no, you"}"#);
+        let tools = SupervisorAgent::delegation_tools(&[a, b], 2, DEFAULT_MAX_DEPTH).unwrap();
+        let result = tools[0].execute(json!({"task": "work"})).await.unwrap();
+        assert_eq!(result["handoffs"], json!(["a", "b", "a"]));
+        assert!(result["error"].as_str().unwrap().contains("Handoff limit"));
+    }
+
+    #[test]
+    fn test_sanitized_name_clash_is_an_error() {
+        let (dashed, _) = sub("code-review", "ok");
+        let (spaced, _) = sub("code review", "ok");
+        let (underscored, _) = sub("code_review", "ok");
+        assert!(SupervisorAgent::delegation_tools(
+            &[dashed, spaced.clone()],
+            DEFAULT_MAX_HANDOFFS,
+            DEFAULT_MAX_DEPTH
+        )
+        .is_ok());
+
+        let err = SupervisorAgent::delegation_tools(
+            &[spaced, underscored],
+            DEFAULT_MAX_HANDOFFS,
+            DEFAULT_MAX_DEPTH,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("code_review"));
+
+        let (reserved, _) = sub("supervisor", "ok");
+        let err =
+            SupervisorAgent::delegation_tools(&[reserved], DEFAULT_MAX_HANDOFFS, DEFAULT_MAX_DEPTH)
+                .err()
+                .unwrap();
+        assert!(err.to_string().contains("reserved"));
+    }
+
+    #[tokio::test]
+    async fn test_delegation_depth_is_configurable() {
+        let (jira, jira_agent) = sub("jira", "done");
+        let tools = SupervisorAgent::delegation_tools(&[jira], DEFAULT_MAX_HANDOFFS, 0).unwrap();
+        let err = tools[0].execute(json!({"task": "work"})).await.unwrap_err();
+        assert!(err.to_string().contains("maximum nesting depth of 0"));
+        assert!(jira_agent.inputs.lock().unwrap().is_empty());
+    }
+}
@@ -5,6 +5,7 @@
 //! This module provides a proper error type hierarchy using thiserror,
 //! replacing the previous Box<dyn Error + Send + Sync> pattern.
 
+use crate::adk::tool::ArgumentError;
 use thiserror::Error;
 
 /// Top-level error type for kinetic-rs
@@ -51,6 +52,52 @@ pub enum KineticError {
     Other(String),
 }
 
+/// Errors from a tool call made by an agent
+#[derive(Debug, Error)]
+pub enum ToolError {
+    /// Arguments don't match the tool's input schema
+    #[error("Invalid arguments for tool '{tool}': {}", summarize(errors))]
+    InvalidArguments {
+        tool: String,
+        errors: Vec<ArgumentError>,
+    },
+
+    /// The tool ran and returned an error
+    #[error("Tool '{tool}' failed: {message}")]
+    Execution { tool: String, message: String },
+}
+
+impl ToolError {
+    /// The error as a tool result for the model
+    ///
+    /// Validation errors include each problem and the expected schema so the
+    /// model can correct its arguments and retry.
+    pub fn to_value(&self, schema: &serde_json::Value) -> serde_json::Value {
+        match self {
+            ToolError::InvalidArguments { errors, .. } => serde_json::json!({
+                "error": self.to_string(),
+                "validation_errors": errors,
+                "expected_schema": schema,
+            }),
+            ToolError::Execution { message, .. } => serde_json::json!({ "error": message }),
+        }
+    }
+}
+
+fn summarize(errors: &[ArgumentError]) -> String {
+    errors
+        .iter()
+        .map(|e| {
+            if e.path.is_empty() {
+                e.message.clone()
+            } else {
+                format!("{}: {}", e.path, e.message)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Workflow-specific errors
 #[derive(Debug, Error)]
 pub enum WorkflowError {
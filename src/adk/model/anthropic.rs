@@ -2,7 +2,11 @@
 
 //! Anthropic Model - Claude API implementation
 
-use super::{Content, GenerationConfig, Model, Part};
+use super::schema::{tool_schema, SchemaDialect};
+use super::{
+    load_attachments, media_unavailable, media_unsupported, Content, GenerationConfig, Media,
+    Model, Part,
+};
 use crate::adk::tool::Tool;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -79,21 +83,30 @@ impl AnthropicModel {
                         "thinking": t
                     }));
                 }
-                Part::FunctionCall { name, args, .. } => {
+                Part::FunctionCall { id, name, args, .. } => {
                     message_content.push(json!({
                         "type": "tool_use",
-                        "id": format!("tool_{}", name), // Generate an ID
+                        "id": Self::tool_use_id(id, name),
                         "name": name,
                         "input": args
                     }));
                 }
-                Part::FunctionResponse { name, response } => {
+                Part::FunctionResponse { id, name, response } => {
                     message_content.push(json!({
                         "type": "tool_result",
-                        "tool_use_id": format!("tool_{}", name),
+                        "tool_use_id": Self::tool_use_id(id, name),
                         "content": serde_json::to_string(response).unwrap_or_default()
                     }));
                 }
+                Part::Image(media) => {
+                    message_content.push(Self::media_block("image", media));
+                }
+                Part::Document(media) => {
+                    message_content.push(Self::file_block(media));
+                }
+                Part::File(media) => {
+                    message_content.push(Self::file_block(media));
+                }
             }
         }
 
@@ -107,6 +120,49 @@ impl AnthropicModel {
         }))
     }
 
+    /// The `tool_use` id for a call, generated from the name when the call has none
+    fn tool_use_id(id: &Option<String>, name: &str) -> String {
+        id.clone().unwrap_or_else(|| format!("tool_{}", name))
+    }
+
+    /// Convert media to an `image` or `document` block with a base64 source
+    fn media_block(block_type: &str, media: &Media) -> serde_json::Value {
+        match media.base64_data() {
+            Ok(data) => json!({
+                "type": block_type,
+                "source": {
+                    "type": "base64",
+                    "media_type": media.mime_type,
+                    "data": data
+                }
+            }),
+            Err(e) => json!({ "type": "text", "text": media_unavailable(media, &e) }),
+        }
+    }
+
+    /// Convert a document or file: PDFs become base64 documents, text files
+    /// plain-text documents, and anything else a text placeholder
+    fn file_block(media: &Media) -> serde_json::Value {
+        if media.mime_type == "application/pdf" {
+            return Self::media_block("document", media);
+        }
+        if !media.mime_type.starts_with("text/") {
+            return json!({ "type": "text", "text": media_unsupported(media, "Anthropic") });
+        }
+        match media.bytes() {
+            Ok(bytes) => json!({
+                "type": "document",
+                "source": {
+                    "type": "text",
+                    "media_type": "text/plain",
+                    "data": String::from_utf8_lossy(&bytes)
+                },
+                "title": media.display_name()
+            }),
+            Err(e) => json!({ "type": "text", "text": media_unavailable(media, &e) }),
+        }
+    }
+
     /// Convert tools to Anthropic tool format
     fn tools_to_anthropic_format(tools: &[Arc<dyn Tool>]) -> Vec<serde_json::Value> {
         tools
@@ -115,7 +171,7 @@ impl AnthropicModel {
                 json!({
                     "name": t.name(),
                     "description": t.description(),
-                    "input_schema": t.schema()
+                    "input_schema": tool_schema(t.as_ref(), SchemaDialect::Anthropic)
                 })
             })
             .collect()
@@ -152,6 +208,7 @@ impl AnthropicModel {
                     let args = block["input"].clone();
 
                     parts.push(Part::FunctionCall {
+                        id: block["id"].as_str().map(|s| s.to_string()),
                         name,
                         args,
                         thought_signature: None, // Anthropic doesn't use thought signatures
@@ -175,6 +232,10 @@ impl AnthropicModel {
 
 #[async_trait]
 impl Model for AnthropicModel {
+    fn name(&self) -> &str {
+        &self.model_name
+    }
+
     async fn generate_content(
         &self,
         history: &[Content],
@@ -187,7 +248,8 @@ impl Model for AnthropicModel {
         let system = Self::extract_system_message(history);
 
         // Convert history to Anthropic message format (excluding system)
-        let messages: Vec<serde_json::Value> = history
+        let messages: Vec<serde_json::Value> = load_attachments(history)
+            .await
             .iter()
             .filter_map(Self::content_to_anthropic_message)
             .collect();
@@ -317,6 +379,7 @@ mod tests {
         let content = Content {
             role: "model".to_string(),
             parts: vec![Part::FunctionCall {
+                id: None,
                 name: "search".to_string(),
                 args: json!({"query": "rust"}),
                 thought_signature: None,
@@ -328,6 +391,53 @@ mod tests {
         assert_eq!(msg["content"][0]["name"], "search");
     }
 
+    #[test]
+    fn test_content_to_anthropic_with_attachments() {
+        let mut notes = Media::from_bytes("text/plain", b"some notes");
+        notes.name = Some("notes.txt".to_string());
+        let content = Content {
+            role: "user".to_string(),
+            parts: vec![
+                Part::Image(Media::from_bytes("image/png", b"png")),
+                Part::Document(Media::from_bytes("application/pdf", b"pdf")),
+                Part::File(notes),
+            ],
+        };
+
+        let msg = AnthropicModel::content_to_anthropic_message(&content).unwrap();
+        let blocks = msg["content"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "image");
+        assert_eq!(blocks[0]["source"]["media_type"], "image/png");
+        assert_eq!(blocks[0]["source"]["data"], "cG5n");
+        assert_eq!(blocks[1]["type"], "document");
+        assert_eq!(blocks[1]["source"]["type"], "base64");
+        assert_eq!(blocks[2]["source"]["type"], "text");
+        assert_eq!(blocks[2]["source"]["data"], "some notes");
+        assert_eq!(blocks[2]["title"], "notes.txt");
+    }
+
+    #[test]
+    fn test_unsupported_file_becomes_placeholder() {
+        let mut archive = Media::from_bytes("application/zip", b"zip");
+        archive.name = Some("logs.zip".to_string());
+        let block = AnthropicModel::file_block(&archive);
+        assert_eq!(block["type"], "text");
+        assert!(block["text"].as_str().unwrap().contains("logs.zip"));
+        assert!(block["text"].as_str().unwrap().contains("not supported"));
+    }
+
+    #[test]
+    fn test_unreadable_attachment_becomes_text() {
+        let media = Media {
+            mime_type: "image/png".to_string(),
+            source: crate::adk::model::MediaSource::Path("/nonexistent/shot.png".to_string()),
+            name: None,
+        };
+        let block = AnthropicModel::media_block("image", &media);
+        assert_eq!(block["type"], "text");
+        assert!(block["text"].as_str().unwrap().contains("unavailable"));
+    }
+
     #[test]
     fn test_parse_anthropic_text_response() {
         let response = json!({
@@ -364,7 +474,8 @@ mod tests {
         assert_eq!(content.parts.len(), 1);
 
         match &content.parts[0] {
-            Part::FunctionCall { name, args, .. } => {
+            Part::FunctionCall { id, name, args, .. } => {
+                assert_eq!(id.as_deref(), Some("tool_123"));
                 assert_eq!(name, "get_weather");
                 assert_eq!(args["city"], "London");
             }
@@ -372,6 +483,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tool_result_uses_call_id() {
+        let content = Content {
+            role: "user".to_string(),
+            parts: vec![
+                Part::FunctionResponse {
+                    id: Some("toolu_01".to_string()),
+                    name: "search".to_string(),
+                    response: json!({"hits": 1}),
+                },
+                Part::FunctionResponse {
+                    id: None,
+                    name: "search".to_string(),
+                    response: json!({"hits": 2}),
+                },
+            ],
+        };
+
+        let msg = AnthropicModel::content_to_anthropic_message(&content).unwrap();
+        assert_eq!(msg["content"][0]["tool_use_id"], "toolu_01");
+        // Calls without an id (e.g. from older sessions) fall back to the name
+        assert_eq!(msg["content"][1]["tool_use_id"], "tool_search");
+    }
+
     #[test]
     fn test_parse_anthropic_thinking_response() {
         let response = json!({
@@ -2,7 +2,11 @@
 
 //! Gemini Model - Google's Gemini API implementation
 
-use super::{Content, GenerationConfig, Model, Part};
+use super::schema::{tool_schema, SchemaDialect};
+use super::{
+    load_attachments, media_unavailable, media_unsupported, Content, GenerationConfig, Media,
+    Model, Part,
+};
 use crate::adk::tool::Tool;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -34,6 +38,10 @@ impl GeminiModel {
 
 #[async_trait]
 impl Model for GeminiModel {
+    fn name(&self) -> &str {
+        &self.model_name
+    }
+
     async fn generate_content(
         &self,
         history: &[Content],
@@ -45,32 +53,12 @@ impl Model for GeminiModel {
             self.model_name, self.api_key
         );
 
-        let contents: Vec<serde_json::Value> = history
+        let contents: Vec<serde_json::Value> = load_attachments(history)
+            .await
             .iter()
             .map(|c| {
-                let parts: Vec<serde_json::Value> = c
-                    .parts
-                    .iter()
-                    .filter_map(|p| match p {
-                        Part::Text(t) => Some(json!({ "text": t })),
-                        Part::Thinking(_) => None, // Thinking is internal, not sent to API
-                        Part::FunctionCall {
-                            name,
-                            args,
-                            thought_signature,
-                        } => {
-                            let mut fc = json!({ "functionCall": { "name": name, "args": args } });
-                            // Include thought_signature if present (required by Gemini thinking models)
-                            if let Some(sig) = thought_signature {
-                                fc["thoughtSignature"] = json!(sig);
-                            }
-                            Some(fc)
-                        }
-                        Part::FunctionResponse { name, response } => Some(
-                            json!({ "functionResponse": { "name": name, "response": response } }),
-                        ),
-                    })
-                    .collect();
+                let parts: Vec<serde_json::Value> =
+                    c.parts.iter().filter_map(part_to_gemini_json).collect();
                 json!({ "role": c.role, "parts": parts })
             })
             .collect();
@@ -87,7 +75,7 @@ impl Model for GeminiModel {
                         json!({
                             "name": t.name(),
                             "description": t.description(),
-                            "parameters": t.schema()
+                            "parameters": tool_schema(t.as_ref(), SchemaDialect::Gemini)
                         })
                     })
                     .collect();
@@ -168,34 +156,7 @@ impl Model for GeminiModel {
             }
         };
 
-        let mut parts = Vec::new();
-        for p in parts_json {
-            // Check for thinking/reasoning content from thinking models
-            // Gemini may return this as "thought" or in thinking-specific fields
-            if let Some(thought) = p.get("thought").and_then(|t| t.as_str()) {
-                if !thought.is_empty() {
-                    parts.push(Part::Thinking(thought.to_string()));
-                }
-            }
-
-            // Regular text content
-            if let Some(text) = p["text"].as_str() {
-                parts.push(Part::Text(text.to_string()));
-            } else if let Some(fc) = p.get("functionCall") {
-                let name = fc["name"].as_str().unwrap_or_default().to_string();
-                let args = fc["args"].clone();
-                // Capture thought_signature (required for Gemini thinking models)
-                let thought_signature = p
-                    .get("thoughtSignature")
-                    .and_then(|s| s.as_str())
-                    .map(|s| s.to_string());
-                parts.push(Part::FunctionCall {
-                    name,
-                    args,
-                    thought_signature,
-                });
-            }
-        }
+        let parts = parts_json.iter().flat_map(parse_gemini_part).collect();
 
         Ok(Content {
             role: "model".to_string(),
@@ -211,22 +172,57 @@ pub fn part_to_gemini_json(part: &Part) -> Option<serde_json::Value> {
         Part::Text(t) => Some(json!({ "text": t })),
         Part::Thinking(_) => None, // Thinking is internal, not sent to API
         Part::FunctionCall {
+            id,
             name,
             args,
             thought_signature,
         } => {
             let mut fc = json!({ "functionCall": { "name": name, "args": args } });
+            if let Some(id) = id {
+                fc["functionCall"]["id"] = json!(id);
+            }
             if let Some(sig) = thought_signature {
                 fc["thoughtSignature"] = json!(sig);
             }
             Some(fc)
         }
-        Part::FunctionResponse { name, response } => {
-            Some(json!({ "functionResponse": { "name": name, "response": response } }))
+        Part::FunctionResponse { id, name, response } => {
+            let mut fr = json!({ "functionResponse": { "name": name, "response": response } });
+            if let Some(id) = id {
+                fr["functionResponse"]["id"] = json!(id);
+            }
+            Some(fr)
         }
+        Part::Image(media) | Part::Document(media) | Part::File(media) => {
+            Some(media_to_gemini_json(media))
+        }
+    }
+}
+
+/// Serialize media as an `inline_data` part, or a text placeholder for
+/// types Gemini doesn't accept inline
+fn media_to_gemini_json(media: &Media) -> serde_json::Value {
+    if !gemini_accepts(&media.mime_type) {
+        return json!({ "text": media_unsupported(media, "Gemini") });
+    }
+    match media.base64_data() {
+        Ok(data) => json!({ "inline_data": { "mime_type": media.mime_type, "data": data } }),
+        Err(e) => json!({ "text": media_unavailable(media, &e) }),
     }
 }
 
+/// MIME types Gemini accepts as `inline_data`: images, audio, video, PDFs
+/// and plain-text formats
+fn gemini_accepts(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "image/png" | "image/jpeg" | "image/webp" | "image/heic" | "image/heif"
+    ) || mime_type.starts_with("audio/")
+        || mime_type.starts_with("video/")
+        || mime_type.starts_with("text/")
+        || mime_type == "application/pdf"
+}
+
 /// Parse a Gemini API JSON part into a Part
 pub fn parse_gemini_part(p: &serde_json::Value) -> Vec<Part> {
     let mut parts = Vec::new();
@@ -249,6 +245,7 @@ pub fn parse_gemini_part(p: &serde_json::Value) -> Vec<Part> {
             .and_then(|s| s.as_str())
             .map(|s| s.to_string());
         parts.push(Part::FunctionCall {
+            id: fc["id"].as_str().map(|s| s.to_string()),
             name,
             args,
             thought_signature,
@@ -281,6 +278,7 @@ mod tests {
     #[test]
     fn test_serialize_function_call_without_thought_signature() {
         let part = Part::FunctionCall {
+            id: None,
             name: "search".to_string(),
             args: json!({"query": "rust"}),
             thought_signature: None,
@@ -295,6 +293,7 @@ mod tests {
     #[test]
     fn test_serialize_function_call_with_thought_signature() {
         let part = Part::FunctionCall {
+            id: None,
             name: "search".to_string(),
             args: json!({"query": "rust"}),
             thought_signature: Some("sig123abc".to_string()),
@@ -309,6 +308,7 @@ mod tests {
     #[test]
     fn test_serialize_function_response() {
         let part = Part::FunctionResponse {
+            id: None,
             name: "search".to_string(),
             response: json!({"results": ["a", "b"]}),
         };
@@ -321,6 +321,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_function_call_id_round_trip() {
+        let json = json!({
+            "functionCall": { "id": "fc_1", "name": "search", "args": {} }
+        });
+        let parts = parse_gemini_part(&json);
+        assert!(matches!(&parts[0], Part::FunctionCall { id: Some(id), .. } if id == "fc_1"));
+
+        let serialized = part_to_gemini_json(&parts[0]).unwrap();
+        assert_eq!(serialized["functionCall"]["id"], "fc_1");
+    }
+
+    #[test]
+    fn test_serialize_media_part_as_inline_data() {
+        let part = Part::Image(Media::from_bytes("image/jpeg", b"jpg"));
+        let json = part_to_gemini_json(&part).unwrap();
+
+        assert_eq!(json["inline_data"]["mime_type"], "image/jpeg");
+        assert_eq!(json["inline_data"]["data"], "anBn");
+    }
+
+    #[test]
+    fn test_unsupported_file_becomes_text() {
+        let mut media = Media::from_bytes("application/zip", b"PK");
+        media.name = Some("bundle.zip".to_string());
+        let json = part_to_gemini_json(&Part::File(media)).unwrap();
+
+        assert!(json.get("inline_data").is_none());
+        let text = json["text"].as_str().unwrap();
+        assert!(text.contains("bundle.zip") && text.contains("Gemini"));
+
+        let csv = Part::File(Media::from_bytes("text/csv", b"a,b"));
+        assert_eq!(
+            part_to_gemini_json(&csv).unwrap()["inline_data"]["mime_type"],
+            "text/csv"
+        );
+    }
+
     // === Parsing Tests ===
 
     #[test]
@@ -363,6 +401,7 @@ mod tests {
                 name,
                 args,
                 thought_signature,
+                ..
             } => {
                 assert_eq!(name, "get_weather");
                 assert_eq!(args["city"], "London");
@@ -389,6 +428,7 @@ mod tests {
                 name,
                 args,
                 thought_signature,
+                ..
             } => {
                 assert_eq!(name, "search");
                 assert_eq!(args["q"], "rust programming");
@@ -447,6 +487,7 @@ mod tests {
             Content {
                 role: "model".to_string(),
                 parts: vec![Part::FunctionCall {
+                    id: None,
                     name: "search".to_string(),
                     args: json!({"q": "Rust"}),
                     thought_signature: Some("turn1_sig".to_string()),
@@ -455,6 +496,7 @@ mod tests {
             Content {
                 role: "user".to_string(),
                 parts: vec![Part::FunctionResponse {
+                    id: None,
                     name: "search".to_string(),
                     response: json!({"results": ["Rust lang"]}),
                 }],
@@ -7,15 +7,21 @@
 //! - [anthropic] - Anthropic's Claude API
 //! - [gemini] - Google's Gemini API
 //! - [openai] - OpenAI's ChatGPT API
+//!
+//! [schema] adapts tool schemas to what each provider accepts.
 
 pub mod anthropic;
 pub mod gemini;
 pub mod openai;
+pub mod schema;
 
 use crate::adk::tool::Tool;
 use async_trait::async_trait;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::error::Error;
+use std::path::Path;
 use std::sync::Arc;
 
 /// Configuration for model generation
@@ -43,6 +49,9 @@ pub enum Part {
     Thinking(String),
     /// Function/tool call requested by the model
     FunctionCall {
+        /// Provider call id, echoed back in the matching `FunctionResponse`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
         name: String,
         args: serde_json::Value,
         /// Thought signature from Gemini thinking models - must be preserved and sent back
@@ -51,9 +60,200 @@ pub enum Part {
     },
     /// Response from executing a function/tool
     FunctionResponse {
+        /// Id of the `FunctionCall` this responds to
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
         name: String,
         response: serde_json::Value,
     },
+    /// Image input (PNG, JPEG, GIF, WebP)
+    Image(Media),
+    /// Document input such as a PDF
+    Document(Media),
+    /// Any other file input
+    File(Media),
+}
+
+/// Binary content attached to a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Media {
+    pub mime_type: String,
+    #[serde(flatten)]
+    pub source: MediaSource,
+    /// Original file name, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Where media content comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaSource {
+    /// Base64-encoded inline data
+    Base64(String),
+    /// Local file path, read when the message is sent
+    Path(String),
+}
+
+impl Media {
+    /// Reference a local file, inferring the MIME type from its extension
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let path = path.as_ref();
+        let is_file = tokio::fs::metadata(path)
+            .await
+            .map(|m| m.is_file())
+            .unwrap_or(false);
+        if !is_file {
+            return Err(format!("Attachment not found: {}", path.display()).into());
+        }
+        let mime_type = mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .essence_str()
+            .to_string();
+
+        Ok(Self {
+            mime_type,
+            source: MediaSource::Path(path.to_string_lossy().to_string()),
+            name: path.file_name().map(|n| n.to_string_lossy().to_string()),
+        })
+    }
+
+    /// Create media from raw bytes
+    pub fn from_bytes(mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            source: MediaSource::Base64(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            name: None,
+        }
+    }
+
+    /// Read a path source into inline base64 data
+    ///
+    /// Base64 sources are returned as they are.
+    pub async fn load(&self) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        match &self.source {
+            MediaSource::Base64(_) => Ok(self.clone()),
+            MediaSource::Path(path) => {
+                let bytes = tokio::fs::read(path)
+                    .await
+                    .map_err(|e| format!("Failed to read attachment {}: {}", path, e))?;
+                Ok(Self {
+                    source: MediaSource::Base64(
+                        base64::engine::general_purpose::STANDARD.encode(bytes),
+                    ),
+                    name: Some(self.display_name()),
+                    ..self.clone()
+                })
+            }
+        }
+    }
+
+    /// Return the content as base64; path sources must be loaded first
+    pub fn base64_data(&self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        match &self.source {
+            MediaSource::Base64(data) => Ok(data.clone()),
+            MediaSource::Path(path) => Err(not_loaded(path)),
+        }
+    }
+
+    /// Return the content as raw bytes; path sources must be loaded first
+    pub fn bytes(&self) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        match &self.source {
+            MediaSource::Base64(data) => {
+                Ok(base64::engine::general_purpose::STANDARD.decode(data)?)
+            }
+            MediaSource::Path(path) => Err(not_loaded(path)),
+        }
+    }
+
+    /// A `data:` URL for providers that accept inline URLs
+    pub fn data_url(&self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(format!(
+            "data:{};base64,{}",
+            self.mime_type,
+            self.base64_data()?
+        ))
+    }
+
+    /// File name to report to providers
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| match &self.source {
+            MediaSource::Path(path) => path.clone(),
+            MediaSource::Base64(_) => "attachment".to_string(),
+        })
+    }
+}
+
+impl Part {
+    /// Create an attachment part from a local file
+    ///
+    /// Images become `Image`, PDFs become `Document`, anything else is a `File`.
+    pub async fn attachment(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let media = Media::from_path(path).await?;
+        Ok(if media.mime_type.starts_with("image/") {
+            Part::Image(media)
+        } else if media.mime_type == "application/pdf" {
+            Part::Document(media)
+        } else {
+            Part::File(media)
+        })
+    }
+}
+
+fn not_loaded(path: &str) -> Box<dyn Error + Send + Sync> {
+    format!("Attachment {} has not been loaded", path).into()
+}
+
+/// Read the files behind path attachments in `history`, so providers can
+/// serialize it without blocking on the disk
+///
+/// Files that can't be read are replaced by a text placeholder. The history
+/// is borrowed as it is when it has no path attachments.
+pub(crate) async fn load_attachments(history: &[Content]) -> Cow<'_, [Content]> {
+    let has_paths = history.iter().flat_map(|c| &c.parts).any(|p| {
+        matches!(
+            p,
+            Part::Image(m) | Part::Document(m) | Part::File(m)
+                if matches!(m.source, MediaSource::Path(_))
+        )
+    });
+    if !has_paths {
+        return Cow::Borrowed(history);
+    }
+
+    let mut loaded = history.to_vec();
+    for part in loaded.iter_mut().flat_map(|c| c.parts.iter_mut()) {
+        let (Part::Image(media) | Part::Document(media) | Part::File(media)) = part else {
+            continue;
+        };
+        match media.load().await {
+            Ok(m) => *media = m,
+            Err(e) => *part = Part::Text(media_unavailable(media, &e)),
+        }
+    }
+    Cow::Owned(loaded)
+}
+
+/// Text placeholder for a file of a type the provider doesn't accept
+pub(crate) fn media_unsupported(media: &Media, provider: &str) -> String {
+    log::warn!(
+        "Dropping attachment {}: {} does not accept {} files",
+        media.display_name(),
+        provider,
+        media.mime_type
+    );
+    format!(
+        "[Attachment {} not sent: {} files are not supported by {}]",
+        media.display_name(),
+        media.mime_type,
+        provider
+    )
+}
+
+/// Text placeholder sent in place of media that could not be loaded
+pub(crate) fn media_unavailable(media: &Media, err: &dyn std::fmt::Display) -> String {
+    log::error!("Dropping attachment {}: {}", media.display_name(), err);
+    format!("[Attachment {} unavailable: {}]", media.display_name(), err)
 }
 
 /// Core trait for LLM model implementations
@@ -65,4 +265,83 @@ pub trait Model: Send + Sync {
         config: Option<&GenerationConfig>,
         tools: Option<&[Arc<dyn Tool>]>,
     ) -> Result<Content, Box<dyn Error + Send + Sync>>;
+
+    /// Model name reported to others, e.g. MCP servers whose sampling
+    /// requests it answers; empty if unknown
+    fn name(&self) -> &str {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_attachment_kind_from_extension() {
+        let dir = std::env::temp_dir().join(format!("kinetic-media-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["shot.png", "spec.pdf", "notes.txt"] {
+            std::fs::write(dir.join(name), b"data").unwrap();
+        }
+
+        assert!(matches!(
+            Part::attachment(dir.join("shot.png")).await.unwrap(),
+            Part::Image(m) if m.mime_type == "image/png"
+        ));
+        assert!(matches!(
+            Part::attachment(dir.join("spec.pdf")).await.unwrap(),
+            Part::Document(m) if m.mime_type == "application/pdf"
+        ));
+        assert!(matches!(
+            Part::attachment(dir.join("notes.txt")).await.unwrap(),
+            Part::File(m) if m.mime_type == "text/plain"
+        ));
+        assert!(Part::attachment(dir.join("missing.png")).await.is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_media_base64_round_trip() {
+        let media = Media::from_bytes("image/png", b"hello");
+        assert_eq!(media.base64_data().unwrap(), "aGVsbG8=");
+        assert_eq!(media.bytes().unwrap(), b"hello");
+        assert_eq!(media.data_url().unwrap(), "data:image/png;base64,aGVsbG8=");
+    }
+
+    #[tokio::test]
+    async fn test_load_attachments_reads_paths() {
+        let path = std::env::temp_dir().join(format!("kinetic-load-{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"hello").unwrap();
+        let history = vec![Content {
+            role: "user".to_string(),
+            parts: vec![
+                Part::attachment(&path).await.unwrap(),
+                Part::Image(Media {
+                    mime_type: "image/png".to_string(),
+                    source: MediaSource::Path("/nonexistent/shot.png".to_string()),
+                    name: None,
+                }),
+            ],
+        }];
+
+        let loaded = load_attachments(&history).await;
+        assert!(matches!(
+            &loaded[0].parts[0],
+            Part::Image(m) if m.base64_data().unwrap() == "aGVsbG8="
+        ));
+        assert!(matches!(&loaded[0].parts[1], Part::Text(t) if t.contains("unavailable")));
+        assert!(matches!(load_attachments(&loaded).await, Cow::Borrowed(_)));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_media_serde_shape() {
+        let media = Media::from_bytes("application/pdf", b"pdf");
+        let json = serde_json::to_value(&media).unwrap();
+        assert_eq!(json["mime_type"], "application/pdf");
+        assert_eq!(json["base64"], "cGRm");
+    }
 }
@@ -2,7 +2,11 @@
 
 //! OpenAI Model - ChatGPT API implementation
 
-use super::{Content, GenerationConfig, Model, Part};
+use super::schema::{tool_schema, SchemaDialect};
+use super::{
+    load_attachments, media_unavailable, media_unsupported, Content, GenerationConfig, Media,
+    Model, Part,
+};
 use crate::adk::tool::Tool;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -37,7 +41,50 @@ impl OpenAIModel {
         })
     }
 
-    /// Convert internal Content to OpenAI message format
+    /// Convert internal Content to OpenAI messages
+    ///
+    /// Tool results become one `tool` message per response, each matched to
+    /// its call via `tool_call_id`; everything else maps to a single message.
+    /// Tool messages only carry text, so media returned by tools follows them
+    /// in a `user` message.
+    fn content_to_openai_messages(content: &Content) -> Vec<serde_json::Value> {
+        let mut tool_messages: Vec<serde_json::Value> = content
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::FunctionResponse { id, name, response } => Some(json!({
+                    "role": "tool",
+                    "tool_call_id": Self::tool_call_id(id, name),
+                    "content": serde_json::to_string(response).unwrap_or_default()
+                })),
+                _ => None,
+            })
+            .collect();
+
+        if tool_messages.is_empty() {
+            return vec![Self::content_to_openai_message(content)];
+        }
+        let rest: Vec<Part> = content
+            .parts
+            .iter()
+            .filter(|part| !matches!(part, Part::FunctionResponse { .. }))
+            .cloned()
+            .collect();
+        if !rest.is_empty() {
+            tool_messages.push(Self::content_to_openai_message(&Content {
+                role: "user".to_string(),
+                parts: rest,
+            }));
+        }
+        tool_messages
+    }
+
+    /// The `tool_call_id` for a call, falling back to the name when the call has no id
+    fn tool_call_id(id: &Option<String>, name: &str) -> String {
+        id.clone().unwrap_or_else(|| name.to_string())
+    }
+
+    /// Convert internal Content without tool results to an OpenAI message
     fn content_to_openai_message(content: &Content) -> serde_json::Value {
         let role = match content.role.as_str() {
             "system" => "system",
@@ -46,28 +93,22 @@ impl OpenAIModel {
             other => other,
         };
 
-        // Check if this is a tool response
-        for part in &content.parts {
-            if let Part::FunctionResponse { name, response } = part {
-                return json!({
-                    "role": "tool",
-                    "tool_call_id": name, // OpenAI requires matching the tool_call_id
-                    "content": serde_json::to_string(response).unwrap_or_default()
-                });
-            }
-        }
-
         // Check for function calls (assistant message with tool_calls)
         let mut tool_calls = Vec::new();
         let mut text_content = String::new();
+        let mut media_parts = Vec::new();
 
         for part in &content.parts {
             match part {
                 Part::Text(t) => text_content.push_str(t),
+                Part::Image(media) => media_parts.push(Self::image_to_openai(media)),
+                Part::Document(media) | Part::File(media) => {
+                    media_parts.push(Self::file_to_openai(media))
+                }
                 Part::Thinking(t) => text_content.push_str(t), // Include thinking as text
-                Part::FunctionCall { name, args, .. } => {
+                Part::FunctionCall { id, name, args, .. } => {
                     tool_calls.push(json!({
-                        "id": name, // Use name as ID for simplicity
+                        "id": Self::tool_call_id(id, name),
                         "type": "function",
                         "function": {
                             "name": name,
@@ -75,7 +116,7 @@ impl OpenAIModel {
                         }
                     }));
                 }
-                Part::FunctionResponse { .. } => {} // Handled above
+                Part::FunctionResponse { .. } => {} // Handled by content_to_openai_messages
             }
         }
 
@@ -85,6 +126,17 @@ impl OpenAIModel {
                 "content": if text_content.is_empty() { serde_json::Value::Null } else { json!(text_content) },
                 "tool_calls": tool_calls
             })
+        } else if !media_parts.is_empty() {
+            // Attachments require the array form of `content`
+            let mut blocks = Vec::with_capacity(media_parts.len() + 1);
+            if !text_content.is_empty() {
+                blocks.push(json!({ "type": "text", "text": text_content }));
+            }
+            blocks.extend(media_parts);
+            json!({
+                "role": role,
+                "content": blocks
+            })
         } else {
             json!({
                 "role": role,
@@ -93,6 +145,38 @@ impl OpenAIModel {
         }
     }
 
+    /// Convert an image to an `image_url` content block with a data URL
+    fn image_to_openai(media: &Media) -> serde_json::Value {
+        match media.data_url() {
+            Ok(url) => json!({ "type": "image_url", "image_url": { "url": url } }),
+            Err(e) => json!({ "type": "text", "text": media_unavailable(media, &e) }),
+        }
+    }
+
+    /// Convert a document or file: PDFs become `file` content blocks, text
+    /// files text blocks, and anything else a text placeholder
+    fn file_to_openai(media: &Media) -> serde_json::Value {
+        if media.mime_type.starts_with("text/") {
+            return match media.bytes() {
+                Ok(bytes) => json!({
+                    "type": "text",
+                    "text": format!("{}:\n{}", media.display_name(), String::from_utf8_lossy(&bytes))
+                }),
+                Err(e) => json!({ "type": "text", "text": media_unavailable(media, &e) }),
+            };
+        }
+        if media.mime_type != "application/pdf" {
+            return json!({ "type": "text", "text": media_unsupported(media, "OpenAI") });
+        }
+        match media.data_url() {
+            Ok(data) => json!({
+                "type": "file",
+                "file": { "filename": media.display_name(), "file_data": data }
+            }),
+            Err(e) => json!({ "type": "text", "text": media_unavailable(media, &e) }),
+        }
+    }
+
     /// Convert tools to OpenAI function format
     fn tools_to_openai_format(tools: &[Arc<dyn Tool>]) -> Vec<serde_json::Value> {
         tools
@@ -103,7 +187,7 @@ impl OpenAIModel {
                     "function": {
                         "name": t.name(),
                         "description": t.description(),
-                        "parameters": t.schema()
+                        "parameters": tool_schema(t.as_ref(), SchemaDialect::OpenAI)
                     }
                 })
             })
@@ -140,6 +224,7 @@ impl OpenAIModel {
                 let args: serde_json::Value = serde_json::from_str(args_str).unwrap_or(json!({}));
 
                 parts.push(Part::FunctionCall {
+                    id: tc["id"].as_str().map(|s| s.to_string()),
                     name,
                     args,
                     thought_signature: None, // OpenAI doesn't use thought signatures
@@ -156,6 +241,10 @@ impl OpenAIModel {
 
 #[async_trait]
 impl Model for OpenAIModel {
+    fn name(&self) -> &str {
+        &self.model_name
+    }
+
     async fn generate_content(
         &self,
         history: &[Content],
@@ -165,9 +254,10 @@ impl Model for OpenAIModel {
         let url = format!("{}/chat/completions", self.base_url);
 
         // Convert history to OpenAI message format
-        let messages: Vec<serde_json::Value> = history
+        let messages: Vec<serde_json::Value> = load_attachments(history)
+            .await
             .iter()
-            .map(Self::content_to_openai_message)
+            .flat_map(Self::content_to_openai_messages)
             .collect();
 
         let mut body = json!({
@@ -273,6 +363,7 @@ mod tests {
         let content = Content {
             role: "model".to_string(),
             parts: vec![Part::FunctionCall {
+                id: None,
                 name: "search".to_string(),
                 args: json!({"query": "rust"}),
                 thought_signature: None,
@@ -287,6 +378,41 @@ mod tests {
         assert_eq!(tool_call["function"]["name"], "search");
     }
 
+    #[test]
+    fn test_content_to_openai_with_attachments() {
+        let content = Content {
+            role: "user".to_string(),
+            parts: vec![
+                Part::Text("What is this?".to_string()),
+                Part::Image(Media::from_bytes("image/png", b"png")),
+                Part::Document(Media::from_bytes("application/pdf", b"pdf")),
+            ],
+        };
+
+        let msg = OpenAIModel::content_to_openai_message(&content);
+        let blocks = msg["content"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "text");
+        assert_eq!(blocks[1]["image_url"]["url"], "data:image/png;base64,cG5n");
+        assert_eq!(blocks[2]["type"], "file");
+        assert_eq!(
+            blocks[2]["file"]["file_data"],
+            "data:application/pdf;base64,cGRm"
+        );
+    }
+
+    #[test]
+    fn test_only_pdfs_are_sent_as_files() {
+        let mut notes = Media::from_bytes("text/plain", b"some notes");
+        notes.name = Some("notes.txt".to_string());
+        let block = OpenAIModel::file_to_openai(&notes);
+        assert_eq!(block["type"], "text");
+        assert_eq!(block["text"], "notes.txt:\nsome notes");
+
+        let block = OpenAIModel::file_to_openai(&Media::from_bytes("application/zip", b"zip"));
+        assert_eq!(block["type"], "text");
+        assert!(block["text"].as_str().unwrap().contains("not supported"));
+    }
+
     #[test]
     fn test_parse_openai_text_response() {
         let response = json!({
@@ -331,11 +457,68 @@ mod tests {
         assert_eq!(content.parts.len(), 1);
 
         match &content.parts[0] {
-            Part::FunctionCall { name, args, .. } => {
+            Part::FunctionCall { id, name, args, .. } => {
+                assert_eq!(id.as_deref(), Some("call_123"));
                 assert_eq!(name, "get_weather");
                 assert_eq!(args["city"], "London");
             }
             _ => panic!("Expected FunctionCall part"),
         }
     }
+
+    #[test]
+    fn test_repeated_tool_calls_keep_distinct_ids() {
+        let call = |id: &str, city: &str| Part::FunctionCall {
+            id: Some(id.to_string()),
+            name: "get_weather".to_string(),
+            args: json!({"city": city}),
+            thought_signature: None,
+        };
+        let result = |id: &str| Part::FunctionResponse {
+            id: Some(id.to_string()),
+            name: "get_weather".to_string(),
+            response: json!({"temp": 20}),
+        };
+
+        let calls = Content {
+            role: "model".to_string(),
+            parts: vec![call("call_a", "London"), call("call_b", "Paris")],
+        };
+        let msg = OpenAIModel::content_to_openai_message(&calls);
+        assert_eq!(msg["tool_calls"][0]["id"], "call_a");
+        assert_eq!(msg["tool_calls"][1]["id"], "call_b");
+
+        let results = Content {
+            role: "user".to_string(),
+            parts: vec![result("call_a"), result("call_b")],
+        };
+        let msgs = OpenAIModel::content_to_openai_messages(&results);
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0]["role"], "tool");
+        assert_eq!(msgs[0]["tool_call_id"], "call_a");
+        assert_eq!(msgs[1]["tool_call_id"], "call_b");
+    }
+
+    #[test]
+    fn test_tool_result_media_follows_tool_messages() {
+        let results = Content {
+            role: "user".to_string(),
+            parts: vec![
+                Part::FunctionResponse {
+                    id: Some("call_a".to_string()),
+                    name: "screenshot".to_string(),
+                    response: json!("[image 1: image/png]"),
+                },
+                Part::Image(Media::from_bytes("image/png", b"png")),
+            ],
+        };
+        let msgs = OpenAIModel::content_to_openai_messages(&results);
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0]["role"], "tool");
+        assert_eq!(msgs[1]["role"], "user");
+        assert_eq!(
+            msgs[1]["content"][0]["image_url"]["url"],
+            "data:image/png;base64,cG5n"
+        );
+    }
 }
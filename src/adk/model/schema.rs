@@ -0,0 +1,554 @@
+// SPDX-License-Identifier: MIT
+
+//! Tool schema sanitization
+//!
+//! Tool schemas come from many places (hand-written, `schemars`, MCP servers)
+//! and use whatever JSON Schema features their authors liked. Providers accept
+//! different subsets: Gemini takes an OpenAPI 3.0 style schema and rejects
+//! `$schema`, `additionalProperties`, `anyOf` with a null branch and most
+//! `format` values, while OpenAI and Anthropic mostly need a plain object at
+//! the root. `$ref`s are resolved inline for every provider.
+
+use crate::adk::tool::Tool;
+use once_cell::sync::Lazy;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Schema subset accepted by a provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaDialect {
+    Gemini,
+    OpenAI,
+    Anthropic,
+}
+
+impl SchemaDialect {
+    fn provider(self) -> &'static str {
+        match self {
+            SchemaDialect::Gemini => "Gemini",
+            SchemaDialect::OpenAI => "OpenAI",
+            SchemaDialect::Anthropic => "Anthropic",
+        }
+    }
+}
+
+/// Result of sanitizing a schema
+#[derive(Debug, Clone)]
+pub struct SanitizedSchema {
+    pub schema: Value,
+    /// What was dropped or rewritten, as `<path>: <change>`
+    pub changes: Vec<String>,
+}
+
+/// Keywords Gemini understands in a schema object
+const GEMINI_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "properties",
+    "required",
+    "minItems",
+    "maxItems",
+    "minProperties",
+    "maxProperties",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "default",
+    "example",
+    "anyOf",
+    "propertyOrdering",
+];
+
+/// Keywords that only make sense at the root and are dropped after `$ref`s are inlined
+const META_KEYWORDS: &[&str] = &["$schema", "$id", "$comment", "definitions", "$defs"];
+
+/// Nesting of inlined `$ref`s before giving up, for recursive schemas
+const MAX_REF_DEPTH: usize = 8;
+
+/// Tool schema in the dialect `dialect`, logging anything dropped
+///
+/// Changes are logged once per tool and provider.
+pub fn tool_schema(tool: &dyn Tool, dialect: SchemaDialect) -> Value {
+    static LOGGED: Lazy<Mutex<HashSet<(String, &'static str)>>> =
+        Lazy::new(|| Mutex::new(HashSet::new()));
+
+    let sanitized = sanitize_schema(tool.schema(), dialect);
+    if !sanitized.changes.is_empty() {
+        let key = (tool.name().to_string(), dialect.provider());
+        if LOGGED.lock().map(|mut l| l.insert(key)).unwrap_or(true) {
+            log::warn!(
+                "Adjusted schema of tool {} for {}: {}",
+                tool.name(),
+                dialect.provider(),
+                sanitized.changes.join("; ")
+            );
+        }
+    }
+    sanitized.schema
+}
+
+/// Rewrite `schema` into the subset accepted by `dialect`
+pub fn sanitize_schema(schema: &Value, dialect: SchemaDialect) -> SanitizedSchema {
+    let mut changes = Vec::new();
+    let mut stack = Vec::new();
+    let inlined = inline_refs(schema, schema, "", &mut stack, &mut changes);
+    let mut schema = strip_meta(inlined, "", &mut changes);
+
+    if dialect == SchemaDialect::Gemini {
+        schema = gemini(schema, "", &mut changes);
+    }
+
+    // Every provider expects an object of named parameters
+    if let Some(obj) = schema.as_object_mut() {
+        if obj.get("type").is_none() {
+            obj.insert("type".to_string(), json!("object"));
+        }
+        if dialect == SchemaDialect::OpenAI && !obj.contains_key("properties") {
+            obj.insert("properties".to_string(), json!({}));
+        }
+    } else {
+        changes.push("/: replaced non-object schema with an empty object".to_string());
+        schema = json!({"type": "object", "properties": {}});
+    }
+
+    SanitizedSchema { schema, changes }
+}
+
+/// Replace local `$ref`s with the schemas they point to
+fn inline_refs(
+    value: &Value,
+    root: &Value,
+    path: &str,
+    stack: &mut Vec<String>,
+    changes: &mut Vec<String>,
+) -> Value {
+    match value {
+        Value::Object(obj) => {
+            if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+                let target = reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| root.pointer(pointer));
+                let resolved = match target {
+                    Some(_)
+                        if stack.len() >= MAX_REF_DEPTH || stack.iter().any(|r| r == reference) =>
+                    {
+                        changes.push(format!(
+                            "{}: replaced recursive $ref {}",
+                            at(path),
+                            reference
+                        ));
+                        json!({"type": "object"})
+                    }
+                    Some(target) => {
+                        stack.push(reference.to_string());
+                        let resolved = inline_refs(target, root, path, stack, changes);
+                        stack.pop();
+                        resolved
+                    }
+                    None => {
+                        changes.push(format!(
+                            "{}: dropped unresolvable $ref {}",
+                            at(path),
+                            reference
+                        ));
+                        json!({})
+                    }
+                };
+
+                // Sibling keywords (usually a description) override the target
+                let mut merged = match resolved {
+                    Value::Object(m) => m,
+                    _ => Map::new(),
+                };
+                for (key, v) in obj {
+                    if key != "$ref" {
+                        let child_path = format!("{}/{}", path, key);
+                        merged.insert(
+                            key.clone(),
+                            inline_refs(v, root, &child_path, stack, changes),
+                        );
+                    }
+                }
+                return Value::Object(merged);
+            }
+
+            let mut out = Map::new();
+            for (key, v) in obj {
+                // Definitions are only reachable through `$ref`; leave them for strip_meta
+                let child = if key == "definitions" || key == "$defs" {
+                    v.clone()
+                } else {
+                    inline_refs(v, root, &format!("{}/{}", path, key), stack, changes)
+                };
+                out.insert(key.clone(), child);
+            }
+            Value::Object(out)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| inline_refs(v, root, &format!("{}/{}", path, i), stack, changes))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Drop `$schema`, `definitions` and similar from every schema object
+fn strip_meta(value: Value, path: &str, changes: &mut Vec<String>) -> Value {
+    map_schema(value, path, changes, &mut |mut obj, path, changes| {
+        for key in META_KEYWORDS {
+            if obj.remove(*key).is_some() && *key != "definitions" && *key != "$defs" {
+                changes.push(format!("{}: dropped {}", at(path), key));
+            }
+        }
+        obj
+    })
+}
+
+/// Apply `f` to a schema object and all subschemas, bottom-up
+fn map_schema<F>(value: Value, path: &str, changes: &mut Vec<String>, f: &mut F) -> Value
+where
+    F: FnMut(Map<String, Value>, &str, &mut Vec<String>) -> Map<String, Value>,
+{
+    let Value::Object(obj) = value else {
+        return value;
+    };
+
+    let mut out = Map::new();
+    for (key, v) in obj {
+        let child_path = format!("{}/{}", path, key);
+        let v = match key.as_str() {
+            "properties" | "patternProperties" => match v {
+                Value::Object(props) => Value::Object(
+                    props
+                        .into_iter()
+                        .map(|(name, s)| {
+                            let p = format!("{}/{}", child_path, name);
+                            (name, map_schema(s, &p, changes, f))
+                        })
+                        .collect(),
+                ),
+                other => other,
+            },
+            "items" | "additionalProperties" | "not" | "contains" => match v {
+                Value::Array(items) => Value::Array(
+                    items
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, s)| map_schema(s, &format!("{}/{}", child_path, i), changes, f))
+                        .collect(),
+                ),
+                other => map_schema(other, &child_path, changes, f),
+            },
+            "anyOf" | "oneOf" | "allOf" => match v {
+                Value::Array(items) => Value::Array(
+                    items
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, s)| map_schema(s, &format!("{}/{}", child_path, i), changes, f))
+                        .collect(),
+                ),
+                other => other,
+            },
+            _ => v,
+        };
+        out.insert(key, v);
+    }
+    Value::Object(f(out, path, changes))
+}
+
+/// Rewrite a schema into Gemini's OpenAPI 3.0 subset
+fn gemini(value: Value, path: &str, changes: &mut Vec<String>) -> Value {
+    map_schema(value, path, changes, &mut gemini_object)
+}
+
+fn gemini_object(
+    mut obj: Map<String, Value>,
+    path: &str,
+    changes: &mut Vec<String>,
+) -> Map<String, Value> {
+    // allOf: merge the parts into this schema
+    if let Some(Value::Array(parts)) = obj.remove("allOf") {
+        changes.push(format!("{}: merged allOf", at(path)));
+        for part in parts {
+            if let Value::Object(part) = part {
+                merge_into(&mut obj, part);
+            }
+        }
+    }
+
+    // oneOf is as close to anyOf as Gemini gets
+    if let Some(one_of) = obj.remove("oneOf") {
+        changes.push(format!("{}: rewrote oneOf as anyOf", at(path)));
+        obj.insert("anyOf".to_string(), one_of);
+    }
+
+    // anyOf with a null branch becomes nullable
+    if let Some(Value::Array(branches)) = obj.remove("anyOf") {
+        let before = branches.len();
+        let mut branches: Vec<Value> = branches.into_iter().filter(|b| !is_null_type(b)).collect();
+        if branches.len() < before {
+            changes.push(format!(
+                "{}: rewrote null anyOf branch as nullable",
+                at(path)
+            ));
+            obj.insert("nullable".to_string(), json!(true));
+        }
+        match branches.len() {
+            0 => {}
+            1 => {
+                if let Value::Object(branch) = branches.remove(0) {
+                    merge_into(&mut obj, branch);
+                }
+            }
+            _ => {
+                obj.insert("anyOf".to_string(), Value::Array(branches));
+            }
+        }
+    }
+
+    // "type": ["string", "null"]
+    if let Some(Value::Array(types)) = obj.get("type").cloned() {
+        let mut types: Vec<Value> = types.into_iter().filter(|t| t != "null").collect();
+        if obj.get("type").and_then(Value::as_array).map(Vec::len) != Some(types.len()) {
+            obj.insert("nullable".to_string(), json!(true));
+        }
+        changes.push(format!("{}: rewrote type list", at(path)));
+        match types.len() {
+            0 => {
+                obj.remove("type");
+            }
+            1 => {
+                obj.insert("type".to_string(), types.remove(0));
+            }
+            _ => {
+                obj.remove("type");
+                let branches = types.into_iter().map(|t| json!({ "type": t })).collect();
+                obj.insert("anyOf".to_string(), Value::Array(branches));
+            }
+        }
+    }
+
+    if let Some(value) = obj.remove("const") {
+        changes.push(format!("{}: rewrote const as enum", at(path)));
+        obj.insert("enum".to_string(), json!([value]));
+    }
+
+    if let Some(Value::Array(values)) = obj.get("enum") {
+        if !values.iter().all(Value::is_string) {
+            changes.push(format!("{}: dropped non-string enum", at(path)));
+            obj.remove("enum");
+        }
+    }
+
+    if let Some(format) = obj.get("format").and_then(Value::as_str) {
+        let ty = obj.get("type").and_then(Value::as_str).unwrap_or_default();
+        if !gemini_format_supported(ty, format) {
+            changes.push(format!("{}: dropped format {}", at(path), format));
+            obj.remove("format");
+        }
+    }
+
+    // Gemini rejects objects with an empty property list
+    if obj
+        .get("properties")
+        .and_then(Value::as_object)
+        .is_some_and(Map::is_empty)
+    {
+        obj.remove("properties");
+    }
+
+    // Required names must exist
+    let properties: Option<HashSet<String>> = obj
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|p| p.keys().cloned().collect());
+    if let Some(Value::Array(required)) = obj.get_mut("required") {
+        let before = required.len();
+        required.retain(|r| {
+            r.as_str()
+                .is_some_and(|r| properties.as_ref().is_some_and(|p| p.contains(r)))
+        });
+        if required.len() < before {
+            changes.push(format!("{}: dropped unknown required properties", at(path)));
+        }
+        if required.is_empty() {
+            obj.remove("required");
+        }
+    }
+
+    // Tuple-style items: keep the first schema
+    if let Some(Value::Array(items)) = obj.get("items") {
+        changes.push(format!("{}: kept the first of the tuple items", at(path)));
+        let first = items.first().cloned().unwrap_or_else(|| json!({}));
+        obj.insert("items".to_string(), first);
+    }
+
+    let unsupported: Vec<String> = obj
+        .keys()
+        .filter(|k| !GEMINI_KEYWORDS.contains(&k.as_str()))
+        .cloned()
+        .collect();
+    for key in unsupported {
+        obj.remove(&key);
+        changes.push(format!("{}: dropped {}", at(path), key));
+    }
+
+    obj
+}
+
+/// Formats Gemini accepts for each type
+fn gemini_format_supported(ty: &str, format: &str) -> bool {
+    match ty {
+        "string" => matches!(format, "enum" | "date-time"),
+        "number" => matches!(format, "float" | "double"),
+        "integer" => matches!(format, "int32" | "int64"),
+        _ => false,
+    }
+}
+
+fn is_null_type(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+/// Merge `part` into `obj`, combining properties and required lists
+///
+/// Keys already on `obj` win, so a description next to `anyOf` is kept.
+fn merge_into(obj: &mut Map<String, Value>, part: Map<String, Value>) {
+    for (key, value) in part {
+        match (key.as_str(), obj.get_mut(&key), value) {
+            ("properties", Some(Value::Object(existing)), Value::Object(props)) => {
+                for (name, schema) in props {
+                    existing.entry(name).or_insert(schema);
+                }
+            }
+            ("required", Some(Value::Array(existing)), Value::Array(required)) => {
+                for name in required {
+                    if !existing.contains(&name) {
+                        existing.push(name);
+                    }
+                }
+            }
+            (_, Some(_), _) => {}
+            (_, None, value) => {
+                obj.insert(key, value);
+            }
+        }
+    }
+}
+
+fn at(path: &str) -> &str {
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gemini_strips_unsupported_constructs() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "path": {"type": "string", "format": "uri"},
+                "limit": {"anyOf": [{"type": "integer", "format": "uint32"}, {"type": "null"}]},
+                "tags": {"type": ["array", "null"], "items": {"type": "string"}},
+                "mode": {"const": "fast"}
+            },
+            "required": ["path", "missing"]
+        });
+
+        let result = sanitize_schema(&schema, SchemaDialect::Gemini);
+        assert_eq!(
+            result.schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string"},
+                    "limit": {"type": "integer", "nullable": true},
+                    "tags": {"type": "array", "nullable": true, "items": {"type": "string"}},
+                    "mode": {"enum": ["fast"]}
+                },
+                "required": ["path"]
+            })
+        );
+        let changes = result.changes.join("\n");
+        assert!(changes.contains("/: dropped $schema"));
+        assert!(changes.contains("/: dropped additionalProperties"));
+        assert!(changes.contains("/properties/path: dropped format uri"));
+    }
+
+    #[test]
+    fn test_refs_are_inlined() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "owner": {"$ref": "#/definitions/User", "description": "Issue owner"},
+                "node": {"$ref": "#/$defs/Node"}
+            },
+            "definitions": {
+                "User": {"type": "object", "properties": {"login": {"type": "string"}}}
+            },
+            "$defs": {
+                "Node": {"type": "object", "properties": {"next": {"$ref": "#/$defs/Node"}}}
+            }
+        });
+
+        let result = sanitize_schema(&schema, SchemaDialect::Anthropic);
+        let schema = result.schema;
+        assert!(schema.get("definitions").is_none());
+        assert!(schema.get("$defs").is_none());
+        assert_eq!(
+            schema["properties"]["owner"],
+            json!({
+                "type": "object",
+                "properties": {"login": {"type": "string"}},
+                "description": "Issue owner"
+            })
+        );
+        // The recursive reference stops after one level
+        assert_eq!(
+            schema["properties"]["node"]["properties"]["next"],
+            json!({"type": "object"})
+        );
+        assert!(result.changes.iter().any(|c| c.contains("recursive $ref")));
+    }
+
+    #[test]
+    fn test_root_is_an_object() {
+        let empty = json!({"type": "object", "properties": {}});
+        let gemini = sanitize_schema(&empty, SchemaDialect::Gemini).schema;
+        assert_eq!(gemini, json!({"type": "object"}));
+
+        let openai = sanitize_schema(&json!({}), SchemaDialect::OpenAI).schema;
+        assert_eq!(openai, json!({"type": "object", "properties": {}}));
+    }
+
+    #[test]
+    fn test_openai_keeps_standard_keywords() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"q": {"type": "string", "format": "uri"}},
+            "additionalProperties": false
+        });
+        let result = sanitize_schema(&schema, SchemaDialect::OpenAI);
+        assert_eq!(result.schema, schema);
+        assert!(result.changes.is_empty());
+    }
+}
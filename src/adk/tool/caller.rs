@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MIT
+
+//! Model of the agent running a tool
+//!
+//! Agents run tool calls inside `with_model`, so an MCP server that asks for
+//! sampling during a call is answered by the model of the agent that made
+//! the call, without the model being threaded through `Tool::execute`.
+
+use crate::adk::model::Model;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    static MODEL: Arc<dyn Model>;
+}
+
+/// Run `f` with `model` as the model seen by `calling_model()`
+pub async fn with_model<F: Future>(model: Arc<dyn Model>, f: F) -> F::Output {
+    MODEL.scope(model, f).await
+}
+
+/// Model of the agent whose tool call is running, if any
+pub fn calling_model() -> Option<Arc<dyn Model>> {
+    MODEL.try_with(|model| model.clone()).ok()
+}
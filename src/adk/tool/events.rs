@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT
+
+//! Event channel of the agent running a tool
+//!
+//! Agents run tool calls inside `with_events`, so a tool (or the MCP client
+//! serving it) can report progress through `event_sender()` without the
+//! channel being threaded through `Tool::execute`.
+
+use crate::adk::agent::AgentEvent;
+use std::future::Future;
+use tokio::sync::mpsc;
+
+tokio::task_local! {
+    static EVENTS: Option<mpsc::Sender<AgentEvent>>;
+}
+
+/// Run `f` with `tx` as the event channel seen by `event_sender()`
+pub async fn with_events<F: Future>(tx: Option<mpsc::Sender<AgentEvent>>, f: F) -> F::Output {
+    EVENTS.scope(tx, f).await
+}
+
+/// Event channel of the agent whose tool call is running, if it streams events
+pub fn event_sender() -> Option<mpsc::Sender<AgentEvent>> {
+    EVENTS.try_with(|tx| tx.clone()).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_event_sender_is_scoped() {
+        assert!(event_sender().is_none());
+
+        let (tx, mut rx) = mpsc::channel(1);
+        with_events(Some(tx), async {
+            let tx = event_sender().expect("sender in scope");
+            tx.send(AgentEvent::Log("halfway".to_string()))
+                .await
+                .unwrap();
+        })
+        .await;
+
+        assert!(matches!(rx.recv().await, Some(AgentEvent::Log(m)) if m == "halfway"));
+        assert!(event_sender().is_none());
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MIT
+
+//! Media returned by tools
+//!
+//! `Tool::execute` returns JSON, so a tool that produces images or files
+//! wraps its result with `with_media`. Agents take the media back out with
+//! `split_media` and send it to the model as content parts next to the
+//! function response, keeping base64 data out of the JSON the model reads.
+
+use crate::adk::model::Part;
+use serde_json::{json, Value};
+
+/// Key holding the media parts of a wrapped result
+pub const MEDIA_KEY: &str = "_media";
+/// Key holding the JSON result next to `MEDIA_KEY`
+const RESULT_KEY: &str = "result";
+
+/// Attach media parts to a tool result; returns `result` unchanged if there are none
+pub fn with_media(result: Value, media: Vec<Part>) -> Value {
+    if media.is_empty() {
+        return result;
+    }
+    json!({ RESULT_KEY: result, MEDIA_KEY: media })
+}
+
+/// Separate a result built by `with_media` into its JSON result and media parts
+///
+/// Other values are returned as they are, with no media.
+pub fn split_media(result: Value) -> (Value, Vec<Part>) {
+    let Value::Object(mut obj) = result else {
+        return (result, Vec::new());
+    };
+    if obj.len() != 2 || !obj.contains_key(RESULT_KEY) {
+        return (Value::Object(obj), Vec::new());
+    }
+    match obj.get(MEDIA_KEY).cloned().map(serde_json::from_value) {
+        Some(Ok(media)) => (obj.remove(RESULT_KEY).unwrap_or_default(), media),
+        _ => (Value::Object(obj), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::model::Media;
+
+    #[test]
+    fn test_media_round_trip() {
+        let image = Part::Image(Media::from_bytes("image/png", b"png"));
+        let wrapped = with_media(json!("chart attached"), vec![image]);
+        assert_eq!(wrapped[MEDIA_KEY][0]["Image"]["mime_type"], "image/png");
+
+        let (result, media) = split_media(wrapped);
+        assert_eq!(result, json!("chart attached"));
+        assert!(matches!(&media[..], [Part::Image(m)] if m.mime_type == "image/png"));
+    }
+
+    #[test]
+    fn test_plain_results_pass_through() {
+        assert_eq!(with_media(json!({"a": 1}), Vec::new()), json!({"a": 1}));
+
+        for plain in [
+            json!({"result": 1, "_media": "not parts"}),
+            json!({"result": 1}),
+        ] {
+            let (result, media) = split_media(plain.clone());
+            assert_eq!(result, plain);
+            assert!(media.is_empty());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+
+//! Per-tool call counters
+//!
+//! Counts are process-wide and keyed by tool name; they are updated by
+//! `tool::call` and can be read with `metrics().snapshot()`.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+static METRICS: Lazy<ToolMetrics> = Lazy::new(ToolMetrics::default);
+
+/// Global tool metrics
+pub fn metrics() -> &'static ToolMetrics {
+    &METRICS
+}
+
+/// Counters for one tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ToolStats {
+    /// All calls, including ones rejected by validation
+    pub calls: u64,
+    pub successes: u64,
+    /// Calls whose arguments didn't match the tool schema
+    pub validation_failures: u64,
+    /// Calls where the tool itself returned an error
+    pub execution_failures: u64,
+    /// Time spent executing the tool (validated calls only)
+    pub total_duration_ms: u64,
+}
+
+/// Outcome of one tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallOutcome {
+    Success,
+    ValidationFailure,
+    ExecutionFailure,
+}
+
+#[derive(Debug, Default)]
+pub struct ToolMetrics {
+    stats: Mutex<HashMap<String, ToolStats>>,
+}
+
+impl ToolMetrics {
+    /// Record a finished call
+    pub fn record(&self, tool: &str, outcome: CallOutcome, duration: Duration) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats.entry(tool.to_string()).or_default();
+        entry.calls += 1;
+        entry.total_duration_ms += duration.as_millis() as u64;
+        match outcome {
+            CallOutcome::Success => entry.successes += 1,
+            CallOutcome::ValidationFailure => entry.validation_failures += 1,
+            CallOutcome::ExecutionFailure => entry.execution_failures += 1,
+        }
+    }
+
+    /// Counters for every tool called so far, sorted by name
+    pub fn snapshot(&self) -> BTreeMap<String, ToolStats> {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        stats.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    /// Counters for one tool
+    pub fn get(&self, tool: &str) -> Option<ToolStats> {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        stats.get(tool).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_outcomes() {
+        let metrics = ToolMetrics::default();
+        metrics.record("t", CallOutcome::Success, Duration::from_millis(5));
+        metrics.record("t", CallOutcome::ValidationFailure, Duration::ZERO);
+        metrics.record("t", CallOutcome::ExecutionFailure, Duration::from_millis(2));
+
+        let stats = metrics.get("t").unwrap();
+        assert_eq!(stats.calls, 3);
+        assert_eq!(stats.successes, 1);
+        assert_eq!(stats.validation_failures, 1);
+        assert_eq!(stats.execution_failures, 1);
+        assert_eq!(stats.total_duration_ms, 7);
+        assert!(metrics.snapshot().contains_key("t"));
+    }
+}
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: MIT
+
+mod caller;
+mod events;
+mod media;
+mod metrics;
+mod output;
+mod validation;
+
+pub use caller::{calling_model, with_model};
+pub use events::{event_sender, with_events};
+pub use kinetic_macros::tool;
+pub use media::{split_media, with_media, MEDIA_KEY};
+pub use metrics::{metrics, CallOutcome, ToolMetrics, ToolStats};
+pub use output::{
+    apply_output_policies, LimitedTool, OutputPolicy, OutputStore, ReadOutputTool, TruncateMode,
+    READ_OUTPUT_TOOL,
+};
+pub use validation::{validate_args, ArgumentError};
+
+use crate::adk::error::ToolError;
+use async_trait::async_trait;
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+/// Trait for tools that can be called by agents.
+///
+/// # Optimization Notes
+/// - `name()` and `description()` return `&str` to avoid allocation on every call
+/// - `schema()` returns `&Value` to avoid cloning the schema on every access
+/// - Implementations should store these values in struct fields
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// Returns the tool name (must be unique within an agent's tool set)
+    fn name(&self) -> &str;
+
+    /// Returns a human-readable description of what the tool does
+    fn description(&self) -> &str;
+
+    /// Returns the JSON schema for the tool's input parameters
+    fn schema(&self) -> &Value;
+
+    /// Execute the tool with the given input and return the result
+    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>>;
+}
+
+/// JSON schema for a tool's argument type
+///
+/// Subschemas are inlined and `Option<T>` fields are described as plain `T`
+/// (they are simply not required), which keeps schemas in the subset model
+/// providers accept.
+pub fn schema_for<T: JsonSchema>() -> Value {
+    let settings = SchemaSettings::draft07().with(|s| {
+        s.option_add_null_type = false;
+        s.inline_subschemas = true;
+        s.meta_schema = None;
+    });
+    let schema = settings.into_generator().into_root_schema_for::<T>();
+    let mut value = serde_json::to_value(schema).unwrap_or_default();
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("title");
+        obj.remove("definitions");
+    }
+    value
+}
+
+/// Re-exports used by `#[tool]` expansions
+#[doc(hidden)]
+pub mod __private {
+    pub use async_trait;
+    pub use once_cell::sync::Lazy;
+    pub use serde_json;
+}
+
+/// Validate `args` against the tool's schema, then execute it
+///
+/// Agents dispatch tool calls through this so that bad model arguments come
+/// back as a structured `ToolError::InvalidArguments` the model can act on.
+/// Every call is counted in `metrics()`.
+pub async fn call(tool: &dyn Tool, args: Value) -> Result<Value, ToolError> {
+    let name = tool.name();
+    if let Err(errors) = validate_args(tool.schema(), &args) {
+        log::warn!("Invalid arguments for tool {}: {:?}", name, errors);
+        metrics().record(name, CallOutcome::ValidationFailure, Duration::ZERO);
+        return Err(ToolError::InvalidArguments {
+            tool: name.to_string(),
+            errors,
+        });
+    }
+
+    let started = Instant::now();
+    let result = tool.execute(args).await;
+    let outcome = match result {
+        Ok(_) => CallOutcome::Success,
+        Err(_) => CallOutcome::ExecutionFailure,
+    };
+    metrics().record(name, outcome, started.elapsed());
+    result.map_err(|e| ToolError::Execution {
+        tool: name.to_string(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    struct Adder {
+        schema: Value,
+    }
+
+    #[async_trait]
+    impl Tool for Adder {
+        fn name(&self) -> &str {
+            "test_call_adder"
+        }
+
+        fn description(&self) -> &str {
+            "Adds two numbers"
+        }
+
+        fn schema(&self) -> &Value {
+            &self.schema
+        }
+
+        async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+            match (input["a"].as_i64(), input["b"].as_i64()) {
+                (Some(a), Some(b)) if b != 0 => Ok(json!(a + b)),
+                _ => Err("b must not be zero".into()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_validates_and_counts() {
+        let tool = Adder {
+            schema: json!({
+                "type": "object",
+                "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+                "required": ["a", "b"]
+            }),
+        };
+
+        assert_eq!(
+            call(&tool, json!({"a": 1, "b": 2})).await.unwrap(),
+            json!(3)
+        );
+
+        let err = call(&tool, json!({"a": "1"})).await.unwrap_err();
+        let value = err.to_value(tool.schema());
+        assert_eq!(value["validation_errors"].as_array().unwrap().len(), 2);
+        assert_eq!(value["expected_schema"], *tool.schema());
+
+        let err = call(&tool, json!({"a": 1, "b": 0})).await.unwrap_err();
+        assert_eq!(
+            err.to_value(tool.schema()),
+            json!({"error": "b must not be zero"})
+        );
+
+        let stats = metrics().get("test_call_adder").unwrap();
+        assert_eq!(stats.calls, 3);
+        assert_eq!(stats.successes, 1);
+        assert_eq!(stats.validation_failures, 1);
+        assert_eq!(stats.execution_failures, 1);
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct GreetArgs {
+        /// Who to greet
+        name: String,
+        /// Repeat the greeting this many times
+        times: Option<u32>,
+    }
+
+    struct Greeter {
+        greeting: String,
+    }
+
+    /// Greets someone by name.
+    #[tool(name = "test_greet")]
+    async fn greet(
+        greeter: &Greeter,
+        args: GreetArgs,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let line = format!("{}, {}!", greeter.greeting, args.name);
+        Ok(json!(vec![line; args.times.unwrap_or(1) as usize]))
+    }
+
+    /// Returns a constant.
+    #[tool]
+    async fn answer() -> Result<u32, std::io::Error> {
+        Ok(42)
+    }
+
+    #[tokio::test]
+    async fn test_tool_macro() {
+        let tool = GreetTool::new(Greeter {
+            greeting: "Hello".to_string(),
+        });
+        assert_eq!(tool.name(), "test_greet");
+        assert_eq!(tool.description(), "Greets someone by name.");
+        assert_eq!(tool.schema()["required"], json!(["name"]));
+        assert_eq!(
+            tool.schema()["properties"]["name"]["description"],
+            "Who to greet"
+        );
+        assert_eq!(tool.schema()["properties"]["times"]["type"], "integer");
+
+        let result = call(&tool, json!({"name": "Ada", "times": 2}))
+            .await
+            .unwrap();
+        assert_eq!(result, json!(["Hello, Ada!", "Hello, Ada!"]));
+        assert!(call(&tool, json!({"times": 2})).await.is_err());
+
+        let tool = AnswerTool::new();
+        assert_eq!(tool.name(), "answer");
+        assert_eq!(tool.execute(json!({})).await.unwrap(), json!(42));
+    }
+}
@@ -0,0 +1,657 @@
+// SPDX-License-Identifier: MIT
+
+//! Tool output policies
+//!
+//! Large tool results (PR diffs, issue searches) go straight into the
+//! conversation history. `LimitedTool` wraps a tool and shrinks its results
+//! according to an `OutputPolicy`: JSON field projection, then head/tail
+//! truncation or summarization by a (cheap) model. The full output is kept in
+//! an `OutputStore` so the agent can page through the rest with the
+//! `read_tool_output` tool.
+
+use super::{split_media, with_media, Tool};
+use crate::adk::model::{Content, Model, Part};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+/// Name of the tool that pages through stored outputs
+pub const READ_OUTPUT_TOOL: &str = "read_tool_output";
+
+/// Rough characters per token, used to turn `max_tokens` into characters
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Full outputs kept per agent for paging
+const MAX_STORED_OUTPUTS: usize = 32;
+
+/// Characters returned by one `read_tool_output` call unless asked otherwise
+const DEFAULT_PAGE_CHARS: usize = 4000;
+
+/// Which part of an oversized output to keep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncateMode {
+    /// Keep the beginning
+    Head,
+    /// Keep the end
+    Tail,
+    /// Keep the beginning and the end, dropping the middle
+    #[default]
+    HeadTail,
+}
+
+/// How a tool's output is shrunk before it enters the history
+///
+/// Unset fields fall back to the agent-wide policy (see `merged`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputPolicy {
+    /// Maximum characters of output
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+    /// Maximum tokens of output (estimated at 4 characters per token)
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    /// Part to keep when truncating (default: head_tail)
+    #[serde(default)]
+    pub truncate: Option<TruncateMode>,
+    /// Keep only these JSON fields; dotted paths select nested fields and
+    /// apply to every element of arrays (e.g. `issues.key`)
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    /// Summarize oversized output with the summary model instead of truncating
+    #[serde(default)]
+    pub summarize: Option<bool>,
+}
+
+impl OutputPolicy {
+    /// This policy with unset fields taken from `defaults`
+    pub fn merged(&self, defaults: &OutputPolicy) -> OutputPolicy {
+        OutputPolicy {
+            max_chars: self.max_chars.or(defaults.max_chars),
+            max_tokens: self.max_tokens.or(defaults.max_tokens),
+            truncate: self.truncate.or(defaults.truncate),
+            fields: self.fields.clone().or_else(|| defaults.fields.clone()),
+            summarize: self.summarize.or(defaults.summarize),
+        }
+    }
+
+    /// Effective character limit, if any
+    pub fn limit(&self) -> Option<usize> {
+        let from_tokens = self.max_tokens.map(|t| t.saturating_mul(CHARS_PER_TOKEN));
+        match (self.max_chars, from_tokens) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        self.limit().is_none() && self.fields.is_none()
+    }
+}
+
+/// Full tool outputs that were cut short, addressable by handle
+#[derive(Default)]
+pub struct OutputStore {
+    entries: Mutex<StoreEntries>,
+}
+
+#[derive(Default)]
+struct StoreEntries {
+    next_id: usize,
+    outputs: VecDeque<StoredOutput>,
+}
+
+struct StoredOutput {
+    handle: String,
+    output: String,
+    /// Character where the part already shown to the model resumes
+    /// (the kept tail); the output's length if nothing after the cut was shown
+    omitted_end: usize,
+}
+
+impl OutputStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `output` and return its handle; the oldest outputs are evicted
+    ///
+    /// `omitted_end` is where the part the model has already seen resumes, so
+    /// paging through the omitted middle stops before the kept tail.
+    pub fn insert(&self, output: String, omitted_end: usize) -> String {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.next_id += 1;
+        let handle = format!("out-{}", entries.next_id);
+        entries.outputs.push_back(StoredOutput {
+            handle: handle.clone(),
+            output,
+            omitted_end,
+        });
+        while entries.outputs.len() > MAX_STORED_OUTPUTS {
+            entries.outputs.pop_front();
+        }
+        handle
+    }
+
+    /// Up to `len` characters of a stored output from `offset`, with the
+    /// offset the page stops at and the output's total length
+    ///
+    /// Pages starting in the omitted part stop where it ends.
+    pub fn read(&self, handle: &str, offset: usize, len: usize) -> Option<(String, usize, usize)> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let stored = entries.outputs.iter().find(|o| o.handle == handle)?;
+        let total = stored.output.chars().count();
+        let stop = if offset < stored.omitted_end {
+            stored.omitted_end.min(total)
+        } else {
+            total
+        };
+        let len = len.min(stop.saturating_sub(offset));
+        let page = stored.output.chars().skip(offset).take(len).collect();
+        Some((page, stop, total))
+    }
+}
+
+/// Tool whose output is shrunk according to an `OutputPolicy`
+pub struct LimitedTool {
+    inner: Arc<dyn Tool>,
+    policy: OutputPolicy,
+    store: Arc<OutputStore>,
+    summarizer: Option<Arc<dyn Model>>,
+}
+
+impl LimitedTool {
+    pub fn new(inner: Arc<dyn Tool>, policy: OutputPolicy, store: Arc<OutputStore>) -> Self {
+        Self {
+            inner,
+            policy,
+            store,
+            summarizer: None,
+        }
+    }
+
+    /// Model used when the policy asks for summaries
+    pub fn with_summarizer(mut self, model: Arc<dyn Model>) -> Self {
+        self.summarizer = Some(model);
+        self
+    }
+
+    async fn summarize(
+        &self,
+        model: &dyn Model,
+        text: &str,
+        limit: usize,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let prompt = format!(
+            "Summarize the following output of the tool `{}` in at most {} characters. \
+             Keep identifiers, numbers, names and anything an agent would need to act on.\n\n{}",
+            self.inner.name(),
+            limit,
+            text
+        );
+        let response = model
+            .generate_content(
+                &[Content {
+                    role: "user".to_string(),
+                    parts: vec![Part::Text(prompt)],
+                }],
+                None,
+                None,
+            )
+            .await?;
+        let summary: String = response
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        Ok(summary)
+    }
+
+    /// Apply the policy to a result without media
+    async fn limit(&self, mut output: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        if let Some(fields) = &self.policy.fields {
+            output = project(&output, fields);
+        }
+
+        let Some(limit) = self.policy.limit() else {
+            return Ok(output);
+        };
+        let text = match &output {
+            Value::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other)?,
+        };
+        let total_chars = text.chars().count();
+        if total_chars <= limit {
+            return Ok(output);
+        }
+
+        log::info!(
+            "Tool {} output of {} characters exceeds {}",
+            self.inner.name(),
+            total_chars,
+            limit
+        );
+
+        if self.policy.summarize == Some(true) {
+            match &self.summarizer {
+                Some(model) => match self.summarize(model.as_ref(), &text, limit).await {
+                    Ok(summary) => {
+                        // None of the output was shown, so all of it is omitted
+                        let handle = self.store.insert(text, total_chars);
+                        return Ok(json!({
+                            "summary": summary,
+                            "truncated": true,
+                            "total_chars": total_chars,
+                            "handle": handle,
+                            "note": format!(
+                                "This is a summary. Call {} with handle \"{}\" to read the full output.",
+                                READ_OUTPUT_TOOL, handle
+                            ),
+                        }));
+                    }
+                    Err(e) => log::warn!(
+                        "Summarizing output of tool {} failed, truncating instead: {}",
+                        self.inner.name(),
+                        e
+                    ),
+                },
+                None => log::warn!(
+                    "Tool {} output policy asks for a summary but no summary model is set",
+                    self.inner.name()
+                ),
+            }
+        }
+
+        let mode = self.policy.truncate.unwrap_or_default();
+        let handle = self
+            .store
+            .insert(text.clone(), total_chars - kept_tail(limit, mode));
+        let (kept, next_offset) = truncate(&text, limit, mode, &handle);
+        Ok(json!({
+            "output": kept,
+            "truncated": true,
+            "total_chars": total_chars,
+            "handle": handle,
+            "next_offset": next_offset,
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for LimitedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn schema(&self) -> &Value {
+        self.inner.schema()
+    }
+
+    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        // Attached media is passed through untouched
+        let (output, media) = split_media(self.inner.execute(input).await?);
+        Ok(with_media(self.limit(output).await?, media))
+    }
+}
+
+/// Cut `text` to about `limit` characters with a marker explaining how to read the rest
+///
+/// Returns the kept text and the offset of the first omitted character.
+fn truncate(text: &str, limit: usize, mode: TruncateMode, handle: &str) -> (String, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    let total = chars.len();
+    let slice = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+
+    let tail = kept_tail(limit, mode);
+    let head = limit - tail;
+    let omitted = total - head - tail;
+    let marker = format!(
+        "[... {} characters omitted; call {} with handle \"{}\" and offset {} to read them ...]",
+        omitted, READ_OUTPUT_TOOL, handle, head
+    );
+
+    let mut kept = String::new();
+    if head > 0 {
+        kept.push_str(&slice(0, head));
+        kept.push('\n');
+    }
+    kept.push_str(&marker);
+    if tail > 0 {
+        kept.push('\n');
+        kept.push_str(&slice(total - tail, total));
+    }
+    (kept, head)
+}
+
+/// Characters kept from the end of an output cut to `limit`
+fn kept_tail(limit: usize, mode: TruncateMode) -> usize {
+    match mode {
+        TruncateMode::Head => 0,
+        TruncateMode::Tail => limit,
+        TruncateMode::HeadTail => limit / 2,
+    }
+}
+
+/// Keep only the dotted `fields` of `value`
+fn project(value: &Value, fields: &[String]) -> Value {
+    #[derive(Default)]
+    struct Node(HashMap<String, Node>);
+
+    fn apply(value: &Value, node: &Node) -> Value {
+        if node.0.is_empty() {
+            return value.clone();
+        }
+        match value {
+            Value::Array(items) => Value::Array(items.iter().map(|v| apply(v, node)).collect()),
+            Value::Object(obj) => {
+                let mut out = Map::new();
+                for (key, child) in &node.0 {
+                    if let Some(v) = obj.get(key) {
+                        out.insert(key.clone(), apply(v, child));
+                    }
+                }
+                Value::Object(out)
+            }
+            other => other.clone(),
+        }
+    }
+
+    let mut root = Node::default();
+    for field in fields {
+        let mut node = &mut root;
+        for part in field.split('.') {
+            node = node.0.entry(part.to_string()).or_default();
+        }
+    }
+    apply(value, &root)
+}
+
+/// Tool that returns more of an output cut short by `LimitedTool`
+pub struct ReadOutputTool {
+    store: Arc<OutputStore>,
+    schema: Value,
+}
+
+impl ReadOutputTool {
+    pub fn new(store: Arc<OutputStore>) -> Self {
+        Self {
+            store,
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "handle": {
+                        "type": "string",
+                        "description": "Handle of the truncated output (e.g. out-1)"
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Character offset to start reading at"
+                    },
+                    "length": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Number of characters to read (default 4000)"
+                    }
+                },
+                "required": ["handle"]
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ReadOutputTool {
+    fn name(&self) -> &str {
+        READ_OUTPUT_TOOL
+    }
+
+    fn description(&self) -> &str {
+        "Reads more of a tool output that was truncated or summarized, by its handle."
+    }
+
+    fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let handle = input["handle"].as_str().ok_or("handle is required")?;
+        let offset = input["offset"].as_u64().unwrap_or(0) as usize;
+        let length = input["length"]
+            .as_u64()
+            .map_or(DEFAULT_PAGE_CHARS, |l| l as usize);
+
+        let (content, stop, total) = self
+            .store
+            .read(handle, offset, length)
+            .ok_or_else(|| format!("Unknown or expired output handle '{}'", handle))?;
+        let end = (offset + content.chars().count()).min(stop);
+        Ok(json!({
+            "content": content,
+            "offset": offset,
+            "next_offset": (end < stop).then_some(end),
+            "total_chars": total,
+        }))
+    }
+}
+
+/// Wrap `tools` in their output policies
+///
+/// `overrides` holds per-tool policies, merged over `default`. Tools without
+/// any limit or projection are returned unwrapped. If any tool is wrapped,
+/// `read_tool_output` is added so the agent can page through cut outputs.
+pub fn apply_output_policies(
+    tools: Vec<Arc<dyn Tool>>,
+    default: &OutputPolicy,
+    overrides: &HashMap<String, OutputPolicy>,
+    summarizer: Option<Arc<dyn Model>>,
+) -> Vec<Arc<dyn Tool>> {
+    let store = Arc::new(OutputStore::new());
+    let mut wrapped_any = false;
+
+    let mut tools: Vec<Arc<dyn Tool>> = tools
+        .into_iter()
+        .map(|tool| {
+            let policy = match overrides.get(tool.name()) {
+                Some(policy) => policy.merged(default),
+                None => default.clone(),
+            };
+            if policy.is_noop() {
+                return tool;
+            }
+            wrapped_any = true;
+            let summarize = policy.summarize == Some(true);
+            let mut limited = LimitedTool::new(tool, policy, store.clone());
+            if let Some(model) = summarizer.as_ref().filter(|_| summarize) {
+                limited = limited.with_summarizer(model.clone());
+            }
+            Arc::new(limited) as Arc<dyn Tool>
+        })
+        .collect();
+
+    if wrapped_any && !tools.iter().any(|t| t.name() == READ_OUTPUT_TOOL) {
+        tools.push(Arc::new(ReadOutputTool::new(store)));
+    }
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::model::GenerationConfig;
+
+    struct FixedTool(Value);
+
+    #[async_trait]
+    impl Tool for FixedTool {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn description(&self) -> &str {
+            "Returns a fixed value"
+        }
+
+        fn schema(&self) -> &Value {
+            static SCHEMA: once_cell::sync::Lazy<Value> =
+                once_cell::sync::Lazy::new(|| json!({"type": "object"}));
+            &SCHEMA
+        }
+
+        async fn execute(&self, _input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct SummaryModel;
+
+    #[async_trait]
+    impl Model for SummaryModel {
+        async fn generate_content(
+            &self,
+            _history: &[Content],
+            _config: Option<&GenerationConfig>,
+            _tools: Option<&[Arc<dyn Tool>]>,
+        ) -> Result<Content, Box<dyn Error + Send + Sync>> {
+            Ok(Content {
+                role: "model".to_string(),
+                parts: vec![Part::Text("short summary".to_string())],
+            })
+        }
+    }
+
+    fn limited(output: Value, policy: OutputPolicy) -> (Vec<Arc<dyn Tool>>, Arc<dyn Tool>) {
+        let tools = apply_output_policies(
+            vec![Arc::new(FixedTool(output))],
+            &policy,
+            &HashMap::new(),
+            Some(Arc::new(SummaryModel)),
+        );
+        let read = tools[1].clone();
+        (tools, read)
+    }
+
+    #[tokio::test]
+    async fn test_head_tail_truncation_and_paging() {
+        let text: String = ('a'..='z').collect();
+        let policy = OutputPolicy {
+            max_chars: Some(10),
+            ..Default::default()
+        };
+        let (tools, read) = limited(json!(text), policy);
+        assert_eq!(read.name(), READ_OUTPUT_TOOL);
+
+        let result = tools[0].execute(json!({})).await.unwrap();
+        let output = result["output"].as_str().unwrap();
+        assert!(output.starts_with("abcde\n[... 16 characters omitted"));
+        assert!(output.ends_with("\nvwxyz"));
+        assert_eq!(result["total_chars"], 26);
+        assert_eq!(result["next_offset"], 5);
+
+        let page = read
+            .execute(json!({"handle": result["handle"], "offset": 5, "length": 10}))
+            .await
+            .unwrap();
+        assert_eq!(page["content"], "fghijklmno");
+        assert_eq!(page["next_offset"], 15);
+
+        // Paging through the omitted middle stops before the tail already shown
+        let page = read
+            .execute(json!({"handle": result["handle"], "offset": 15}))
+            .await
+            .unwrap();
+        assert_eq!(page["content"], "pqrstu");
+        assert_eq!(page["next_offset"], Value::Null);
+
+        // The tail can still be read explicitly
+        let page = read
+            .execute(json!({"handle": result["handle"], "offset": 21}))
+            .await
+            .unwrap();
+        assert_eq!(page["content"], "vwxyz");
+
+        assert!(read.execute(json!({"handle": "out-999"})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_media_is_not_truncated() {
+        let image = Part::Image(crate::adk::model::Media::from_bytes("image/png", &[0; 64]));
+        let policy = OutputPolicy {
+            max_chars: Some(10),
+            ..Default::default()
+        };
+        let (tools, _) = limited(with_media(json!("short"), vec![image]), policy);
+
+        let (result, media) = split_media(tools[0].execute(json!({})).await.unwrap());
+        assert_eq!(result, json!("short"));
+        assert_eq!(media.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_field_projection() {
+        let output = json!({
+            "total": 2,
+            "issues": [
+                {"key": "A-1", "summary": "One", "body": "long"},
+                {"key": "A-2", "summary": "Two", "body": "long"}
+            ]
+        });
+        let policy = OutputPolicy {
+            fields: Some(vec!["total".to_string(), "issues.key".to_string()]),
+            ..Default::default()
+        };
+        let (tools, _) = limited(output, policy);
+        let result = tools[0].execute(json!({})).await.unwrap();
+        assert_eq!(
+            result,
+            json!({"total": 2, "issues": [{"key": "A-1"}, {"key": "A-2"}]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summarize_oversized_output() {
+        let policy = OutputPolicy {
+            max_tokens: Some(2),
+            summarize: Some(true),
+            ..Default::default()
+        };
+        let (tools, _) = limited(json!("a long tool output"), policy);
+        let result = tools[0].execute(json!({})).await.unwrap();
+        assert_eq!(result["summary"], "short summary");
+        assert_eq!(result["truncated"], true);
+    }
+
+    #[test]
+    fn test_per_tool_policy_overrides_default() {
+        let default = OutputPolicy {
+            max_chars: Some(100),
+            truncate: Some(TruncateMode::Tail),
+            ..Default::default()
+        };
+        let over = OutputPolicy {
+            max_chars: Some(5000),
+            ..Default::default()
+        };
+        let merged = over.merged(&default);
+        assert_eq!(merged.limit(), Some(5000));
+        assert_eq!(merged.truncate, Some(TruncateMode::Tail));
+
+        let tools = apply_output_policies(
+            vec![Arc::new(FixedTool(json!(1)))],
+            &OutputPolicy::default(),
+            &HashMap::new(),
+            None,
+        );
+        assert_eq!(tools.len(), 1, "no policy, no wrapping");
+    }
+}
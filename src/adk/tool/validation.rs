@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: MIT
+
+//! Tool argument validation against the tool's JSON schema
+
+use jsonschema::Validator;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+/// Compiled validators by schema fingerprint; `None` for schemas that don't
+/// compile
+type Validators = HashMap<u64, Option<Arc<Validator>>>;
+
+static VALIDATORS: Lazy<RwLock<Validators>> = Lazy::new(Default::default);
+
+/// Schemas to keep compiled before the cache is cleared
+const MAX_VALIDATORS: usize = 512;
+
+/// One way in which tool arguments don't match the schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgumentError {
+    /// JSON pointer to the offending value ("" for the arguments object itself)
+    pub path: String,
+    pub message: String,
+}
+
+/// Check `args` against `schema`
+///
+/// Schemas are compiled once and reused across calls. Schemas that can't be
+/// compiled are not enforced, so a tool with an unusual schema still works
+/// rather than rejecting every call.
+pub fn validate_args(schema: &Value, args: &Value) -> Result<(), Vec<ArgumentError>> {
+    match cached_validator(schema) {
+        Some(validator) => check(&validator, args),
+        None => Ok(()),
+    }
+}
+
+fn cached_validator(schema: &Value) -> Option<Arc<Validator>> {
+    let key = fingerprint(schema);
+    if let Some(validator) = VALIDATORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+    {
+        return validator.clone();
+    }
+    let validator = compile(schema).map(Arc::new);
+    let mut validators = VALIDATORS.write().unwrap_or_else(|e| e.into_inner());
+    if validators.len() >= MAX_VALIDATORS {
+        validators.clear();
+    }
+    validators.insert(key, validator.clone());
+    validator
+}
+
+/// Hash of a schema's structure, without serializing it
+fn fingerprint(schema: &Value) -> u64 {
+    fn feed(value: &Value, state: &mut DefaultHasher) {
+        match value {
+            Value::Null => 0u8.hash(state),
+            Value::Bool(b) => (1u8, b).hash(state),
+            Value::Number(n) => (2u8, n).hash(state),
+            Value::String(s) => (3u8, s).hash(state),
+            Value::Array(items) => {
+                (4u8, items.len()).hash(state);
+                items.iter().for_each(|v| feed(v, state));
+            }
+            Value::Object(map) => {
+                (5u8, map.len()).hash(state);
+                for (k, v) in map {
+                    k.hash(state);
+                    feed(v, state);
+                }
+            }
+        }
+    }
+    let mut state = DefaultHasher::new();
+    feed(schema, &mut state);
+    state.finish()
+}
+
+fn compile(schema: &Value) -> Option<Validator> {
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => Some(validator),
+        Err(e) => {
+            log::debug!(
+                "Skipping argument validation, schema does not compile: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+fn check(validator: &Validator, args: &Value) -> Result<(), Vec<ArgumentError>> {
+    let errors: Vec<ArgumentError> = validator
+        .iter_errors(args)
+        .map(|e| ArgumentError {
+            path: e.instance_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "owner": { "type": "string" },
+                "pr_number": { "type": "integer" }
+            },
+            "required": ["owner", "pr_number"]
+        })
+    }
+
+    #[test]
+    fn test_valid_args() {
+        assert!(validate_args(&schema(), &json!({"owner": "o", "pr_number": 1})).is_ok());
+    }
+
+    #[test]
+    fn test_reports_each_error_with_path() {
+        let errors = validate_args(&schema(), &json!({"pr_number": "42"})).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .any(|e| e.path.is_empty() && e.message.contains("owner")));
+        assert!(errors
+            .iter()
+            .any(|e| e.path == "/pr_number" && e.message.contains("integer")));
+    }
+
+    #[test]
+    fn test_validators_are_cached_per_schema() {
+        let first = cached_validator(&schema()).unwrap();
+        assert!(Arc::ptr_eq(&first, &cached_validator(&schema()).unwrap()));
+        assert!(validate_args(&schema(), &json!({"owner": "o"})).is_err());
+
+        // A different schema gets its own validator
+        let optional = json!({"type": "object", "title": "test_validation_cache"});
+        let second = cached_validator(&optional).unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert!(validate_args(&optional, &json!({"owner": "o"})).is_ok());
+    }
+
+    #[test]
+    fn test_uncompilable_schema_is_not_enforced() {
+        let schema = json!({"type": "not-a-type"});
+        assert!(validate_args(&schema, &json!({"anything": true})).is_ok());
+    }
+}
@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: MIT
+
+//! Human-in-the-loop input
+//!
+//! Parts of a run that need an answer from the user (such as MCP
+//! elicitation requests) go through a [`HumanInput`]. The CLI answers on the
+//! terminal with [`ConsoleInput`]; without one, requests are declined.
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::io::Write;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+use tokio::sync::Mutex;
+
+/// A question for the user
+#[derive(Debug, Clone)]
+pub struct HumanRequest {
+    /// Who is asking, e.g. the MCP server name
+    pub source: String,
+    pub message: String,
+    /// JSON schema of the answer: an object with primitive properties
+    pub schema: Value,
+}
+
+/// The user's reply to a [`HumanRequest`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum HumanResponse {
+    /// Answered with an object matching the request schema
+    Accept(Value),
+    /// Chose not to answer; the caller may continue without the data
+    Decline,
+    /// Abandoned the operation that asked
+    Cancel,
+}
+
+/// Channel for asking the user questions while an agent runs
+#[async_trait]
+pub trait HumanInput: Send + Sync {
+    async fn ask(
+        &self,
+        request: &HumanRequest,
+    ) -> Result<HumanResponse, Box<dyn Error + Send + Sync>>;
+}
+
+/// Asks on the terminal, one prompt per schema property
+///
+/// Questions asked at the same time (e.g. by parallel tool calls) wait for
+/// each other, so their prompts and answers don't interleave. Other terminal
+/// input, such as chat messages, should be read with [`ConsoleInput::read_line`]
+/// so that no line is buffered by a second reader.
+#[derive(Default)]
+pub struct ConsoleInput {
+    /// stdin, opened on first use and shared by every question
+    lines: Mutex<Option<Lines<BufReader<Stdin>>>>,
+}
+
+impl ConsoleInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the next line of stdin; `None` at end of input
+    pub async fn read_line(&self) -> std::io::Result<Option<String>> {
+        let mut stdin = self.lines.lock().await;
+        stdin
+            .get_or_insert_with(|| BufReader::new(tokio::io::stdin()).lines())
+            .next_line()
+            .await
+    }
+}
+
+#[async_trait]
+impl HumanInput for ConsoleInput {
+    async fn ask(
+        &self,
+        request: &HumanRequest,
+    ) -> Result<HumanResponse, Box<dyn Error + Send + Sync>> {
+        let mut stdin = self.lines.lock().await;
+        let lines = stdin.get_or_insert_with(|| BufReader::new(tokio::io::stdin()).lines());
+        println!("\n[{}] {}", request.source, request.message);
+        print!("Answer? [y]es / [n]o / [c]ancel: ");
+        std::io::stdout().flush()?;
+        match lines.next_line().await?.as_deref().map(str::trim) {
+            Some("y" | "Y" | "yes") => {}
+            Some("c" | "C" | "cancel") | None => return Ok(HumanResponse::Cancel),
+            Some(_) => return Ok(HumanResponse::Decline),
+        }
+
+        let required: Vec<&str> = request.schema["required"]
+            .as_array()
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let mut answer = Map::new();
+        if let Some(properties) = request.schema["properties"].as_object() {
+            for (name, schema) in properties {
+                let is_required = required.contains(&name.as_str());
+                loop {
+                    print!("{}{}: ", name, field_hint(schema, is_required));
+                    std::io::stdout().flush()?;
+                    let Some(line) = lines.next_line().await? else {
+                        return Ok(HumanResponse::Cancel);
+                    };
+                    let line = line.trim();
+                    if line.is_empty() && !is_required {
+                        break;
+                    }
+                    match parse_field(line, schema) {
+                        Ok(value) => {
+                            answer.insert(name.clone(), value);
+                            break;
+                        }
+                        Err(e) => println!("  {}", e),
+                    }
+                }
+            }
+        }
+        Ok(HumanResponse::Accept(Value::Object(answer)))
+    }
+}
+
+/// Description, type and choices shown after a field name
+fn field_hint(schema: &Value, required: bool) -> String {
+    let mut hint = String::new();
+    if let Some(description) = schema["description"].as_str() {
+        hint.push_str(&format!(" ({})", description));
+    }
+    match schema["enum"].as_array() {
+        Some(choices) => {
+            let choices: Vec<_> = choices.iter().filter_map(Value::as_str).collect();
+            hint.push_str(&format!(" [{}]", choices.join("|")));
+        }
+        None => hint.push_str(&format!(
+            " [{}]",
+            schema["type"].as_str().unwrap_or("string")
+        )),
+    }
+    if !required {
+        hint.push_str(" (optional)");
+    }
+    hint
+}
+
+/// Convert a typed-in answer to the JSON type the property asks for
+fn parse_field(input: &str, schema: &Value) -> Result<Value, String> {
+    if let Some(choices) = schema["enum"].as_array() {
+        return choices
+            .iter()
+            .find(|c| c.as_str() == Some(input))
+            .cloned()
+            .ok_or_else(|| "not one of the listed choices".to_string());
+    }
+    match schema["type"].as_str().unwrap_or("string") {
+        "boolean" => match input.to_ascii_lowercase().as_str() {
+            "y" | "yes" | "true" => Ok(Value::Bool(true)),
+            "n" | "no" | "false" => Ok(Value::Bool(false)),
+            _ => Err("expected yes or no".to_string()),
+        },
+        "integer" => input
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| "expected a whole number".to_string()),
+        "number" => input
+            .parse::<f64>()
+            .map(Value::from)
+            .map_err(|_| "expected a number".to_string()),
+        _ if input.is_empty() => Err("a value is required".to_string()),
+        _ => Ok(Value::String(input.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_field() {
+        assert_eq!(
+            parse_field("yes", &json!({"type": "boolean"})),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            parse_field("42", &json!({"type": "integer"})),
+            Ok(json!(42))
+        );
+        assert_eq!(
+            parse_field("1.5", &json!({"type": "number"})),
+            Ok(json!(1.5))
+        );
+        assert_eq!(
+            parse_field("main", &json!({"type": "string"})),
+            Ok(json!("main"))
+        );
+        assert_eq!(
+            parse_field("eu", &json!({"type": "string", "enum": ["us", "eu"]})),
+            Ok(json!("eu"))
+        );
+        assert!(parse_field("apac", &json!({"type": "string", "enum": ["us", "eu"]})).is_err());
+        assert!(parse_field("4.2", &json!({"type": "integer"})).is_err());
+        assert!(parse_field("", &json!({"type": "string"})).is_err());
+    }
+
+    #[test]
+    fn test_field_hint() {
+        assert_eq!(
+            field_hint(&json!({"type": "integer", "description": "Port"}), true),
+            " (Port) [integer]"
+        );
+        assert_eq!(
+            field_hint(&json!({"type": "string", "enum": ["us", "eu"]}), false),
+            " [us|eu] (optional)"
+        );
+    }
+}
@@ -0,0 +1,481 @@
+// SPDX-License-Identifier: MIT
+
+//! Client side of an MCP connection
+//!
+//! [`McpClientHandler`] answers the requests servers send back to us:
+//! `sampling/createMessage` is run on the model of the agent calling the
+//! server's tool (or a configured fallback model), `roots/list` returns
+//! the configured directories, and elicitation goes to the [`HumanInput`]
+//! channel. Progress notifications for a tool call are forwarded to the
+//! calling agent as `AgentEvent::Log`.
+
+use crate::adk::agent::AgentEvent;
+use crate::adk::model::{Content, GenerationConfig, Media, MediaSource, Model, Part};
+use crate::kinetic::human::{HumanInput, HumanRequest, HumanResponse};
+use rmcp::model::{
+    ClientCapabilities, ClientInfo, Content as McpContent, CreateElicitationRequestParam,
+    CreateElicitationResult, CreateMessageRequestParam, CreateMessageResult, ElicitationAction,
+    ElicitationCapability, Implementation, ListRootsResult, NumberOrString,
+    ProgressNotificationParam, ProgressToken, RawContent, Role, Root, RootsCapabilities,
+    SamplingMessage,
+};
+use rmcp::service::{NotificationContext, RequestContext, RoleClient};
+use rmcp::{ClientHandler, ErrorData as McpError};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::mpsc;
+
+/// Model that serves a server's sampling requests
+#[derive(Clone)]
+struct SamplingModel {
+    name: String,
+    model: Arc<dyn Model>,
+}
+
+/// Where progress for one in-flight request goes
+struct ProgressRoute {
+    label: String,
+    tx: mpsc::Sender<AgentEvent>,
+}
+
+type ProgressRoutes = Arc<Mutex<HashMap<ProgressToken, ProgressRoute>>>;
+
+/// Models of the agents with a tool call in flight, oldest first
+type Callers = Arc<Mutex<Vec<(u64, Arc<dyn Model>)>>>;
+
+/// Handler for requests and notifications from one MCP server
+///
+/// Clones share the sampling model, callers and progress routes, so the
+/// handler of a reconnected service keeps serving the same agents.
+#[derive(Clone)]
+pub struct McpClientHandler {
+    server: String,
+    /// Fallback for sampling outside an agent's tool call
+    sampling: Arc<RwLock<Option<SamplingModel>>>,
+    callers: Callers,
+    next_caller: Arc<AtomicU64>,
+    roots: Vec<Root>,
+    human: Option<Arc<dyn HumanInput>>,
+    progress: ProgressRoutes,
+}
+
+impl McpClientHandler {
+    pub fn new(server: impl Into<String>) -> Self {
+        Self {
+            server: server.into(),
+            sampling: Arc::new(RwLock::new(None)),
+            callers: Arc::new(Mutex::new(Vec::new())),
+            next_caller: Arc::new(AtomicU64::new(0)),
+            roots: Vec::new(),
+            human: None,
+            progress: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Directories offered to the server as `file://` roots
+    pub fn with_roots(mut self, paths: &[String]) -> Self {
+        self.roots = paths.iter().map(|path| root_for(path)).collect();
+        self
+    }
+
+    /// Ask the user through `human` when the server elicits input
+    pub fn with_human_input(mut self, human: Arc<dyn HumanInput>) -> Self {
+        self.human = Some(human);
+        self
+    }
+
+    /// Serve sampling requests with `model`, reported to the server as `name`,
+    /// when no agent's tool call is in flight
+    pub fn set_sampling_model(&self, name: impl Into<String>, model: Arc<dyn Model>) {
+        *self.sampling.write().unwrap() = Some(SamplingModel {
+            name: name.into(),
+            model,
+        });
+    }
+
+    /// Serve sampling requests with `model`, the calling agent's, until the
+    /// guard is dropped
+    ///
+    /// The server's requests do not say which call they belong to, so with
+    /// several calls in flight the most recent caller's model answers.
+    pub fn track_caller(&self, model: Arc<dyn Model>) -> CallerGuard {
+        let id = self.next_caller.fetch_add(1, Ordering::Relaxed);
+        self.callers.lock().unwrap().push((id, model));
+        CallerGuard {
+            id,
+            callers: self.callers.clone(),
+        }
+    }
+
+    /// Model for a sampling request: the latest caller's, else the fallback
+    fn sampling_model(&self) -> Option<SamplingModel> {
+        if let Some((_, model)) = self.callers.lock().unwrap().last() {
+            let name = match model.name() {
+                "" => "kinetic".to_string(),
+                name => name.to_string(),
+            };
+            return Some(SamplingModel {
+                name,
+                model: model.clone(),
+            });
+        }
+        self.sampling.read().unwrap().clone()
+    }
+
+    /// Send progress reported under the returned token to `tx`, labelled
+    /// `label`, until the guard is dropped
+    pub fn track_progress(
+        &self,
+        label: impl Into<String>,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> ProgressGuard {
+        let token = ProgressToken(NumberOrString::String(
+            uuid::Uuid::new_v4().to_string().into(),
+        ));
+        self.progress.lock().unwrap().insert(
+            token.clone(),
+            ProgressRoute {
+                label: label.into(),
+                tx,
+            },
+        );
+        ProgressGuard {
+            token,
+            routes: self.progress.clone(),
+        }
+    }
+}
+
+/// Keeps a progress route registered; see [`McpClientHandler::track_progress`]
+pub struct ProgressGuard {
+    token: ProgressToken,
+    routes: ProgressRoutes,
+}
+
+impl ProgressGuard {
+    /// Token to send as `progressToken` with the request
+    pub fn token(&self) -> &ProgressToken {
+        &self.token
+    }
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        self.routes.lock().unwrap().remove(&self.token);
+    }
+}
+
+/// Keeps a caller's model registered; see [`McpClientHandler::track_caller`]
+pub struct CallerGuard {
+    id: u64,
+    callers: Callers,
+}
+
+impl Drop for CallerGuard {
+    fn drop(&mut self) {
+        self.callers
+            .lock()
+            .unwrap()
+            .retain(|(id, _)| *id != self.id);
+    }
+}
+
+impl ClientHandler for McpClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo {
+            protocol_version: Default::default(),
+            capabilities: ClientCapabilities {
+                // The model is assigned after connecting, so sampling is always offered
+                sampling: Some(Default::default()),
+                roots: (!self.roots.is_empty()).then(RootsCapabilities::default),
+                elicitation: self
+                    .human
+                    .as_ref()
+                    .map(|_| ElicitationCapability::default()),
+                ..Default::default()
+            },
+            client_info: Implementation {
+                name: "kinetic-rs".to_string(),
+                version: "0.1.0".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, McpError> {
+        let sampling = self.sampling_model().ok_or_else(|| {
+            McpError::invalid_request(
+                format!("No model is configured for sampling by '{}'", self.server),
+                None,
+            )
+        })?;
+        log::info!(
+            "MCP server '{}' requested sampling with {} message(s)",
+            self.server,
+            params.messages.len()
+        );
+
+        let config = GenerationConfig {
+            temperature: params.temperature,
+            max_output_tokens: Some(params.max_tokens),
+            ..Default::default()
+        };
+        let response = sampling
+            .model
+            .generate_content(&sampling_history(&params), Some(&config), None)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Sampling failed: {}", e), None))?;
+        Ok(sampling_result(&sampling.name, &response))
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, McpError> {
+        Ok(ListRootsResult {
+            roots: self.roots.clone(),
+        })
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, McpError> {
+        let Some(human) = &self.human else {
+            log::warn!(
+                "Declining input request from MCP server '{}': no one to ask",
+                self.server
+            );
+            return Ok(CreateElicitationResult {
+                action: ElicitationAction::Decline,
+                content: None,
+            });
+        };
+
+        let request = HumanRequest {
+            source: self.server.clone(),
+            message: request.message,
+            schema: serde_json::to_value(&request.requested_schema).unwrap_or_default(),
+        };
+        let response = human.ask(&request).await.map_err(|e| {
+            McpError::internal_error(format!("Asking the user failed: {}", e), None)
+        })?;
+        Ok(match response {
+            HumanResponse::Accept(content) => CreateElicitationResult {
+                action: ElicitationAction::Accept,
+                content: Some(content),
+            },
+            HumanResponse::Decline => CreateElicitationResult {
+                action: ElicitationAction::Decline,
+                content: None,
+            },
+            HumanResponse::Cancel => CreateElicitationResult {
+                action: ElicitationAction::Cancel,
+                content: None,
+            },
+        })
+    }
+
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let route = self
+            .progress
+            .lock()
+            .unwrap()
+            .get(&params.progress_token)
+            .map(|route| (route.label.clone(), route.tx.clone()));
+        if let Some((label, tx)) = route {
+            let _ = tx
+                .send(AgentEvent::Log(progress_text(&label, &params)))
+                .await;
+        }
+    }
+}
+
+/// A `file://` root for a local directory
+fn root_for(path: &str) -> Root {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    Root {
+        uri: format!("file://{}", path.display()),
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()),
+    }
+}
+
+/// Conversation for a sampling request, system prompt first
+fn sampling_history(params: &CreateMessageRequestParam) -> Vec<Content> {
+    let mut history = Vec::with_capacity(params.messages.len() + 1);
+    if let Some(system) = &params.system_prompt {
+        history.push(Content {
+            role: "system".to_string(),
+            parts: vec![Part::Text(system.clone())],
+        });
+    }
+    for message in &params.messages {
+        let part = match &message.content.raw {
+            RawContent::Text(text) => Part::Text(text.text.clone()),
+            RawContent::Image(image) => Part::Image(Media {
+                mime_type: image.mime_type.clone(),
+                source: MediaSource::Base64(image.data.clone()),
+                name: None,
+            }),
+            RawContent::Audio(audio) => Part::File(Media {
+                mime_type: audio.mime_type.clone(),
+                source: MediaSource::Base64(audio.data.clone()),
+                name: None,
+            }),
+            RawContent::Resource(resource) => Part::Text(
+                crate::kinetic::mcp::resource::resource_text(&rmcp::model::ReadResourceResult {
+                    contents: vec![resource.resource.clone()],
+                }),
+            ),
+            RawContent::ResourceLink(link) => Part::Text(format!("[resource {}]", link.uri)),
+        };
+        history.push(Content {
+            role: match message.role {
+                Role::User => "user",
+                Role::Assistant => "model",
+            }
+            .to_string(),
+            parts: vec![part],
+        });
+    }
+    history
+}
+
+/// The model's reply as a sampling result
+fn sampling_result(model: &str, response: &Content) -> CreateMessageResult {
+    let text: String = response
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    CreateMessageResult {
+        model: model.to_string(),
+        stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+        message: SamplingMessage {
+            role: Role::Assistant,
+            content: McpContent::text(text),
+        },
+    }
+}
+
+/// One-line description of a progress notification
+fn progress_text(label: &str, params: &ProgressNotificationParam) -> String {
+    let mut text = match params.total {
+        Some(total) => format!("{}: {}/{}", label, params.progress, total),
+        None => format!("{}: {}", label, params.progress),
+    };
+    if let Some(message) = &params.message {
+        text.push_str(&format!(" {}", message));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_history() {
+        let params = CreateMessageRequestParam {
+            messages: vec![
+                SamplingMessage {
+                    role: Role::User,
+                    content: McpContent::text("Summarize this"),
+                },
+                SamplingMessage {
+                    role: Role::Assistant,
+                    content: McpContent::image("aGk=", "image/png"),
+                },
+            ],
+            model_preferences: None,
+            system_prompt: Some("Be brief.".to_string()),
+            include_context: None,
+            temperature: None,
+            max_tokens: 100,
+            stop_sequences: None,
+            metadata: None,
+        };
+        let history = sampling_history(&params);
+        let roles: Vec<_> = history.iter().map(|c| c.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "model"]);
+        assert!(matches!(&history[1].parts[0], Part::Text(t) if t == "Summarize this"));
+        assert!(matches!(&history[2].parts[0], Part::Image(m) if m.mime_type == "image/png"));
+    }
+
+    #[test]
+    fn test_sampling_result() {
+        let response = Content {
+            role: "model".to_string(),
+            parts: vec![
+                Part::Thinking("hmm".to_string()),
+                Part::Text("Short summary.".to_string()),
+            ],
+        };
+        let result = sampling_result("gpt-4o", &response);
+        assert_eq!(result.model, "gpt-4o");
+        assert_eq!(result.message.role, Role::Assistant);
+        assert_eq!(
+            result.message.content.as_text().map(|t| t.text.as_str()),
+            Some("Short summary.")
+        );
+    }
+
+    #[test]
+    fn test_progress_text() {
+        let mut params = ProgressNotificationParam {
+            progress_token: ProgressToken(NumberOrString::Number(1)),
+            progress: 3.0,
+            total: Some(10.0),
+            message: Some("indexing".to_string()),
+        };
+        assert_eq!(
+            progress_text("docs:index", &params),
+            "docs:index: 3/10 indexing"
+        );
+        params.total = None;
+        params.message = None;
+        assert_eq!(progress_text("docs:index", &params), "docs:index: 3");
+    }
+
+    #[test]
+    fn test_progress_routes_are_removed_with_guard() {
+        let handler = McpClientHandler::new("docs");
+        let (tx, _rx) = mpsc::channel(1);
+        let guard = handler.track_progress("docs:index", tx);
+        assert!(handler.progress.lock().unwrap().contains_key(guard.token()));
+        let token = guard.token().clone();
+        drop(guard);
+        assert!(!handler.progress.lock().unwrap().contains_key(&token));
+    }
+
+    #[test]
+    fn test_capabilities_follow_configuration() {
+        let info = McpClientHandler::new("docs").get_info();
+        assert!(info.capabilities.sampling.is_some());
+        assert!(info.capabilities.roots.is_none());
+        assert!(info.capabilities.elicitation.is_none());
+
+        let handler = McpClientHandler::new("docs")
+            .with_roots(&["/tmp".to_string()])
+            .with_human_input(Arc::new(crate::kinetic::human::ConsoleInput::new()));
+        let info = handler.get_info();
+        assert!(info.capabilities.roots.is_some());
+        assert!(info.capabilities.elicitation.is_some());
+        assert_eq!(handler.roots[0].name.as_deref(), Some("tmp"));
+        assert!(handler.roots[0].uri.starts_with("file:///"));
+    }
+}
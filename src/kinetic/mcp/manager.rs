@@ -1,61 +1,479 @@
 // SPDX-License-Identifier: MIT
 
-use crate::kinetic::mcp::{create_mcp_service, BasicClientHandler};
-use rmcp::service::{RoleClient, RunningService};
+use crate::adk::tool::{calling_model, event_sender};
+use crate::kinetic::human::HumanInput;
+use crate::kinetic::mcp::client::McpClientHandler;
+use crate::kinetic::mcp::{create_mcp_service, create_remote_mcp_service, McpTransport};
+use rmcp::model::{
+    CallToolRequest, CallToolRequestParam, CallToolResult, ClientRequest, GetPromptRequestParam,
+    GetPromptResult, Meta, Prompt, ReadResourceRequestParam, ReadResourceResult, Resource,
+    ServerCapabilities, ServerResult, Tool as McpToolInfo,
+};
+use rmcp::service::{PeerRequestOptions, RoleClient, RunningService, ServiceError};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::env;
 use std::error::Error;
-use std::sync::Arc;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Timeout for connecting and for each request when `timeout_secs` is unset
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+/// Reconnect attempts before a server is marked failed
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+type McpService = RunningService<RoleClient, McpClientHandler>;
+
 /// Configuration for an MCP server
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct McpServerConfig {
     pub name: String,
+    pub transport: McpTransport,
+    /// Command to spawn (stdio transport)
     pub command: String,
     pub args: Vec<String>,
+    /// Extra environment for the command; `${VAR}` is expanded from our own environment
+    pub env: HashMap<String, String>,
+    /// Working directory for the command
+    pub cwd: Option<String>,
+    /// Server URL (http and sse transports)
+    pub url: Option<String>,
+    /// Extra HTTP headers (http and sse transports)
+    pub headers: HashMap<String, String>,
+    /// Environment variable holding a bearer token (http and sse transports)
+    pub bearer_token_env: Option<String>,
+    /// Timeout in seconds for connecting and for each request
+    pub timeout_secs: Option<u64>,
+    /// Directories the server may ask about with `roots/list`
+    pub roots: Vec<String>,
+}
+
+impl McpServerConfig {
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+}
+
+/// Lifecycle state of a managed MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpServerState {
+    Running,
+    /// The connection closed (e.g. the child exited); reconnects on next use
+    Disconnected,
+    Reconnecting,
+    /// Reconnecting gave up; retried on next use
+    Failed,
+    Stopped,
 }
 
-/// Type alias for MCP service map to reduce complexity
-type ServiceMap = HashMap<String, Arc<RwLock<RunningService<RoleClient, BasicClientHandler>>>>;
+/// Status of one managed MCP server, as returned by [`McpServiceManager::status`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct McpServerStatus {
+    pub name: String,
+    pub transport: McpTransport,
+    pub state: McpServerState,
+    pub tool_count: usize,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct ConnectionInfo {
+    state: McpServerState,
+    tool_count: usize,
+    restarts: u32,
+    last_error: Option<String>,
+}
+
+/// A connection to one MCP server that reconnects with backoff when the
+/// server goes away
+pub struct McpConnection {
+    config: McpServerConfig,
+    handler: McpClientHandler,
+    service: RwLock<Option<McpService>>,
+    /// Held while reconnecting, so only one caller reconnects at a time
+    reconnecting: tokio::sync::Mutex<()>,
+    info: Mutex<ConnectionInfo>,
+}
+
+impl McpConnection {
+    async fn connect(
+        config: McpServerConfig,
+        handler: McpClientHandler,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let service = connect(&config, &handler).await?;
+        Ok(Self {
+            config,
+            handler,
+            service: RwLock::new(Some(service)),
+            reconnecting: tokio::sync::Mutex::new(()),
+            info: Mutex::new(ConnectionInfo {
+                state: McpServerState::Running,
+                tool_count: 0,
+                restarts: 0,
+                last_error: None,
+            }),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Handler answering the server's sampling, roots and elicitation requests
+    pub fn client(&self) -> &McpClientHandler {
+        &self.handler
+    }
+
+    /// List the server's tools
+    pub async fn list_all_tools(&self) -> Result<Vec<McpToolInfo>, Box<dyn Error + Send + Sync>> {
+        let tools = self
+            .request(|service| async move { service.list_all_tools().await })
+            .await?;
+        self.info.lock().unwrap().tool_count = tools.len();
+        Ok(tools)
+    }
+
+    /// Call a tool on the server
+    ///
+    /// When the calling agent streams events, the server's progress
+    /// notifications for the call are forwarded to it as `AgentEvent::Log`.
+    /// A call the connection dropped during is not sent again, since the
+    /// server may already have run it. Sampling requests during the call are
+    /// answered with the calling agent's model.
+    pub async fn call_tool(
+        &self,
+        param: CallToolRequestParam,
+    ) -> Result<CallToolResult, Box<dyn Error + Send + Sync>> {
+        let _caller = calling_model().map(|model| self.handler.track_caller(model));
+        let Some(tx) = event_sender() else {
+            return self
+                .request_with(Retry::IfNotSent, |service| {
+                    let param = param.clone();
+                    async move { service.call_tool(param).await }
+                })
+                .await;
+        };
+
+        let label = format!("{}:{}", self.config.name, param.name);
+        let progress = self.handler.track_progress(label, tx);
+        let mut meta = Meta::new();
+        meta.set_progress_token(progress.token().clone());
+        self.request_with(Retry::IfNotSent, |service| {
+            let request = ClientRequest::CallToolRequest(CallToolRequest::new(param.clone()));
+            let options = PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta.clone()),
+            };
+            async move {
+                let result = service
+                    .send_request_with_option(request, options)
+                    .await?
+                    .await_response()
+                    .await?;
+                match result {
+                    ServerResult::CallToolResult(result) => Ok(result),
+                    _ => Err(ServiceError::UnexpectedResponse),
+                }
+            }
+        })
+        .await
+    }
+
+    /// List the server's resources
+    pub async fn list_all_resources(&self) -> Result<Vec<Resource>, Box<dyn Error + Send + Sync>> {
+        self.request(|service| async move { service.list_all_resources().await })
+            .await
+    }
+
+    /// Read the resource at `uri`
+    pub async fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Result<ReadResourceResult, Box<dyn Error + Send + Sync>> {
+        self.request(|service| async move {
+            service
+                .read_resource(ReadResourceRequestParam {
+                    uri: uri.to_string(),
+                })
+                .await
+        })
+        .await
+    }
+
+    /// List the server's prompts
+    pub async fn list_all_prompts(&self) -> Result<Vec<Prompt>, Box<dyn Error + Send + Sync>> {
+        self.request(|service| async move { service.list_all_prompts().await })
+            .await
+    }
+
+    /// Instantiate the prompt `name` with `arguments`
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, String>,
+    ) -> Result<GetPromptResult, Box<dyn Error + Send + Sync>> {
+        let arguments: serde_json::Map<String, serde_json::Value> = arguments
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect();
+        self.request(|service| {
+            let arguments = arguments.clone();
+            async move {
+                service
+                    .get_prompt(GetPromptRequestParam {
+                        name: name.to_string(),
+                        arguments: Some(arguments),
+                    })
+                    .await
+            }
+        })
+        .await
+    }
+
+    /// Capabilities the server announced when connecting
+    pub async fn capabilities(&self) -> Option<ServerCapabilities> {
+        let service = self.service.read().await;
+        let info = service.as_ref()?.peer_info()?;
+        Some(info.capabilities.clone())
+    }
+
+    /// Current status of the server
+    pub async fn status(&self) -> McpServerStatus {
+        let closed = match &*self.service.read().await {
+            Some(service) => service.is_transport_closed(),
+            None => false,
+        };
+        let info = self.info.lock().unwrap();
+        let state = match info.state {
+            McpServerState::Running if closed => McpServerState::Disconnected,
+            state => state,
+        };
+        McpServerStatus {
+            name: self.config.name.clone(),
+            transport: self.config.transport,
+            state,
+            tool_count: info.tool_count,
+            restarts: info.restarts,
+            last_error: info.last_error.clone(),
+        }
+    }
+
+    /// Close the connection, letting a child process exit
+    pub async fn shutdown(&self) {
+        let service = self.service.write().await.take();
+        self.set_state(McpServerState::Stopped);
+        if let Some(service) = service {
+            log::info!("Stopping MCP service '{}'", self.config.name);
+            if let Err(e) = service.cancel().await {
+                log::warn!(
+                    "MCP service '{}' did not stop cleanly: {}",
+                    self.config.name,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Run a request, reconnecting and retrying once if the transport is gone
+    async fn request<T, F, Fut>(&self, f: F) -> Result<T, Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(rmcp::Peer<RoleClient>) -> Fut,
+        Fut: Future<Output = Result<T, ServiceError>>,
+    {
+        self.request_with(Retry::Always, f).await
+    }
+
+    /// Run a request, reconnecting first if the transport is already gone and
+    /// retrying once after a failure that `retry` allows
+    async fn request_with<T, F, Fut>(
+        &self,
+        retry: Retry,
+        f: F,
+    ) -> Result<T, Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(rmcp::Peer<RoleClient>) -> Fut,
+        Fut: Future<Output = Result<T, ServiceError>>,
+    {
+        let mut reconnected = false;
+        loop {
+            let (peer, restarts) = self.peer().await?;
+            if peer.is_transport_closed() && !reconnected {
+                self.reconnect(restarts).await?;
+                reconnected = true;
+                continue;
+            }
+
+            let result = tokio::time::timeout(self.config.timeout(), f(peer))
+                .await
+                .map_err(|_| {
+                    format!(
+                        "MCP server '{}' did not respond within {}s",
+                        self.config.name,
+                        self.config.timeout().as_secs()
+                    )
+                })?;
+            match result {
+                Err(ServiceError::TransportSend(_)) if !reconnected => {
+                    self.reconnect(restarts).await?;
+                    reconnected = true;
+                }
+                Err(ServiceError::TransportClosed) if !reconnected && retry == Retry::Always => {
+                    self.reconnect(restarts).await?;
+                    reconnected = true;
+                }
+                Err(ServiceError::TransportClosed) if retry == Retry::IfNotSent => {
+                    // Reconnects on the next request
+                    return Err(format!(
+                        "MCP server '{}' closed the connection during the request; \
+                         it may or may not have been carried out",
+                        self.config.name
+                    )
+                    .into());
+                }
+                other => return Ok(other?),
+            }
+        }
+    }
+
+    /// The current peer and the restart count it belongs to
+    async fn peer(&self) -> Result<(rmcp::Peer<RoleClient>, u32), Box<dyn Error + Send + Sync>> {
+        match &*self.service.read().await {
+            Some(service) => Ok((service.peer().clone(), self.info.lock().unwrap().restarts)),
+            None => Err(format!("MCP server '{}' has been stopped", self.config.name).into()),
+        }
+    }
+
+    /// Replace the service that failed after `seen_restarts` restarts with a
+    /// new connection, backing off between attempts
+    ///
+    /// The service lock is only taken to swap in the new connection, so
+    /// `status` keeps answering while attempts are made.
+    async fn reconnect(&self, seen_restarts: u32) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let _reconnecting = self.reconnecting.lock().await;
+        let stopped = || format!("MCP server '{}' has been stopped", self.config.name);
+        if self.service.read().await.is_none() {
+            return Err(stopped().into());
+        }
+        if self.info.lock().unwrap().restarts != seen_restarts {
+            // Another caller already reconnected
+            return Ok(());
+        }
+
+        self.set_state(McpServerState::Reconnecting);
+        let mut backoff = INITIAL_BACKOFF;
+        let mut last_error = String::new();
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            log::warn!(
+                "MCP server '{}' disconnected, reconnecting (attempt {}/{})",
+                self.config.name,
+                attempt,
+                MAX_RECONNECT_ATTEMPTS
+            );
+            match connect(&self.config, &self.handler).await {
+                Ok(new_service) => {
+                    let mut service = self.service.write().await;
+                    let Some(old) = service.replace(new_service) else {
+                        // Shut down while connecting
+                        if let Some(new_service) = service.take() {
+                            new_service.cancellation_token().cancel();
+                        }
+                        return Err(stopped().into());
+                    };
+                    old.cancellation_token().cancel();
+                    let mut info = self.info.lock().unwrap();
+                    info.state = McpServerState::Running;
+                    info.restarts += 1;
+                    return Ok(());
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+            if attempt < MAX_RECONNECT_ATTEMPTS {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+
+        let mut info = self.info.lock().unwrap();
+        info.state = McpServerState::Failed;
+        info.last_error = Some(last_error.clone());
+        Err(format!(
+            "MCP server '{}' could not be reconnected: {}",
+            self.config.name, last_error
+        )
+        .into())
+    }
+
+    fn set_state(&self, state: McpServerState) {
+        self.info.lock().unwrap().state = state;
+    }
+}
+
+/// When a request may be sent again after reconnecting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Retry {
+    /// Also when the connection closed after sending (reads and listings)
+    Always,
+    /// Only when the request provably was not sent (tool calls)
+    IfNotSent,
+}
 
 /// Manages the lifecycle of MCP services
 pub struct McpServiceManager {
-    services: Arc<RwLock<ServiceMap>>,
+    services: Arc<RwLock<HashMap<String, Arc<McpConnection>>>>,
+    human: Option<Arc<dyn HumanInput>>,
 }
 
 impl McpServiceManager {
     pub fn new() -> Self {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
+            human: None,
         }
     }
 
+    /// Answer servers' elicitation requests through `human`; without it
+    /// they are declined
+    pub fn with_human_input(mut self, human: Arc<dyn HumanInput>) -> Self {
+        self.human = Some(human);
+        self
+    }
+
     /// Get or create an MCP service for the given server configuration
+    ///
+    /// Servers are shared by name, so asking for a running server's name
+    /// with a different configuration is an error.
     pub async fn get_or_create_service(
         &self,
         config: &McpServerConfig,
-    ) -> Result<
-        Arc<RwLock<RunningService<RoleClient, BasicClientHandler>>>,
-        Box<dyn Error + Send + Sync>,
-    > {
+    ) -> Result<Arc<McpConnection>, Box<dyn Error + Send + Sync>> {
         // Check if service already exists
         {
             let services = self.services.read().await;
             if let Some(service) = services.get(&config.name) {
+                if service.config != *config {
+                    return Err(format!(
+                        "MCP server '{}' is already running with a different configuration; \
+                         give one of them another name",
+                        config.name
+                    )
+                    .into());
+                }
                 return Ok(service.clone());
             }
         }
 
-        // Create new service
-        log::info!(
-            "Creating MCP service '{}' with command: {} {:?}",
-            config.name,
-            config.command,
-            config.args
-        );
-
-        let service = create_mcp_service(&config.command, &config.args).await?;
-        let service = Arc::new(RwLock::new(service));
+        let mut handler = McpClientHandler::new(&config.name).with_roots(&config.roots);
+        if let Some(human) = &self.human {
+            handler = handler.with_human_input(human.clone());
+        }
+        let service = Arc::new(McpConnection::connect(config.clone(), handler).await?);
 
         // Store in map
         {
@@ -67,13 +485,130 @@ impl McpServiceManager {
     }
 
     /// Get an existing service by name
-    pub async fn get_service(
-        &self,
-        name: &str,
-    ) -> Option<Arc<RwLock<RunningService<RoleClient, BasicClientHandler>>>> {
+    pub async fn get_service(&self, name: &str) -> Option<Arc<McpConnection>> {
         let services = self.services.read().await;
         services.get(name).cloned()
     }
+
+    /// Status of every managed server, sorted by name
+    pub async fn status(&self) -> Vec<McpServerStatus> {
+        let services: Vec<_> = self.services.read().await.values().cloned().collect();
+        let mut statuses = Vec::with_capacity(services.len());
+        for service in services {
+            statuses.push(service.status().await);
+        }
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
+    /// Stop every server and forget it
+    pub async fn shutdown(&self) {
+        let services: Vec<_> = self
+            .services
+            .write()
+            .await
+            .drain()
+            .map(|(_, s)| s)
+            .collect();
+        for service in services {
+            service.shutdown().await;
+        }
+    }
+}
+
+/// Start or connect to the server described by `config`
+async fn connect(
+    config: &McpServerConfig,
+    handler: &McpClientHandler,
+) -> Result<McpService, Box<dyn Error + Send + Sync>> {
+    let timeout = config.timeout();
+    tokio::time::timeout(timeout, connect_inner(config, handler.clone()))
+        .await
+        .map_err(|_| {
+            format!(
+                "Timed out connecting to MCP server '{}' after {}s",
+                config.name,
+                timeout.as_secs()
+            )
+        })?
+}
+
+async fn connect_inner(
+    config: &McpServerConfig,
+    handler: McpClientHandler,
+) -> Result<McpService, Box<dyn Error + Send + Sync>> {
+    if config.transport == McpTransport::Stdio {
+        if config.command.is_empty() {
+            return Err(format!("MCP server '{}' has no command", config.name).into());
+        }
+        let mut env = HashMap::with_capacity(config.env.len());
+        for (key, value) in &config.env {
+            let value = expand_env(value)
+                .map_err(|e| format!("MCP server '{}': env {}: {}", config.name, key, e))?;
+            env.insert(key.clone(), value);
+        }
+        log::info!(
+            "Creating MCP service '{}' with command: {} {:?}",
+            config.name,
+            config.command,
+            config.args
+        );
+        return create_mcp_service(
+            &config.command,
+            &config.args,
+            &env,
+            config.cwd.as_deref().map(Path::new),
+            handler,
+        )
+        .await;
+    }
+
+    let url = config.url.as_deref().ok_or_else(|| {
+        format!(
+            "MCP server '{}' uses the {:?} transport but has no url",
+            config.name, config.transport
+        )
+    })?;
+    let bearer_token = match &config.bearer_token_env {
+        Some(var) => Some(env::var(var).map_err(|_| {
+            format!(
+                "MCP server '{}': bearer token variable {} is not set",
+                config.name, var
+            )
+        })?),
+        None => None,
+    };
+    log::info!(
+        "Connecting to MCP service '{}' at {} ({:?})",
+        config.name,
+        url,
+        config.transport
+    );
+    create_remote_mcp_service(
+        config.transport,
+        url,
+        &config.headers,
+        bearer_token.as_deref(),
+        handler,
+    )
+    .await
+}
+
+/// Replace `${VAR}` with the value of `VAR` from the process environment
+fn expand_env(value: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unterminated ${{ in '{}'", value))?;
+        let var = &rest[start + 2..start + end];
+        out.push_str(&env::var(var).map_err(|_| format!("variable {} is not set", var))?);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
 }
 
 impl Default for McpServiceManager {
@@ -81,3 +616,40 @@ impl Default for McpServiceManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_env() {
+        env::set_var("KINETIC_TEST_EXPAND", "secret");
+        assert_eq!(expand_env("plain").unwrap(), "plain");
+        assert_eq!(expand_env("${KINETIC_TEST_EXPAND}").unwrap(), "secret");
+        assert_eq!(
+            expand_env("Bearer ${KINETIC_TEST_EXPAND}!").unwrap(),
+            "Bearer secret!"
+        );
+        assert!(expand_env("${KINETIC_TEST_MISSING_VAR}")
+            .unwrap_err()
+            .contains("KINETIC_TEST_MISSING_VAR"));
+        assert!(expand_env("${UNTERMINATED").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_env_variable_is_reported() {
+        let err = connect(
+            &McpServerConfig {
+                name: "local".to_string(),
+                command: "sh".to_string(),
+                env: HashMap::from([("TOKEN".to_string(), "${KINETIC_TEST_UNSET}".to_string())]),
+                ..Default::default()
+            },
+            &McpClientHandler::new("local"),
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("KINETIC_TEST_UNSET"));
+    }
+}
@@ -1,42 +1,61 @@
 // SPDX-License-Identifier: MIT
 
+pub mod client;
 pub mod manager;
+pub mod prompt;
+pub mod resource;
+pub mod server;
 pub mod tool;
 
-use rmcp::model::{ClientCapabilities, ClientInfo, Implementation};
+use client::McpClientHandler;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
 use rmcp::transport::child_process::TokioChildProcess;
-use rmcp::{ClientHandler, ServiceExt};
+use rmcp::transport::sse_client::SseClientConfig;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use rmcp::transport::{SseClientTransport, StreamableHttpClientTransport};
+use rmcp::ServiceExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
+use std::path::Path;
 use tokio::process::Command;
 
-#[derive(Debug, Clone)]
-pub struct BasicClientHandler;
-
-impl ClientHandler for BasicClientHandler {
-    fn get_info(&self) -> ClientInfo {
-        ClientInfo {
-            protocol_version: Default::default(),
-            capabilities: ClientCapabilities::default(),
-            client_info: Implementation {
-                name: "kinetic-rs".to_string(),
-                version: "0.1.0".to_string(),
-                ..Default::default()
-            },
-        }
-    }
+/// How to reach an MCP server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransport {
+    /// Spawn `command` and talk over stdin/stdout
+    #[default]
+    Stdio,
+    /// Streamable HTTP at `url`
+    Http,
+    /// Legacy HTTP + Server-Sent Events at `url` (the SSE endpoint)
+    Sse,
 }
 
 /// Creates an MCP service by connecting to an MCP server via stdio.
 ///
 /// Returns the service which can be used to call `list_all_tools()`, `call_tool()`, etc.
+/// `env` is added to the inherited environment of the child and `cwd`, if
+/// given, becomes its working directory. `handler` answers the server's
+/// requests back to us.
 ///
 /// # Example
 /// ```rust,ignore
+/// use kinetic_rs::kinetic::mcp::client::McpClientHandler;
 /// use kinetic_rs::kinetic::mcp::create_mcp_service;
+/// use std::collections::HashMap;
 ///
 /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
 ///     let args = vec!["arg1".to_string()];
-///     let service = create_mcp_service("command", &args).await?;
+///     let service = create_mcp_service(
+///         "command",
+///         &args,
+///         &HashMap::new(),
+///         None,
+///         McpClientHandler::new("example"),
+///     )
+///     .await?;
 ///     let tools = service.list_all_tools().await?;
 ///     Ok(())
 /// }
@@ -44,18 +63,80 @@ impl ClientHandler for BasicClientHandler {
 pub async fn create_mcp_service(
     command: &str,
     args: &[String],
+    env: &HashMap<String, String>,
+    cwd: Option<&Path>,
+    handler: McpClientHandler,
 ) -> Result<
-    rmcp::service::RunningService<rmcp::service::RoleClient, BasicClientHandler>,
+    rmcp::service::RunningService<rmcp::service::RoleClient, McpClientHandler>,
     Box<dyn Error + Send + Sync>,
 > {
     let mut server_cmd = Command::new(command);
     for arg in args {
         server_cmd.arg(arg);
     }
+    server_cmd.envs(env);
+    if let Some(cwd) = cwd {
+        server_cmd.current_dir(cwd);
+    }
 
     let transport = TokioChildProcess::new(server_cmd)?;
-    let client_handler = BasicClientHandler;
-    let service = client_handler.serve(transport).await?;
+    let service = handler.serve(transport).await?;
+
+    Ok(service)
+}
+
+/// Creates an MCP service by connecting to a remote MCP server over HTTP.
+///
+/// `headers` are sent with every request; `bearer_token`, if given, is sent
+/// as `Authorization: Bearer <token>`.
+pub async fn create_remote_mcp_service(
+    transport: McpTransport,
+    url: &str,
+    headers: &HashMap<String, String>,
+    bearer_token: Option<&str>,
+    handler: McpClientHandler,
+) -> Result<
+    rmcp::service::RunningService<rmcp::service::RoleClient, McpClientHandler>,
+    Box<dyn Error + Send + Sync>,
+> {
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers {
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+        value.set_sensitive(true);
+        header_map.insert(HeaderName::from_bytes(name.as_bytes())?, value);
+    }
+    if let Some(token) = bearer_token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| format!("Invalid bearer token: {}", e))?;
+        value.set_sensitive(true);
+        header_map.insert(AUTHORIZATION, value);
+    }
+    let client = reqwest::Client::builder()
+        .default_headers(header_map)
+        .build()?;
+
+    let service = match transport {
+        McpTransport::Http => {
+            let transport = StreamableHttpClientTransport::with_client(
+                client,
+                StreamableHttpClientTransportConfig::with_uri(url),
+            );
+            handler.serve(transport).await?
+        }
+        McpTransport::Sse => {
+            let transport = SseClientTransport::start_with_client(
+                client,
+                SseClientConfig {
+                    sse_endpoint: url.into(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            handler.serve(transport).await?
+        }
+        McpTransport::Stdio => return Err("stdio MCP servers are started with a command".into()),
+    };
 
     Ok(service)
 }
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+
+use crate::kinetic::mcp::resource::resource_text;
+use rmcp::model::{GetPromptResult, PromptMessageContent, ReadResourceResult};
+
+/// Render an instantiated MCP prompt as plain text for agent instructions
+///
+/// Message texts and embedded resources are joined in order; images and
+/// resource links are replaced by a short placeholder.
+pub fn prompt_text(result: &GetPromptResult) -> String {
+    result
+        .messages
+        .iter()
+        .map(|message| match &message.content {
+            PromptMessageContent::Text { text } => text.clone(),
+            PromptMessageContent::Resource { resource } => resource_text(&ReadResourceResult {
+                contents: vec![resource.resource.clone()],
+            }),
+            PromptMessageContent::Image { image } => format!("[image ({})]", image.mime_type),
+            PromptMessageContent::ResourceLink { link } => format!("[resource {}]", link.uri),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{PromptMessage, PromptMessageRole};
+
+    #[test]
+    fn test_prompt_text_joins_messages() {
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![
+                PromptMessage::new_text(PromptMessageRole::User, "Review the PR."),
+                PromptMessage::new_text(PromptMessageRole::Assistant, "Focus on tests."),
+            ],
+        };
+        assert_eq!(prompt_text(&result), "Review the PR.\n\nFocus on tests.");
+    }
+}
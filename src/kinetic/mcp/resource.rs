@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MIT
+
+use crate::adk::tool::Tool;
+use crate::kinetic::mcp::manager::McpConnection;
+use async_trait::async_trait;
+use rmcp::model::{ReadResourceResult, Resource, ResourceContents};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Name of the generated resource tool on each server
+pub const READ_RESOURCE_TOOL: &str = "read_resource";
+
+/// Resources listed in the tool description
+const MAX_LISTED_RESOURCES: usize = 20;
+
+/// Split an `mcp://<server>/<resource-uri>` reference into server and URI
+pub fn parse_resource_ref(reference: &str) -> Option<(&str, &str)> {
+    let rest = reference.strip_prefix("mcp://")?;
+    let (server, uri) = rest.split_once('/')?;
+    if server.is_empty() || uri.is_empty() {
+        return None;
+    }
+    Some((server, uri))
+}
+
+/// Text of a resource read; binary contents are described rather than inlined
+pub fn resource_text(result: &ReadResourceResult) -> String {
+    result
+        .contents
+        .iter()
+        .map(contents_text)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Text of one resource's contents, or a description of binary contents
+pub(crate) fn contents_text(contents: &ResourceContents) -> String {
+    match contents {
+        ResourceContents::TextResourceContents { text, .. } => text.clone(),
+        ResourceContents::BlobResourceContents { uri, mime_type, .. } => format!(
+            "[binary resource {} ({})]",
+            uri,
+            mime_type.as_deref().unwrap_or("unknown type")
+        ),
+    }
+}
+
+/// Tool reading a resource from an MCP server
+pub struct ReadResourceTool {
+    service: Arc<McpConnection>,
+    name: String,
+    description: String,
+    schema: Value,
+}
+
+impl ReadResourceTool {
+    /// `resources` are listed in the description so the model knows what to ask for
+    pub fn new(service: Arc<McpConnection>, resources: &[Resource]) -> Self {
+        let mut description = format!(
+            "Reads a resource from the '{}' MCP server by URI.",
+            service.name()
+        );
+        if !resources.is_empty() {
+            description.push_str(" Available resources:");
+            for resource in resources.iter().take(MAX_LISTED_RESOURCES) {
+                description.push_str(&format!("\n- {} ({})", resource.uri, resource.name));
+                if let Some(desc) = &resource.description {
+                    description.push_str(&format!(": {}", desc));
+                }
+            }
+            if resources.len() > MAX_LISTED_RESOURCES {
+                description.push_str(&format!(
+                    "\n- ... and {} more",
+                    resources.len() - MAX_LISTED_RESOURCES
+                ));
+            }
+        }
+
+        Self {
+            service,
+            name: READ_RESOURCE_TOOL.to_string(),
+            description,
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "uri": {"type": "string", "description": "URI of the resource to read"}
+                },
+                "required": ["uri"]
+            }),
+        }
+    }
+
+    /// Present the tool to the model as `<prefix>read_resource`
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.name = format!("{}{}", prefix, READ_RESOURCE_TOOL);
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for ReadResourceTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let uri = input["uri"].as_str().ok_or("uri must be a string")?;
+        let result = self.service.read_resource(uri).await?;
+        Ok(json!({
+            "uri": uri,
+            "contents": resource_text(&result),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resource_ref() {
+        assert_eq!(
+            parse_resource_ref("mcp://docs/file:///guide.md"),
+            Some(("docs", "file:///guide.md"))
+        );
+        assert_eq!(
+            parse_resource_ref("mcp://db/schema"),
+            Some(("db", "schema"))
+        );
+        assert_eq!(parse_resource_ref("mcp://docs"), None);
+        assert_eq!(parse_resource_ref("mcp:///uri"), None);
+        assert_eq!(parse_resource_ref("file:///guide.md"), None);
+    }
+
+    #[test]
+    fn test_resource_text() {
+        let result = ReadResourceResult {
+            contents: vec![
+                ResourceContents::text("first", "mem://a"),
+                ResourceContents::BlobResourceContents {
+                    uri: "mem://b".to_string(),
+                    mime_type: Some("image/png".to_string()),
+                    blob: "aGk=".to_string(),
+                    meta: None,
+                },
+            ],
+        };
+        assert_eq!(
+            resource_text(&result),
+            "first\n\n[binary resource mem://b (image/png)]"
+        );
+    }
+}
@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: MIT
+
+//! Workflows served over MCP
+//!
+//! [`WorkflowServer`] exposes workflow and agent files as MCP tools, so MCP
+//! clients such as IDE assistants can run them. Each tool takes the
+//! workflow's `input_schema` (or a `task`/`context` pair), returns the
+//! workflow output, and reports the run's events as progress notifications
+//! when the client asks for progress. Over HTTP a bearer token can be
+//! required, since the tools run with the server's credentials.
+
+use crate::adk::agent::{default_input_schema, input_message, tool_name, Agent, AgentEvent};
+use crate::kinetic::workflow::builder::Builder;
+use axum::http::{header, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use rmcp::handler::server::ServerHandler;
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult,
+    PaginatedRequestParam, ProgressNotificationParam, ServerCapabilities, ServerInfo,
+    Tool as McpToolInfo,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::{ErrorData as McpError, ServiceExt};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::{mpsc, OnceCell};
+
+/// Longest event text sent in a progress notification
+const MAX_PROGRESS_MESSAGE_CHARS: usize = 200;
+
+/// One workflow file offered as a tool
+struct ServedWorkflow {
+    tool: McpToolInfo,
+    path: String,
+    agent: OnceCell<Arc<dyn Agent>>,
+}
+
+/// MCP server running workflows as tools
+#[derive(Clone)]
+pub struct WorkflowServer {
+    builder: Builder,
+    workflows: Arc<Vec<ServedWorkflow>>,
+    /// Bearer token HTTP clients must send, if any
+    token: Option<Arc<str>>,
+}
+
+impl WorkflowServer {
+    /// Serve the workflow files at `paths`
+    ///
+    /// Files that fail to load, or whose tool name is already taken, are
+    /// skipped with a warning.
+    pub fn new(builder: Builder, paths: &[String]) -> Self {
+        let mut names = HashSet::new();
+        let mut workflows = Vec::with_capacity(paths.len());
+        for path in paths {
+            let (name, description, input_schema) = match builder.describe_workflow(path) {
+                Ok(described) => described,
+                Err(e) => {
+                    log::warn!("Not serving {}: {}", path, e);
+                    continue;
+                }
+            };
+            let name = tool_name(&name);
+            if !names.insert(name.clone()) {
+                log::warn!("Not serving {}: tool name {} is already taken", path, name);
+                continue;
+            }
+            let schema = match input_schema.unwrap_or_else(default_input_schema) {
+                Value::Object(schema) => schema,
+                _ => {
+                    log::warn!("Not serving {}: input_schema is not an object", path);
+                    continue;
+                }
+            };
+            log::info!("Serving {} as MCP tool {}", path, name);
+            workflows.push(ServedWorkflow {
+                tool: McpToolInfo::new(name, description, Arc::new(schema)),
+                path: path.clone(),
+                agent: OnceCell::new(),
+            });
+        }
+
+        Self {
+            builder,
+            workflows: Arc::new(workflows),
+            token: None,
+        }
+    }
+
+    /// Require `Authorization: Bearer <token>` on HTTP requests
+    pub fn with_token(mut self, token: String) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Names of the tools being served
+    pub fn tool_names(&self) -> Vec<&str> {
+        self.workflows
+            .iter()
+            .map(|w| w.tool.name.as_ref())
+            .collect()
+    }
+
+    /// Serve over stdin/stdout until the client disconnects
+    pub async fn serve_stdio(self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let service = self.serve(rmcp::transport::stdio()).await?;
+        service.waiting().await?;
+        Ok(())
+    }
+
+    /// Router serving Streamable HTTP at `/mcp`
+    pub fn router(self) -> axum::Router {
+        let token = self.token.clone();
+        let service = StreamableHttpService::new(
+            move || Ok(self.clone()),
+            LocalSessionManager::default().into(),
+            StreamableHttpServerConfig::default(),
+        );
+        let router = axum::Router::new().nest_service("/mcp", service);
+        match token {
+            Some(token) => router.layer(axum::middleware::from_fn(
+                move |request: Request<axum::body::Body>, next: Next| {
+                    let token = token.clone();
+                    async move { check_token(&token, request, next).await }
+                },
+            )),
+            None => router,
+        }
+    }
+
+    /// Serve Streamable HTTP at `http://<host>:<port>/mcp`
+    pub async fn serve_http(
+        self,
+        host: &str,
+        port: u16,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let listener = tokio::net::TcpListener::bind((host, port)).await?;
+        let addr = listener.local_addr()?;
+        if !addr.ip().is_loopback() && self.token.is_none() {
+            log::warn!(
+                "Serving MCP on {} without a token: anyone who can reach it can run the workflows",
+                addr
+            );
+        }
+        log::info!("Serving MCP on http://{}/mcp", addr);
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    async fn agent(
+        &self,
+        workflow: &ServedWorkflow,
+    ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
+        workflow
+            .agent
+            .get_or_try_init(|| self.builder.build_agent(&workflow.path))
+            .await
+            .cloned()
+    }
+}
+
+impl ServerHandler for WorkflowServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "kinetic-rs".to_string(),
+                version: "0.1.0".to_string(),
+                ..Default::default()
+            },
+            instructions: Some("Each tool runs a kinetic workflow and returns its output.".into()),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.workflows.iter().map(|w| w.tool.clone()).collect(),
+            ..Default::default()
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let workflow = self
+            .workflows
+            .iter()
+            .find(|w| w.tool.name == request.name)
+            .ok_or_else(|| {
+                McpError::invalid_params(format!("Unknown tool: {}", request.name), None)
+            })?;
+        let agent = match self.agent(workflow).await {
+            Ok(agent) => agent,
+            Err(e) => return Ok(error_result(format!("Failed to build workflow: {}", e))),
+        };
+        let input = input_message(Value::Object(request.arguments.unwrap_or_default()));
+
+        let run = async {
+            let Some(token) = context.meta.get_progress_token() else {
+                return agent.run_message(input).await;
+            };
+            let (tx, mut rx) = mpsc::channel(32);
+            let peer = context.peer.clone();
+            let forward = tokio::spawn(async move {
+                let mut progress = 0.0;
+                while let Some(event) = rx.recv().await {
+                    let Some(message) = progress_message(&event) else {
+                        continue;
+                    };
+                    progress += 1.0;
+                    let _ = peer
+                        .notify_progress(ProgressNotificationParam {
+                            progress_token: token.clone(),
+                            progress,
+                            total: None,
+                            message: Some(message),
+                        })
+                        .await;
+                }
+            });
+            let result = agent.run_message_stream(input, tx).await;
+            let _ = forward.await;
+            result
+        };
+
+        let result = tokio::select! {
+            result = run => result,
+            _ = context.ct.cancelled() => return Ok(error_result("Cancelled".to_string())),
+        };
+        Ok(match result {
+            Ok(output) => {
+                let mut result = CallToolResult::success(vec![Content::text(output.text)]);
+                result.structured_content = output.data.filter(Value::is_object);
+                result
+            }
+            Err(e) => error_result(e.to_string()),
+        })
+    }
+}
+
+/// Reject requests without `Authorization: Bearer <token>`
+async fn check_token(token: &str, request: Request<axum::body::Body>, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|sent| sent == token);
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+fn error_result(message: String) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(message)])
+}
+
+/// Workflow and agent files (`*.yaml`, `*.yml`) in `dirs`, sorted per directory
+pub fn discover_workflows(dirs: &[String]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for dir in dirs {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Cannot read workflow directory {}: {}", dir, e);
+                continue;
+            }
+        };
+        let mut found: Vec<String> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(|e| e.to_str()),
+                        Some("yaml" | "yml")
+                    )
+            })
+            .map(|path| Path::new(dir).join(path.file_name().unwrap()))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        found.sort();
+        paths.extend(found);
+    }
+    paths
+}
+
+/// Progress text for an agent event; answers are returned as the result instead
+fn progress_message(event: &AgentEvent) -> Option<String> {
+    let message = match event {
+        AgentEvent::Thought(thought) => format!("Thinking: {}", thought),
+        AgentEvent::ToolCall { name, .. } => format!("Calling {}", name),
+        AgentEvent::ToolResult { name, .. } => format!("{} returned", name),
+        AgentEvent::Plan(steps) => format!("Planned {} steps", steps.len()),
+        AgentEvent::StepResult { step, .. } => format!("Finished step: {}", step),
+        AgentEvent::ReasoningPath { index, .. } => format!("Reasoning path {}", index + 1),
+        AgentEvent::Vote { answer, .. } => format!("Voted for: {}", answer),
+        AgentEvent::Critique {
+            round, approved, ..
+        } => format!(
+            "Critique round {}: {}",
+            round,
+            if *approved { "approved" } else { "revising" }
+        ),
+        AgentEvent::Log(line) => line.clone(),
+        AgentEvent::Error(e) => format!("Error: {}", e),
+        AgentEvent::Answer(_) => return None,
+    };
+    Some(truncate(&message))
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_PROGRESS_MESSAGE_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinetic::mcp::manager::McpServiceManager;
+    use crate::kinetic::workflow::registry::ToolRegistry;
+
+    fn builder() -> Builder {
+        Builder::new(ToolRegistry::new(), Arc::new(McpServiceManager::new()))
+    }
+
+    #[test]
+    fn test_discover_workflows() {
+        let paths = discover_workflows(&["agents".to_string(), "missing".to_string()]);
+        assert!(paths.contains(&"agents/pr_reviewer.yaml".to_string()));
+        assert!(paths.iter().all(|p| p.starts_with("agents/")));
+        let mut sorted = paths.clone();
+        sorted.sort();
+        assert_eq!(paths, sorted);
+    }
+
+    #[test]
+    fn test_workflows_become_tools() {
+        let paths = vec![
+            "agents/jira_details_fetcher.yaml".to_string(),
+            "agents/jira_details_fetcher.yaml".to_string(),
+            "agents/does_not_exist.yaml".to_string(),
+        ];
+        let server = WorkflowServer::new(builder(), &paths);
+        assert_eq!(server.tool_names(), ["JiraDetailsFetcher"]);
+        let tool = &server.workflows[0].tool;
+        assert!(tool.description.as_deref().unwrap().contains("Jira"));
+        assert_eq!(tool.input_schema["required"], serde_json::json!(["task"]));
+    }
+
+    #[test]
+    fn test_progress_message() {
+        assert_eq!(
+            progress_message(&AgentEvent::ToolCall {
+                name: "get_pr".to_string(),
+                args: Value::Null,
+            }),
+            Some("Calling get_pr".to_string())
+        );
+        assert_eq!(progress_message(&AgentEvent::Answer("done".into())), None);
+        let long = progress_message(&AgentEvent::Thought("é".repeat(300))).unwrap();
+        assert!(long.ends_with("..."));
+        assert_eq!(
+            long.chars().count(),
+            MAX_PROGRESS_MESSAGE_CHARS + "...".len()
+        );
+    }
+}
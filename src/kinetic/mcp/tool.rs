@@ -1,42 +1,47 @@
 // SPDX-License-Identifier: MIT
 
-use crate::adk::tool::Tool;
+use crate::adk::model::{Media, MediaSource, Part};
+use crate::adk::tool::{with_media, Tool};
+use crate::kinetic::mcp::manager::McpConnection;
+use crate::kinetic::mcp::resource::contents_text;
 use async_trait::async_trait;
-use rmcp::model::CallToolRequestParam;
-use rmcp::service::RoleClient;
+use rmcp::model::{CallToolRequestParam, CallToolResult, RawContent, ResourceContents};
 use serde_json::Value;
 use std::error::Error;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
 /// Wrapper around an MCP tool that implements the kinetic-rs Tool trait
 pub struct McpTool {
-    service: Arc<
-        RwLock<rmcp::service::RunningService<RoleClient, crate::kinetic::mcp::BasicClientHandler>>,
-    >,
+    service: Arc<McpConnection>,
+    /// Name presented to the model
     name: String,
+    /// Name of the tool on the server
+    tool_name: String,
     description: String,
     schema: Value,
 }
 
 impl McpTool {
     pub fn new(
-        service: Arc<
-            RwLock<
-                rmcp::service::RunningService<RoleClient, crate::kinetic::mcp::BasicClientHandler>,
-            >,
-        >,
+        service: Arc<McpConnection>,
         name: String,
         description: String,
         schema: Value,
     ) -> Self {
         Self {
             service,
+            tool_name: name.clone(),
             name,
             description,
             schema,
         }
     }
+
+    /// Present the tool to the model as `<prefix><name>`
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.name = format!("{}{}", prefix, self.tool_name);
+        self
+    }
 }
 
 #[async_trait]
@@ -54,21 +59,164 @@ impl Tool for McpTool {
     }
 
     async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let service = self.service.read().await;
-
         // Convert Value to Map if it's an object
         let arguments = match input {
             Value::Object(map) => Some(map),
             _ => None,
         };
 
-        let result = service
+        let result = self
+            .service
             .call_tool(CallToolRequestParam {
-                name: self.name.clone().into(),
+                name: self.tool_name.clone().into(),
                 arguments,
             })
             .await?;
 
-        Ok(serde_json::to_value(result)?)
+        tool_result(result)
+    }
+}
+
+/// Convert a tool call result into the value returned to the agent
+///
+/// Results flagged `isError` fail the call with their text. Otherwise
+/// `structuredContent` is preferred when the server sends it; if not, text
+/// and embedded text resources are joined, and a lone text holding a JSON
+/// document is parsed. Images and embedded image or PDF blobs are attached
+/// as media with a marker in the text; audio is only described.
+fn tool_result(result: CallToolResult) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let mut texts = Vec::new();
+    let mut media = Vec::new();
+    for content in result.content {
+        match content.raw {
+            RawContent::Text(text) => texts.push(text.text),
+            RawContent::Image(image) => {
+                texts.push(format!("[image {}: {}]", media.len() + 1, image.mime_type));
+                media.push(Part::Image(inline_media(image.mime_type, image.data, None)));
+            }
+            RawContent::Audio(audio) => {
+                texts.push(format!("[audio ({}) not shown]", audio.mime_type))
+            }
+            RawContent::Resource(embedded) => match embedded.resource {
+                ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type: Some(mime_type),
+                    blob,
+                    ..
+                } if mime_type.starts_with("image/") || mime_type == "application/pdf" => {
+                    texts.push(format!("[attachment {}: {}]", media.len() + 1, uri));
+                    let attachment = inline_media(mime_type, blob, Some(uri));
+                    media.push(if attachment.mime_type.starts_with("image/") {
+                        Part::Image(attachment)
+                    } else {
+                        Part::Document(attachment)
+                    });
+                }
+                resource => texts.push(contents_text(&resource)),
+            },
+            RawContent::ResourceLink(link) => {
+                texts.push(format!("[resource {}: {}]", link.name, link.uri))
+            }
+        }
+    }
+
+    if result.is_error == Some(true) {
+        let message = texts.join("\n\n");
+        return Err(if message.is_empty() {
+            "MCP tool reported an error".into()
+        } else {
+            message.into()
+        });
+    }
+
+    let value = match result.structured_content {
+        Some(structured) => structured,
+        None if texts.len() == 1 && media.is_empty() => {
+            let text = texts.remove(0);
+            match serde_json::from_str::<Value>(&text) {
+                Ok(json @ (Value::Object(_) | Value::Array(_))) => json,
+                _ => Value::String(text),
+            }
+        }
+        None => Value::String(texts.join("\n\n")),
+    };
+    Ok(with_media(value, media))
+}
+
+fn inline_media(mime_type: String, data: String, name: Option<String>) -> Media {
+    Media {
+        mime_type,
+        source: MediaSource::Base64(data),
+        name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::tool::split_media;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn result(content: Vec<Content>) -> CallToolResult {
+        CallToolResult::success(content)
+    }
+
+    #[test]
+    fn test_text_and_json_results() {
+        let value = tool_result(result(vec![Content::text("done")])).unwrap();
+        assert_eq!(value, json!("done"));
+
+        let value = tool_result(result(vec![Content::text(r#"{"id": 7}"#)])).unwrap();
+        assert_eq!(value, json!({"id": 7}));
+
+        let value = tool_result(result(vec![Content::text("a"), Content::text("b")])).unwrap();
+        assert_eq!(value, json!("a\n\nb"));
+    }
+
+    #[test]
+    fn test_structured_content_is_preferred() {
+        let mut structured = result(vec![Content::text("3 open issues")]);
+        structured.structured_content = Some(json!({"open": 3}));
+        assert_eq!(tool_result(structured).unwrap(), json!({"open": 3}));
+    }
+
+    #[test]
+    fn test_is_error_fails_the_call() {
+        let err = tool_result(CallToolResult::error(vec![Content::text("not found")]));
+        assert_eq!(err.unwrap_err().to_string(), "not found");
+
+        let err = tool_result(CallToolResult::error(Vec::new()));
+        assert_eq!(err.unwrap_err().to_string(), "MCP tool reported an error");
+    }
+
+    #[test]
+    fn test_images_and_resources() {
+        let value = tool_result(result(vec![
+            Content::text("Chart:"),
+            Content::image("cG5n", "image/png"),
+            Content::resource(ResourceContents::text("fn main() {}", "file:///main.rs")),
+            Content::resource(ResourceContents::BlobResourceContents {
+                uri: "file:///data.bin".to_string(),
+                mime_type: Some("application/octet-stream".to_string()),
+                blob: "AAAA".to_string(),
+                meta: None,
+            }),
+        ]))
+        .unwrap();
+
+        let (value, media) = split_media(value);
+        assert_eq!(
+            value,
+            json!(
+                "Chart:\n\n[image 1: image/png]\n\nfn main() {}\n\n\
+                 [binary resource file:///data.bin (application/octet-stream)]"
+            )
+        );
+        assert!(matches!(
+            &media[..],
+            [Part::Image(Media { mime_type, source: MediaSource::Base64(data), .. })]
+                if mime_type == "image/png" && data == "cG5n"
+        ));
     }
 }
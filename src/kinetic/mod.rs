@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: MIT
 
+pub mod human;
 pub mod mcp;
 pub mod server;
+pub mod session;
 pub mod tools;
 pub mod workflow;
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT
 
 use axum::{
-    extract::Path,
+    extract::{Path, State},
     response::sse::{Event, Sse},
     routing::{get, post},
     Json, Router,
@@ -19,21 +19,38 @@ use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
+use crate::adk::agent::AgentMessage;
+use crate::kinetic::mcp::manager::McpServiceManager;
+use crate::kinetic::session::{self, FileSessionStore, SessionStore};
 use crate::kinetic::tools::{github, jira, search};
 use crate::kinetic::workflow::builder::Builder;
 use crate::kinetic::workflow::registry::ToolRegistry;
 
-pub async fn serve(port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// Serve the REST API. MCP servers started by workflows are shared between
+/// requests through `mcp_manager`.
+pub async fn serve(
+    port: u16,
+    mcp_manager: Arc<McpServiceManager>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let app = Router::new()
         .route("/api/health", get(health_check))
+        .route("/api/metrics/tools", get(tool_metrics))
+        .route("/api/mcp/servers", get(mcp_servers))
         .route("/api/workflows", get(list_workflows))
         .route("/api/workflows/{id}", get(get_workflow))
         .route("/api/agents", get(list_agents))
         .route("/api/agents/{id}", get(get_agent))
         .route("/api/executions", post(create_execution))
         .route("/api/executions/stream", post(stream_execution))
+        .route("/api/sessions", get(list_sessions).post(create_session))
+        .route(
+            "/api/sessions/{id}",
+            get(get_session).delete(delete_session),
+        )
+        .route("/api/sessions/{id}/messages", post(continue_session))
         .layer(TraceLayer::new_for_http())
-        .layer(CorsLayer::permissive());
+        .layer(CorsLayer::permissive())
+        .with_state(mcp_manager);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
     log::info!("Listening on http://{}", addr);
@@ -48,6 +65,16 @@ async fn health_check() -> Json<Value> {
     Json(json!({ "status": "ok" }))
 }
 
+/// Per-tool call counts, including validation and execution failures
+async fn tool_metrics() -> Json<Value> {
+    Json(json!(crate::adk::tool::metrics().snapshot()))
+}
+
+/// State, tool count and restarts of each MCP server
+async fn mcp_servers(State(mcp_manager): State<Arc<McpServiceManager>>) -> Json<Value> {
+    Json(json!(mcp_manager.status().await))
+}
+
 async fn list_workflows() -> Json<Value> {
     let mut workflows = Vec::new();
     if let Ok(mut entries) = fs::read_dir("examples").await {
@@ -125,7 +152,8 @@ async fn get_agent(Path(id): Path<String>) -> Json<Value> {
 #[derive(Deserialize)]
 struct ExecutionRequest {
     workflow_id: String,
-    input: String,
+    /// Plain string or any JSON value
+    input: Value,
 }
 
 // Register tools Helper
@@ -133,7 +161,8 @@ async fn register_tools(registry: &ToolRegistry) {
     if let Ok(search_tool) = search::BraveSearchTool::new() {
         registry.register(Arc::new(search_tool)).await;
     }
-    if let Ok(github_tools) = github::create_tools() {
+    // Nobody is at a terminal to approve writes, so they need GITHUB_ALLOW_WRITES
+    if let Ok(github_tools) = github::create_tools(None) {
         for tool in github_tools {
             registry.register(tool).await;
         }
@@ -145,34 +174,64 @@ async fn register_tools(registry: &ToolRegistry) {
     }
 }
 
-async fn create_execution(Json(payload): Json<ExecutionRequest>) -> Json<Value> {
-    let path = PathBuf::from("examples").join(format!("{}.yaml", payload.workflow_id));
-    let workflow_path = if path.exists() {
+/// Resolve a workflow id to a file in `examples/`, falling back to `agents/`
+fn resolve_workflow_path(workflow_id: &str) -> PathBuf {
+    let path = PathBuf::from("examples").join(format!("{}.yaml", workflow_id));
+    if path.exists() {
         path
     } else {
-        PathBuf::from("agents").join(format!("{}.yaml", payload.workflow_id))
-    };
+        PathBuf::from("agents").join(format!("{}.yaml", workflow_id))
+    }
+}
+
+/// Build the agent for a workflow id with all native tools registered
+async fn build_workflow_agent(
+    workflow_id: &str,
+    mcp_manager: Arc<McpServiceManager>,
+) -> Result<Arc<dyn crate::adk::agent::Agent>, String> {
+    build_agent_from(&resolve_workflow_path(workflow_id), mcp_manager).await
+}
 
+/// Build the agent for a workflow file with all native tools registered
+async fn build_agent_from(
+    workflow_path: &std::path::Path,
+    mcp_manager: Arc<McpServiceManager>,
+) -> Result<Arc<dyn crate::adk::agent::Agent>, String> {
     if !workflow_path.exists() {
-        return Json(json!({"error": "Workflow/Agent not found"}));
+        return Err("Workflow/Agent not found".to_string());
     }
 
     let registry = ToolRegistry::new();
     register_tools(&registry).await;
 
-    let mcp_manager = Arc::new(crate::kinetic::mcp::manager::McpServiceManager::new());
     let builder = Builder::new(registry, mcp_manager);
 
-    match builder.build_agent(workflow_path.to_str().unwrap()).await {
-        Ok(agent) => match agent.run(payload.input).await {
-            Ok(response) => Json(json!({ "status": "completed", "output": response })),
+    builder
+        .build_agent(workflow_path.to_str().unwrap())
+        .await
+        .map_err(|e| format!("Failed to build agent: {}", e))
+}
+
+async fn create_execution(
+    State(mcp_manager): State<Arc<McpServiceManager>>,
+    Json(payload): Json<ExecutionRequest>,
+) -> Json<Value> {
+    match build_workflow_agent(&payload.workflow_id, mcp_manager).await {
+        Ok(agent) => match agent.run_message(AgentMessage::from(payload.input)).await {
+            Ok(response) => Json(json!({
+                "status": "completed",
+                "output": response.text,
+                "data": response.data,
+                "metadata": response.metadata
+            })),
             Err(e) => Json(json!({ "error": format!("Execution failed: {}", e) })),
         },
-        Err(e) => Json(json!({"error": format!("Failed to build agent: {}", e)})),
+        Err(e) => Json(json!({ "error": e })),
     }
 }
 
 async fn stream_execution(
+    State(mcp_manager): State<Arc<McpServiceManager>>,
     Json(payload): Json<ExecutionRequest>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let (tx, rx) = mpsc::channel(100);
@@ -183,13 +242,7 @@ async fn stream_execution(
             payload.workflow_id
         );
 
-        // Determine path Logic (Copied from create_execution)
-        let path = PathBuf::from("examples").join(format!("{}.yaml", payload.workflow_id));
-        let workflow_path = if path.exists() {
-            path
-        } else {
-            PathBuf::from("agents").join(format!("{}.yaml", payload.workflow_id))
-        };
+        let workflow_path = resolve_workflow_path(&payload.workflow_id);
 
         if !workflow_path.exists() {
             log::warn!("Workflow/agent not found: {:?}", workflow_path);
@@ -204,14 +257,16 @@ async fn stream_execution(
         let registry = ToolRegistry::new();
         register_tools(&registry).await;
 
-        let mcp_manager = Arc::new(crate::kinetic::mcp::manager::McpServiceManager::new());
         let builder = Builder::new(registry, mcp_manager);
 
         log::info!("Building agent from: {:?}", workflow_path);
         match builder.build_agent(workflow_path.to_str().unwrap()).await {
             Ok(agent) => {
                 log::info!("Agent built successfully, starting run_stream");
-                if let Err(e) = agent.run_stream(payload.input, tx.clone()).await {
+                if let Err(e) = agent
+                    .run_message_stream(AgentMessage::from(payload.input), tx.clone())
+                    .await
+                {
                     log::error!("Agent execution failed: {}", e);
                     let _ = tx
                         .send(crate::adk::agent::AgentEvent::Error(format!(
@@ -241,3 +296,87 @@ async fn stream_execution(
         axum::response::sse::KeepAlive::new().interval(std::time::Duration::from_secs(1)),
     )
 }
+
+#[derive(Deserialize)]
+struct CreateSessionRequest {
+    workflow_id: String,
+}
+
+#[derive(Deserialize)]
+struct SessionMessageRequest {
+    input: String,
+}
+
+async fn create_session(Json(payload): Json<CreateSessionRequest>) -> Json<Value> {
+    let workflow_path = resolve_workflow_path(&payload.workflow_id);
+    if !workflow_path.exists() {
+        return Json(json!({"error": "Workflow/Agent not found"}));
+    }
+
+    // Sessions store the workflow file, like `kinetic chat` does
+    let store = FileSessionStore::from_env();
+    match store.create(&workflow_path.to_string_lossy()).await {
+        Ok(session) => Json(json!(session.summary())),
+        Err(e) => Json(json!({"error": e.to_string()})),
+    }
+}
+
+async fn list_sessions() -> Json<Value> {
+    let store = FileSessionStore::from_env();
+    match store.list().await {
+        Ok(sessions) => Json(json!(sessions)),
+        Err(e) => Json(json!({"error": e.to_string()})),
+    }
+}
+
+async fn get_session(Path(id): Path<String>) -> Json<Value> {
+    let store = FileSessionStore::from_env();
+    match store.get(&id).await {
+        Ok(Some(session)) => Json(json!(session)),
+        Ok(None) => Json(json!({"error": "Session not found"})),
+        Err(e) => Json(json!({"error": e.to_string()})),
+    }
+}
+
+async fn delete_session(Path(id): Path<String>) -> Json<Value> {
+    let store = FileSessionStore::from_env();
+    match store.delete(&id).await {
+        Ok(true) => Json(json!({"status": "deleted"})),
+        Ok(false) => Json(json!({"error": "Session not found"})),
+        Err(e) => Json(json!({"error": e.to_string()})),
+    }
+}
+
+async fn continue_session(
+    State(mcp_manager): State<Arc<McpServiceManager>>,
+    Path(id): Path<String>,
+    Json(payload): Json<SessionMessageRequest>,
+) -> Json<Value> {
+    // Held until the session is saved, so concurrent messages can't drop an exchange
+    let _guard = session::lock(&id).await;
+    let store = FileSessionStore::from_env();
+    let mut session = match store.get(&id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => return Json(json!({"error": "Session not found"})),
+        Err(e) => return Json(json!({"error": e.to_string()})),
+    };
+
+    let agent = match build_agent_from(std::path::Path::new(&session.workflow), mcp_manager).await {
+        Ok(agent) => agent,
+        Err(e) => return Json(json!({"error": e})),
+    };
+
+    let result = session.send(agent.as_ref(), payload.input).await;
+    if let Err(e) = store.save(&session).await {
+        return Json(json!({"error": format!("Failed to save session: {}", e)}));
+    }
+
+    match result {
+        Ok(output) => Json(json!({
+            "status": "completed",
+            "session_id": session.id,
+            "output": output
+        })),
+        Err(e) => Json(json!({ "error": format!("Execution failed: {}", e) })),
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MIT
+
+//! File-backed session store
+
+use super::{Session, SessionStore, SessionSummary};
+use async_trait::async_trait;
+use std::env;
+use std::error::Error;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// Stores each session as `<dir>/<id>.json`
+pub struct FileSessionStore {
+    dir: PathBuf,
+}
+
+impl FileSessionStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Create a store in `KINETIC_SESSION_DIR` (default: `.kinetic/sessions`)
+    pub fn from_env() -> Self {
+        let dir =
+            env::var("KINETIC_SESSION_DIR").unwrap_or_else(|_| ".kinetic/sessions".to_string());
+        Self::new(dir)
+    }
+
+    /// Resolve the file path for a session id, rejecting ids that could escape the directory
+    fn session_path(&self, id: &str) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("Invalid session id: {}", id).into());
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn get(&self, id: &str) -> Result<Option<Session>, Box<dyn Error + Send + Sync>> {
+        let path = self.session_path(id)?;
+        match fs::read_to_string(&path).await {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, session: &Session) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let path = self.session_path(&session.id)?;
+        fs::create_dir_all(&self.dir).await?;
+
+        // Write to a temp file first so a crash never leaves a truncated session
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(session)?).await?;
+        fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<SessionSummary>, Box<dyn Error + Send + Sync>> {
+        let mut sessions = Vec::new();
+        let mut entries = match fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(sessions),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).await?;
+            match serde_json::from_str::<Session>(&content) {
+                Ok(session) => sessions.push(session.summary()),
+                Err(e) => log::warn!("Skipping unreadable session file {:?}: {}", path, e),
+            }
+        }
+
+        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(sessions)
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let path = self.session_path(id)?;
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adk::model::{Content, Part};
+
+    fn temp_store() -> (FileSessionStore, PathBuf) {
+        let dir = env::temp_dir().join(format!("kinetic-sessions-{}", uuid::Uuid::new_v4()));
+        (FileSessionStore::new(&dir), dir)
+    }
+
+    #[tokio::test]
+    async fn test_create_get_and_save() {
+        let (store, dir) = temp_store();
+
+        let mut session = store.create("pr_reviewer").await.unwrap();
+        session.history.push(Content {
+            role: "user".to_string(),
+            parts: vec![Part::Text("Review PR 42".to_string())],
+        });
+        store.save(&session).await.unwrap();
+
+        let loaded = store.get(&session.id).await.unwrap().unwrap();
+        assert_eq!(loaded.workflow, "pr_reviewer");
+        assert_eq!(loaded.history.len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_delete() {
+        let (store, dir) = temp_store();
+        assert!(store.list().await.unwrap().is_empty());
+
+        let a = store.create("a").await.unwrap();
+        let _b = store.create("b").await.unwrap();
+        assert_eq!(store.list().await.unwrap().len(), 2);
+
+        assert!(store.delete(&a.id).await.unwrap());
+        assert!(!store.delete(&a.id).await.unwrap());
+        assert!(store.get(&a.id).await.unwrap().is_none());
+        assert_eq!(store.list().await.unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_path_traversal_ids() {
+        let (store, _dir) = temp_store();
+        assert!(store.get("../etc/passwd").await.is_err());
+        assert!(store.delete("").await.is_err());
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT
+
+//! Persistent chat sessions
+//!
+//! A session records the conversation history of one workflow so that
+//! follow-up messages can continue where the previous run left off.
+//! - `Session` - a conversation with its stored history
+//! - `SessionStore` - storage backend trait
+//! - `FileSessionStore` - stores each session as a JSON file
+//! - `lock` - serializes updates to one session within the process
+
+mod file;
+
+pub use file::FileSessionStore;
+
+use crate::adk::agent::Agent;
+use crate::adk::model::Content;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+/// A conversation with a workflow and its stored history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    /// Path of the workflow file this session talks to
+    pub workflow: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Conversation turns (user, model and tool messages), without the system prompt
+    #[serde(default)]
+    pub history: Vec<Content>,
+}
+
+/// Lightweight session listing entry (history omitted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub workflow: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub message_count: usize,
+}
+
+impl Session {
+    /// Create a new empty session for a workflow
+    pub fn new(workflow: impl Into<String>) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workflow: workflow.into(),
+            created_at: now.clone(),
+            updated_at: now,
+            history: Vec::new(),
+        }
+    }
+
+    /// Send a message to the agent and record the exchange in this session
+    pub async fn send(
+        &mut self,
+        agent: &dyn Agent,
+        input: String,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let output = agent.run_with_history(&mut self.history, input).await?;
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+        Ok(output)
+    }
+
+    pub fn summary(&self) -> SessionSummary {
+        SessionSummary {
+            id: self.id.clone(),
+            workflow: self.workflow.clone(),
+            created_at: self.created_at.clone(),
+            updated_at: self.updated_at.clone(),
+            message_count: self.history.len(),
+        }
+    }
+}
+
+/// Storage backend for chat sessions
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Create and persist a new session for a workflow
+    async fn create(&self, workflow: &str) -> Result<Session, Box<dyn Error + Send + Sync>> {
+        let session = Session::new(workflow);
+        self.save(&session).await?;
+        Ok(session)
+    }
+
+    /// Load a session by id, returning None if it doesn't exist
+    async fn get(&self, id: &str) -> Result<Option<Session>, Box<dyn Error + Send + Sync>>;
+
+    /// Persist a session, replacing any previous version
+    async fn save(&self, session: &Session) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// List all stored sessions, most recently updated first
+    async fn list(&self) -> Result<Vec<SessionSummary>, Box<dyn Error + Send + Sync>>;
+
+    /// Delete a session, returning whether it existed
+    async fn delete(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+}
+
+/// Per-session locks, removed again once nobody holds or waits for them
+static LOCKS: Lazy<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(Default::default);
+
+/// Exclusive access to one session, released on drop
+pub struct SessionGuard {
+    id: String,
+    _guard: OwnedMutexGuard<()>,
+}
+
+/// Wait until no one else in this process is updating session `id`
+///
+/// Hold the guard across load, run and save so that concurrent messages to
+/// the same session don't overwrite each other's exchanges.
+pub async fn lock(id: &str) -> SessionGuard {
+    let mutex = LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(id.to_string())
+        .or_default()
+        .clone();
+    SessionGuard {
+        id: id.to_string(),
+        _guard: mutex.lock_owned().await,
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+        // Only the map and this guard hold it, so no one is waiting
+        if locks
+            .get(&self.id)
+            .is_some_and(|m| Arc::strong_count(m) <= 2)
+        {
+            locks.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoAgent;
+
+    #[async_trait]
+    impl Agent for EchoAgent {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+            Ok(input)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_send_records_history() {
+        let mut session = Session::new("examples/chat.yaml");
+        let output = session.send(&EchoAgent, "hi".to_string()).await.unwrap();
+
+        assert_eq!(output, "hi");
+        assert_eq!(session.history.len(), 2);
+        assert_eq!(session.summary().message_count, 2);
+        assert_eq!(session.summary().workflow, "examples/chat.yaml");
+    }
+
+    #[tokio::test]
+    async fn test_lock_serializes_one_session() {
+        let guard = lock("locked-session").await;
+        let wait =
+            tokio::time::timeout(std::time::Duration::from_millis(50), lock("locked-session"));
+        assert!(wait.await.is_err(), "second lock must wait");
+        let _other = lock("other-session").await;
+
+        drop(guard);
+        drop(lock("locked-session").await);
+        assert!(!LOCKS.lock().unwrap().contains_key("locked-session"));
+    }
+}
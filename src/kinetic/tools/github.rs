@@ -1,259 +1,687 @@
 // SPDX-License-Identifier: MIT
 
-use crate::adk::tool::Tool;
-use async_trait::async_trait;
-use octocrab::Octocrab;
-use once_cell::sync::Lazy;
+use crate::adk::tool::{tool, Tool};
+use crate::kinetic::human::{HumanInput, HumanRequest, HumanResponse};
+use crate::kinetic::workflow::registry::glob_match;
+use base64::Engine;
+use octocrab::models::repos::DiffEntry;
+use octocrab::{Octocrab, Page};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::env;
 use std::error::Error;
 use std::sync::Arc;
 
-// --- Static schemas (computed once, returned by reference) ---
-
-static FETCH_PR_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "pr_number": {
-                "type": "integer",
-                "description": "The pull request number"
-            },
-            "owner": {
-                "type": "string",
-                "description": "Repository owner/org (optional, defaults to GITHUB_ORG env var)"
-            },
-            "repo": {
-                "type": "string",
-                "description": "Repository name (optional, defaults to GITHUB_REPO env var)"
-            }
-        },
-        "required": ["pr_number"]
-    })
-});
-
-static GET_DIFF_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "pr_number": {
-                "type": "integer",
-                "description": "The pull request number"
-            },
-            "owner": {
-                "type": "string",
-                "description": "Repository owner/org (optional)"
-            },
-            "repo": {
-                "type": "string",
-                "description": "Repository name (optional)"
-            }
-        },
-        "required": ["pr_number"]
-    })
-});
-
-static LIST_MERGED_PRS_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "days": {
-                "type": "integer",
-                "description": "Number of days to look back"
-            }
-        },
-        "required": ["days"]
-    })
-});
-
-static GET_COMMENTS_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "pr_number": {
-                "type": "integer",
-                "description": "The pull request number"
-            },
-            "owner": {
-                "type": "string",
-                "description": "Repository owner/org (optional)"
-            },
-            "repo": {
-                "type": "string",
-                "description": "Repository name (optional)"
-            }
-        },
-        "required": ["pr_number"]
-    })
-});
-
-// --- Fetch Pull Request ---
+// --- GitHub Client Helper ---
+
+/// Items requested per page from list endpoints (the API maximum)
+const PER_PAGE: usize = 100;
+/// Search results the API returns at most for one query
+const MAX_SEARCH_RESULTS: usize = 1000;
+
+/// Settings for the GitHub tools, normally read from the environment
+#[derive(Debug, Clone, Default)]
+pub struct GitHubConfig {
+    pub token: String,
+    /// Default repository owner/org
+    pub owner: String,
+    /// Default repository name
+    pub repo: String,
+    /// API root for GitHub Enterprise or tests (default: https://api.github.com)
+    pub base_url: Option<String>,
+    /// Describe write requests instead of sending them
+    pub dry_run: bool,
+    /// Send writes without an approver; without one they are refused otherwise
+    pub allow_writes: bool,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FetchPRArgs {
-    pub pr_number: u64,
-    /// Optional owner/org - falls back to GITHUB_ORG env var
-    pub owner: Option<String>,
-    /// Optional repo name - falls back to GITHUB_REPO env var
-    pub repo: Option<String>,
+impl GitHubConfig {
+    /// Read `GITHUB_TOKEN`, `GITHUB_ORG`, `GITHUB_REPO` and the optional
+    /// `GITHUB_API_URL`, `GITHUB_DRY_RUN` and `GITHUB_ALLOW_WRITES`
+    pub fn from_env() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self {
+            token: env::var("GITHUB_TOKEN").map_err(|_| "GITHUB_TOKEN must be set")?,
+            owner: env::var("GITHUB_ORG").map_err(|_| "GITHUB_ORG must be set")?,
+            repo: env::var("GITHUB_REPO").map_err(|_| "GITHUB_REPO must be set")?,
+            base_url: env::var("GITHUB_API_URL").ok().filter(|u| !u.is_empty()),
+            dry_run: env_flag("GITHUB_DRY_RUN"),
+            allow_writes: env_flag("GITHUB_ALLOW_WRITES"),
+        })
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FetchPRResult {
-    pub number: u64,
-    pub title: String,
-    pub body: Option<String>,
-    pub state: String,
-    pub author: String,
-    pub files: Vec<String>,
-    pub additions: u64,
-    pub deletions: u64,
+/// Whether the environment variable `name` is set to 1, true or yes
+fn env_flag(name: &str) -> bool {
+    env::var(name)
+        .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
 }
 
-pub struct FetchPRTool {
+/// Authenticated client plus the default repository from the environment
+#[derive(Clone)]
+struct GitHubClient {
     octocrab: Arc<Octocrab>,
     owner: String,
     repo: String,
+    dry_run: bool,
+    allow_writes: bool,
+    /// Asked to approve each write; without one writes need `allow_writes`
+    approval: Option<Arc<dyn HumanInput>>,
+}
+
+/// HTTP method of a write request
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum WriteMethod {
+    Post,
+    Delete,
+}
+
+/// A request that changes something on GitHub
+#[derive(Debug, Serialize)]
+struct WriteRequest {
+    method: WriteMethod,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
 }
 
-impl FetchPRTool {
-    pub fn new(octocrab: Arc<Octocrab>, owner: String, repo: String) -> Self {
+impl WriteRequest {
+    fn post(path: String, body: Value) -> Self {
         Self {
-            octocrab,
-            owner,
-            repo,
+            method: WriteMethod::Post,
+            path,
+            body: Some(body),
         }
     }
-}
 
-#[async_trait]
-impl Tool for FetchPRTool {
-    fn name(&self) -> &str {
-        "fetch_pull_request"
+    fn delete(path: String) -> Self {
+        Self {
+            method: WriteMethod::Delete,
+            path,
+            body: None,
+        }
     }
+}
 
-    fn description(&self) -> &str {
-        "Fetches a pull request by number. Returns PR details including title, body, description, and changed files. Can optionally specify owner/repo."
+impl GitHubClient {
+    /// Use the requested owner/repo, falling back to the defaults
+    fn repo_or_default<'a>(
+        &'a self,
+        owner: &'a Option<String>,
+        repo: &'a Option<String>,
+    ) -> (&'a str, &'a str) {
+        (
+            owner.as_deref().unwrap_or(&self.owner),
+            repo.as_deref().unwrap_or(&self.repo),
+        )
     }
 
-    fn schema(&self) -> &Value {
-        &FETCH_PR_SCHEMA
+    /// GET a list endpoint page by page until it runs out or `limit` items are read
+    ///
+    /// `key` names the array in object responses (search results, check
+    /// runs); the first page is returned as well for their other fields.
+    async fn get_pages(
+        &self,
+        route: &str,
+        params: &[(&str, String)],
+        key: Option<&str>,
+        limit: usize,
+    ) -> Result<(Value, Vec<Value>), Box<dyn Error + Send + Sync>> {
+        let mut first = Value::Null;
+        let mut items = Vec::new();
+        for page in 1.. {
+            let mut query: Vec<(&str, String)> = params.to_vec();
+            query.push(("per_page", PER_PAGE.to_string()));
+            query.push(("page", page.to_string()));
+            let mut response: Value = self.octocrab.get(route, Some(&query)).await?;
+            let batch = match key {
+                Some(key) => response[key].take(),
+                None => response.take(),
+            };
+            let batch = match batch {
+                Value::Array(batch) => batch,
+                _ => Vec::new(),
+            };
+            if page == 1 {
+                first = response;
+            }
+            let done = batch.len() < PER_PAGE;
+            items.extend(batch);
+            if done || items.len() >= limit {
+                break;
+            }
+        }
+        items.truncate(limit);
+        Ok((first, items))
     }
 
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: FetchPRArgs = serde_json::from_value(input)?;
-
-        // Use provided owner/repo or fall back to defaults
-        let owner = args.owner.as_ref().unwrap_or(&self.owner);
-        let repo = args.repo.as_ref().unwrap_or(&self.repo);
+    /// GET a plain-text resource such as a job log, following redirects
+    async fn get_text(&self, route: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let response = self.octocrab._get(route).await?;
+        let status = response.status();
+        let text = self.octocrab.body_to_string(response).await?;
+        if !status.is_success() {
+            return Err(format!("GitHub returned {} for {}: {}", status, route, text).into());
+        }
+        Ok(text)
+    }
 
-        let pr = self.octocrab.pulls(owner, repo).get(args.pr_number).await?;
-        let files = self
+    /// Commit SHA of a pull request's head or base
+    async fn pr_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: u64,
+        version: PRVersion,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let pr: Value = self
             .octocrab
-            .pulls(owner, repo)
-            .list_files(args.pr_number)
+            .get(
+                format!("{}/pulls/{}", repo_route(owner, repo)?, pr_number),
+                None::<&()>,
+            )
             .await?;
+        let side = match version {
+            PRVersion::Head => "head",
+            PRVersion::Base => "base",
+        };
+        pr[side]["sha"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("Pull request {} has no {} commit", pr_number, side).into())
+    }
 
-        let file_names: Vec<String> = files.items.into_iter().map(|f| f.filename).collect();
-
-        let result = FetchPRResult {
-            number: pr.number,
-            title: pr.title.unwrap_or_default(),
-            body: pr.body,
-            state: format!(
-                "{:?}",
-                pr.state.unwrap_or(octocrab::models::IssueState::Open)
+    /// Clear a write with the dry-run flag and the approver before sending it
+    ///
+    /// Returns the preview to give back instead of sending in dry-run mode,
+    /// and an error if the user does not approve or there is nobody to ask
+    /// and writes were not allowed.
+    async fn confirm_write(
+        &self,
+        action: &str,
+        requests: &[WriteRequest],
+    ) -> Result<Option<Value>, Box<dyn Error + Send + Sync>> {
+        if self.dry_run {
+            log::info!("Dry run, not sending: {}", action);
+            return Ok(Some(json!({
+                "dry_run": true,
+                "action": action,
+                "requests": requests,
+            })));
+        }
+        let Some(approval) = &self.approval else {
+            if self.allow_writes {
+                return Ok(None);
+            }
+            return Err(format!(
+                "Refusing to {}: there is nobody to approve GitHub writes; \
+                 set GITHUB_ALLOW_WRITES=1 to allow them",
+                lowercase_first(action)
+            )
+            .into());
+        };
+        let request = HumanRequest {
+            source: "github".to_string(),
+            message: format!(
+                "{}\n{}",
+                action,
+                serde_json::to_string_pretty(requests).unwrap_or_default()
             ),
-            author: pr
-                .user
-                .map(|u| u.login)
-                .unwrap_or_else(|| "unknown".to_string()),
-            files: file_names,
-            additions: pr.additions.unwrap_or(0),
-            deletions: pr.deletions.unwrap_or(0),
+            schema: json!({"type": "object", "properties": {}}),
         };
+        match approval.ask(&request).await? {
+            HumanResponse::Accept(_) => Ok(None),
+            HumanResponse::Decline => Err(format!("The user declined: {}", action).into()),
+            HumanResponse::Cancel => Err(format!("The user cancelled: {}", action).into()),
+        }
+    }
 
-        Ok(serde_json::to_value(result)?)
+    async fn send(&self, request: &WriteRequest) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Ok(match request.method {
+            WriteMethod::Post => {
+                self.octocrab
+                    .post(&request.path, request.body.as_ref())
+                    .await?
+            }
+            WriteMethod::Delete => self.octocrab.delete(&request.path, None::<&()>).await?,
+        })
     }
 }
 
-// --- Get Pull Request Diff ---
+/// `action` with its first letter lowercased, to continue a sentence
+fn lowercase_first(action: &str) -> String {
+    let mut chars = action.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// API path of a repository, with `owner` and `repo` encoded
+fn repo_route(owner: &str, repo: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    Ok(format!(
+        "/repos/{}/{}",
+        path_segment(owner)?,
+        path_segment(repo)?
+    ))
+}
+
+/// Encode a git ref for an API path, keeping the `/` of names like `release/1.0`
+fn ref_path(git_ref: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    if git_ref.is_empty() || git_ref.contains("..") {
+        return Err(format!("Invalid git ref: '{}'", git_ref).into());
+    }
+    Ok(git_ref
+        .split('/')
+        .map(path_segment)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("/"))
+}
+
+/// Encode a repository file path for the contents API, keeping its `/`s
+fn file_path(path: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    Ok(path
+        .split('/')
+        .map(path_segment)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("/"))
+}
+
+/// Percent-encode a value used as one segment of an API path
+///
+/// `.` and `..` are refused: URL resolution would drop or climb them (even
+/// percent-encoded), sending the request, and the token, to another endpoint.
+fn path_segment(value: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    if value == "." || value == ".." {
+        return Err(format!("Invalid path segment: '{}'", value).into());
+    }
+    Ok(value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect())
+}
+
+/// Result of a write tool: the API's answer, or what would have been sent
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WriteResult<T> {
+    Done(T),
+    DryRun(Value),
+}
+
+// --- Fetch Pull Request ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FetchPRArgs {
+    /// The pull request number
+    pub pr_number: u64,
+    /// Repository owner/org (optional, defaults to GITHUB_ORG env var)
+    pub owner: Option<String>,
+    /// Repository name (optional, defaults to GITHUB_REPO env var)
+    pub repo: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
+pub struct FetchPRResult {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+    pub author: String,
+    pub files: Vec<String>,
+    pub additions: u64,
+    pub deletions: u64,
+}
+
+/// Fetches a pull request by number. Returns PR details including title, body, description, and changed files. Can optionally specify owner/repo.
+#[tool(name = "fetch_pull_request", struct_name = "FetchPRTool")]
+async fn fetch_pr(
+    client: &GitHubClient,
+    args: FetchPRArgs,
+) -> Result<FetchPRResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+
+    let pr = client
+        .octocrab
+        .pulls(owner, repo)
+        .get(args.pr_number)
+        .await?;
+    let files = client
+        .octocrab
+        .pulls(owner, repo)
+        .list_files(args.pr_number)
+        .await?;
+
+    let file_names: Vec<String> = files.items.into_iter().map(|f| f.filename).collect();
+
+    Ok(FetchPRResult {
+        number: pr.number,
+        title: pr.title.unwrap_or_default(),
+        body: pr.body,
+        state: format!(
+            "{:?}",
+            pr.state.unwrap_or(octocrab::models::IssueState::Open)
+        ),
+        author: pr
+            .user
+            .map(|u| u.login)
+            .unwrap_or_else(|| "unknown".to_string()),
+        files: file_names,
+        additions: pr.additions.unwrap_or(0),
+        deletions: pr.deletions.unwrap_or(0),
+    })
+}
+
+// --- Get Pull Request Diff ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetDiffArgs {
+    /// The pull request number
     pub pr_number: u64,
+    /// Only include files whose path matches one of these patterns (`*` matches any characters, e.g. `src/*.rs`)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Leave out files whose path matches one of these patterns (e.g. `*.lock`)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Repository owner/org (optional)
     pub owner: Option<String>,
+    /// Repository name (optional)
     pub repo: Option<String>,
 }
 
+/// One line of a hunk with its line numbers in the old and new file
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    /// `add`, `remove` or `context`
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_line: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<u64>,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line, including any section heading
+    pub header: String,
+    pub old_start: u64,
+    pub old_lines: u64,
+    pub new_start: u64,
+    pub new_lines: u64,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffFile {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>,
+    /// added, removed, modified, renamed, copied, changed or unchanged
+    pub status: String,
+    pub additions: u64,
+    pub deletions: u64,
+    pub hunks: Vec<DiffHunk>,
+    /// Why there are no hunks, e.g. for binary or very large files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetDiffResult {
-    pub diff: String,
+    pub files: Vec<DiffFile>,
+    /// Changed files in the pull request, before include/exclude
+    pub total_files: usize,
+    pub additions: u64,
+    pub deletions: u64,
 }
 
-pub struct GetDiffTool {
-    octocrab: Arc<Octocrab>,
-    owner: String,
-    repo: String,
+/// Gets the changes of a pull request as structured diffs: every changed file with its status, additions/deletions and hunks whose lines carry old and new line numbers (use these for inline review comments). Filter files with include/exclude patterns. Can optionally specify owner/repo.
+#[tool(name = "get_pull_request_diff")]
+async fn get_diff(
+    client: &GitHubClient,
+    args: GetDiffArgs,
+) -> Result<GetDiffResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+
+    let first_page: Page<DiffEntry> = client
+        .octocrab
+        .get(
+            format!(
+                "{}/pulls/{}/files",
+                repo_route(owner, repo)?,
+                args.pr_number
+            ),
+            Some(&[("per_page", 100)]),
+        )
+        .await?;
+    let entries = client.octocrab.all_pages(first_page).await?;
+    let total_files = entries.len();
+
+    let files: Vec<DiffFile> = entries
+        .into_iter()
+        .filter(|e| path_selected(&e.filename, &args.include, &args.exclude))
+        .map(|entry| {
+            let note = match &entry.patch {
+                Some(_) => None,
+                None if entry.changes == 0 => Some("No content changes".to_string()),
+                None => Some(
+                    "No patch available (binary or too large); use get_file_contents".to_string(),
+                ),
+            };
+            DiffFile {
+                hunks: entry.patch.as_deref().map(parse_patch).unwrap_or_default(),
+                status: serde_json::to_value(&entry.status)
+                    .ok()
+                    .and_then(|s| s.as_str().map(str::to_string))
+                    .unwrap_or_else(|| "changed".to_string()),
+                path: entry.filename,
+                previous_path: entry.previous_filename,
+                additions: entry.additions,
+                deletions: entry.deletions,
+                note,
+            }
+        })
+        .collect();
+
+    Ok(GetDiffResult {
+        total_files,
+        additions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        files,
+    })
 }
 
-impl GetDiffTool {
-    pub fn new(octocrab: Arc<Octocrab>, owner: String, repo: String) -> Self {
-        Self {
-            octocrab,
-            owner,
-            repo,
-        }
-    }
+/// Whether `path` passes the include (any, if given) and exclude patterns
+fn path_selected(path: &str, include: &[String], exclude: &[String]) -> bool {
+    (include.is_empty() || include.iter().any(|p| glob_match(p, path)))
+        && !exclude.iter().any(|p| glob_match(p, path))
 }
 
-#[async_trait]
-impl Tool for GetDiffTool {
-    fn name(&self) -> &str {
-        "get_pull_request_diff"
+/// Split a unified diff patch into hunks with per-line numbers
+fn parse_patch(patch: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in patch.lines() {
+        if let Some(hunk) = parse_hunk_header(line) {
+            old_line = hunk.old_start;
+            new_line = hunk.new_start;
+            hunks.push(hunk);
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+        let (kind, old, new) = match line.chars().next() {
+            Some('+') => ("add", None, Some(new_line)),
+            Some('-') => ("remove", Some(old_line), None),
+            // "\ No newline at end of file"
+            Some('\\') => continue,
+            _ => ("context", Some(old_line), Some(new_line)),
+        };
+        if old.is_some() {
+            old_line += 1;
+        }
+        if new.is_some() {
+            new_line += 1;
+        }
+        hunk.lines.push(DiffLine {
+            kind: kind.to_string(),
+            old_line: old,
+            new_line: new,
+            text: line.get(1..).unwrap_or_default().to_string(),
+        });
     }
+    hunks
+}
 
-    fn description(&self) -> &str {
-        "Gets the full diff for a pull request showing all code changes. Can optionally specify owner/repo."
-    }
+/// Parse `@@ -a,b +c,d @@ heading`; a missing count means 1
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let ranges = line.strip_prefix("@@ -")?;
+    let (ranges, _) = ranges.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |r: &str| -> Option<(u64, u64)> {
+        match r.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((r.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    Some(DiffHunk {
+        header: line.to_string(),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    })
+}
 
-    fn schema(&self) -> &Value {
-        &GET_DIFF_SCHEMA
-    }
+// --- Get File Contents ---
 
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: GetDiffArgs = serde_json::from_value(input)?;
+/// Which version of a pull request's files to read
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PRVersion {
+    #[default]
+    Head,
+    Base,
+}
 
-        let owner = args.owner.as_ref().unwrap_or(&self.owner);
-        let repo = args.repo.as_ref().unwrap_or(&self.repo);
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetFileContentsArgs {
+    /// File path relative to the repository root
+    pub path: String,
+    /// Read the file as of this pull request's head or base
+    pub pr_number: Option<u64>,
+    /// head (default): the PR's changes; base: the target branch it merges into
+    pub version: Option<PRVersion>,
+    /// Branch, tag or commit SHA to read when no pr_number is given (default branch if omitted)
+    pub git_ref: Option<String>,
+    /// First line to return (1-based, default 1)
+    pub start_line: Option<usize>,
+    /// Last line to return (default: end of file)
+    pub end_line: Option<usize>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
 
-        let files = self
-            .octocrab
-            .pulls(owner, repo)
-            .list_files(args.pr_number)
-            .await?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileContentsResult {
+    pub path: String,
+    /// Commit SHA or ref the file was read at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub total_lines: usize,
+    pub content: String,
+}
 
-        let mut diffs = Vec::new();
-        for file in files.items {
-            if let Some(patch) = file.patch {
-                diffs.push(format!("File: {}\n{}\n", file.filename, patch));
-            }
+/// Gets the contents of a file, optionally at a pull request's head or base, for context around a diff. Use start_line/end_line to read part of a large file. Can optionally specify owner/repo.
+#[tool(name = "get_file_contents", struct_name = "GetFileContentsTool")]
+async fn get_file_contents(
+    client: &GitHubClient,
+    args: GetFileContentsArgs,
+) -> Result<FileContentsResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+
+    let git_ref = match args.pr_number {
+        Some(pr_number) => Some(
+            client
+                .pr_commit(owner, repo, pr_number, args.version.unwrap_or_default())
+                .await?,
+        ),
+        None => args.git_ref.clone(),
+    };
+
+    let route = format!(
+        "{}/contents/{}",
+        repo_route(owner, repo)?,
+        file_path(&args.path)?
+    );
+    let file: Value = match &git_ref {
+        Some(git_ref) => {
+            client
+                .octocrab
+                .get(route, Some(&[("ref", git_ref)]))
+                .await?
         }
+        None => client.octocrab.get(route, None::<&()>).await?,
+    };
 
-        let result = GetDiffResult {
-            diff: diffs.join("\n---\n\n"),
-        };
-
-        Ok(serde_json::to_value(result)?)
+    if file.is_array() {
+        return Err(format!("{} is a directory", args.path).into());
+    }
+    let encoded = file["content"].as_str().unwrap_or_default();
+    if encoded.is_empty() && file["size"].as_u64().unwrap_or(0) > 0 {
+        return Err(format!("{} is too large to read through the API", args.path).into());
     }
+    let bytes =
+        base64::engine::general_purpose::STANDARD.decode(encoded.replace(['\n', '\r'], ""))?;
+    let text = String::from_utf8(bytes).map_err(|_| format!("{} is not a text file", args.path))?;
+
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    let start_line = args.start_line.unwrap_or(1).max(1);
+    let end_line = args.end_line.unwrap_or(total_lines).min(total_lines);
+    let content = if start_line > end_line {
+        String::new()
+    } else {
+        lines[start_line - 1..end_line].join("\n")
+    };
+
+    Ok(FileContentsResult {
+        path: args.path,
+        git_ref,
+        start_line,
+        end_line,
+        total_lines,
+        content,
+    })
 }
 
 // --- List Merged PRs ---
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ListMergedPRsArgs {
+    /// Number of days to look back
     pub days: u64,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -262,7 +690,7 @@ pub struct MergedPRInfo {
     pub title: String,
     pub author: String,
     pub merged_at: String,
-    pub merge_sha: String,
+    pub merge_sha: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -270,75 +698,91 @@ pub struct ListMergedPRsResult {
     pub prs: Vec<MergedPRInfo>,
 }
 
-pub struct ListMergedPRsTool {
-    octocrab: Arc<Octocrab>,
-    owner: String,
-    repo: String,
-}
-
-impl ListMergedPRsTool {
-    pub fn new(octocrab: Arc<Octocrab>, owner: String, repo: String) -> Self {
-        Self {
-            octocrab,
-            owner,
-            repo,
+/// Lists pull requests that were merged within the specified number of days, with their merge commit SHAs. Can optionally specify owner/repo.
+#[tool(name = "list_merged_prs", struct_name = "ListMergedPRsTool")]
+async fn list_merged_prs(
+    client: &GitHubClient,
+    args: ListMergedPRsArgs,
+) -> Result<ListMergedPRsResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(args.days as i64);
+    let since = |field: &Value| {
+        field
+            .as_str()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t >= cutoff)
+    };
+
+    // Closed PRs, most recently updated first: a PR merged after the cutoff
+    // was updated after it too, so paging stops at the first older update
+    let route = format!("{}/pulls", repo_route(owner, repo)?);
+    let mut prs = Vec::new();
+    for page in 1.. {
+        let query = [
+            ("state", "closed".to_string()),
+            ("sort", "updated".to_string()),
+            ("direction", "desc".to_string()),
+            ("per_page", PER_PAGE.to_string()),
+            ("page", page.to_string()),
+        ];
+        let batch: Vec<Value> = client.octocrab.get(&route, Some(&query)).await?;
+        let full_page = batch.len() == PER_PAGE;
+        let mut reached_cutoff = false;
+        for pr in batch {
+            if !since(&pr["updated_at"]) {
+                reached_cutoff = true;
+                break;
+            }
+            if !since(&pr["merged_at"]) {
+                continue;
+            }
+            prs.push(MergedPRInfo {
+                number: pr["number"].as_u64().unwrap_or_default(),
+                title: pr["title"].as_str().unwrap_or_default().to_string(),
+                author: login(&pr["user"]),
+                merged_at: pr["merged_at"].as_str().unwrap_or_default().to_string(),
+                merge_sha: string_field(&pr["merge_commit_sha"]),
+            });
+        }
+        if reached_cutoff || !full_page {
+            break;
         }
-    }
-}
-
-#[async_trait]
-impl Tool for ListMergedPRsTool {
-    fn name(&self) -> &str {
-        "list_merged_prs"
-    }
-
-    fn description(&self) -> &str {
-        "Lists pull requests that were merged within the specified number of days."
-    }
-
-    fn schema(&self) -> &Value {
-        &LIST_MERGED_PRS_SCHEMA
     }
 
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: ListMergedPRsArgs = serde_json::from_value(input)?;
-
-        let date = chrono::Utc::now() - chrono::Duration::days(args.days as i64);
-        let date_str = date.format("%Y-%m-%d").to_string();
-        let query = format!(
-            "is:pr is:merged repo:{}/{} merged:>{}",
-            self.owner, self.repo, date_str
-        );
+    Ok(ListMergedPRsResult { prs })
+}
 
-        let page = self
-            .octocrab
-            .search()
-            .issues_and_pull_requests(&query)
-            .send()
-            .await?;
+/// Login of a user object, or "unknown" for deleted accounts
+fn login(user: &Value) -> String {
+    user["login"].as_str().unwrap_or("unknown").to_string()
+}
 
-        let mut prs = Vec::new();
-        for issue in page.items {
-            prs.push(MergedPRInfo {
-                number: issue.number,
-                title: issue.title,
-                author: issue.user.login,
-                merged_at: issue.closed_at.map(|d| d.to_rfc3339()).unwrap_or_default(),
-                merge_sha: "unknown".to_string(),
-            });
-        }
+/// Names of the label objects in `labels`
+fn label_names(labels: &Value) -> Vec<String> {
+    labels
+        .as_array()
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|l| l["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-        let result = ListMergedPRsResult { prs };
-        Ok(serde_json::to_value(result)?)
-    }
+fn string_field(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_string)
 }
 
 // --- Get Pull Request Comments ---
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetCommentsArgs {
+    /// The pull request number
     pub pr_number: u64,
+    /// Repository owner/org (optional)
     pub owner: Option<String>,
+    /// Repository name (optional)
     pub repo: Option<String>,
 }
 
@@ -355,118 +799,1191 @@ pub struct GetCommentsResult {
     pub comments: Vec<PRComment>,
 }
 
-pub struct GetPRCommentsTool {
-    octocrab: Arc<Octocrab>,
-    owner: String,
-    repo: String,
-}
+/// Gets all comments on a pull request including review comments and issue comments. Can optionally specify owner/repo.
+#[tool(name = "get_pull_request_comments", struct_name = "GetPRCommentsTool")]
+async fn get_pr_comments(
+    client: &GitHubClient,
+    args: GetCommentsArgs,
+) -> Result<GetCommentsResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+
+    let mut all_comments = Vec::new();
+
+    // Fetch issue comments (general PR comments)
+    let issue_comments = client
+        .octocrab
+        .issues(owner, repo)
+        .list_comments(args.pr_number)
+        .send()
+        .await?;
+
+    for comment in issue_comments.items {
+        all_comments.push(PRComment {
+            author: comment.user.login,
+            body: comment.body.unwrap_or_default(),
+            created_at: comment.created_at.to_rfc3339(),
+            comment_type: "issue".to_string(),
+        });
+    }
 
-impl GetPRCommentsTool {
-    pub fn new(octocrab: Arc<Octocrab>, owner: String, repo: String) -> Self {
-        Self {
-            octocrab,
-            owner,
-            repo,
-        }
+    // Fetch review comments (inline code comments)
+    let review_comments = client
+        .octocrab
+        .pulls(owner, repo)
+        .list_comments(Some(args.pr_number))
+        .send()
+        .await?;
+
+    for comment in review_comments.items {
+        let author = comment
+            .user
+            .map(|u| u.login)
+            .unwrap_or_else(|| "unknown".to_string());
+        all_comments.push(PRComment {
+            author,
+            body: comment.body,
+            created_at: comment.created_at.to_rfc3339(),
+            comment_type: "review".to_string(),
+        });
     }
+
+    // Sort by created_at
+    all_comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    Ok(GetCommentsResult {
+        comments: all_comments,
+    })
 }
 
-#[async_trait]
-impl Tool for GetPRCommentsTool {
-    fn name(&self) -> &str {
-        "get_pull_request_comments"
-    }
+// --- Search Code ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchCodeArgs {
+    /// GitHub code search query, e.g. `fn parse_patch language:rust`; limited to the default repository unless it has a repo:, org: or user: qualifier
+    pub query: String,
+    /// Maximum results to return (default 30)
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CodeMatch {
+    pub path: String,
+    pub repository: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchCodeResult {
+    pub total_count: u64,
+    pub matches: Vec<CodeMatch>,
+}
+
+/// Searches code on GitHub and returns matching file paths. Searches the default repository unless the query names another repo, org or user.
+#[tool(name = "search_code")]
+async fn search_code(
+    client: &GitHubClient,
+    args: SearchCodeArgs,
+) -> Result<SearchCodeResult, Box<dyn Error + Send + Sync>> {
+    let scoped = ["repo:", "org:", "user:"]
+        .iter()
+        .any(|q| args.query.contains(q));
+    let query = if scoped {
+        args.query
+    } else {
+        format!("{} repo:{}/{}", args.query, client.owner, client.repo)
+    };
+
+    let limit = args.max_results.unwrap_or(30).min(MAX_SEARCH_RESULTS);
+    let (first, items) = client
+        .get_pages("/search/code", &[("q", query)], Some("items"), limit)
+        .await?;
+    Ok(SearchCodeResult {
+        total_count: first["total_count"].as_u64().unwrap_or(items.len() as u64),
+        matches: items
+            .iter()
+            .map(|item| CodeMatch {
+                path: item["path"].as_str().unwrap_or_default().to_string(),
+                repository: item["repository"]["full_name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                url: item["html_url"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect(),
+    })
+}
+
+// --- List Issues ---
+
+/// Which issues to list by state
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueStateFilter {
+    #[default]
+    Open,
+    Closed,
+    All,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListIssuesArgs {
+    /// open (default), closed or all
+    pub state: Option<IssueStateFilter>,
+    /// Only issues with all of these labels
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Only issues assigned to this login (`none` for unassigned)
+    pub assignee: Option<String>,
+    /// Maximum issues to return (default 30)
+    pub max_results: Option<usize>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueSummary {
+    pub number: u64,
+    pub title: String,
+    pub state: String,
+    pub author: String,
+    pub labels: Vec<String>,
+    pub comments: u64,
+    pub created_at: String,
+    pub updated_at: String,
+}
 
-    fn description(&self) -> &str {
-        "Gets all comments on a pull request including review comments and issue comments. Can optionally specify owner/repo."
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListIssuesResult {
+    pub issues: Vec<IssueSummary>,
+}
+
+/// Lists issues (not pull requests) in a repository, most recently created first, filtered by state, labels and assignee. Can optionally specify owner/repo.
+#[tool(name = "list_issues")]
+async fn list_issues(
+    client: &GitHubClient,
+    args: ListIssuesArgs,
+) -> Result<ListIssuesResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let state = serde_json::to_value(args.state.unwrap_or_default())?;
+    let mut params = vec![("state", state.as_str().unwrap_or("open").to_string())];
+    if !args.labels.is_empty() {
+        params.push(("labels", args.labels.join(",")));
+    }
+    if let Some(assignee) = args.assignee {
+        params.push(("assignee", assignee));
     }
 
-    fn schema(&self) -> &Value {
-        &GET_COMMENTS_SCHEMA
+    let limit = args.max_results.unwrap_or(30);
+    let route = format!("{}/issues", repo_route(owner, repo)?);
+    let mut issues = Vec::new();
+    // The endpoint also returns pull requests; keep paging past them
+    for page in 1.. {
+        let mut query = params.clone();
+        query.push(("per_page", PER_PAGE.to_string()));
+        query.push(("page", page.to_string()));
+        let batch: Vec<Value> = client.octocrab.get(&route, Some(&query)).await?;
+        let done = batch.len() < PER_PAGE;
+        issues.extend(
+            batch
+                .into_iter()
+                .filter(|item| item.get("pull_request").is_none())
+                .map(|item| IssueSummary {
+                    number: item["number"].as_u64().unwrap_or_default(),
+                    title: item["title"].as_str().unwrap_or_default().to_string(),
+                    state: item["state"].as_str().unwrap_or_default().to_string(),
+                    author: login(&item["user"]),
+                    labels: label_names(&item["labels"]),
+                    comments: item["comments"].as_u64().unwrap_or_default(),
+                    created_at: item["created_at"].as_str().unwrap_or_default().to_string(),
+                    updated_at: item["updated_at"].as_str().unwrap_or_default().to_string(),
+                }),
+        );
+        if done || issues.len() >= limit {
+            break;
+        }
     }
+    issues.truncate(limit);
+    Ok(ListIssuesResult { issues })
+}
+
+// --- Get Issue ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetIssueArgs {
+    /// The issue number
+    pub number: u64,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
 
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: GetCommentsArgs = serde_json::from_value(input)?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueComment {
+    pub author: String,
+    pub body: String,
+    pub created_at: String,
+}
 
-        let owner = args.owner.as_ref().unwrap_or(&self.owner);
-        let repo = args.repo.as_ref().unwrap_or(&self.repo);
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueDetails {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+    pub author: String,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    pub created_at: String,
+    pub closed_at: Option<String>,
+    pub comments: Vec<IssueComment>,
+}
 
-        let mut all_comments = Vec::new();
+/// Gets an issue with its description, labels, assignees and all comments. Can optionally specify owner/repo.
+#[tool(name = "get_issue", struct_name = "GetIssueTool")]
+async fn get_issue(
+    client: &GitHubClient,
+    args: GetIssueArgs,
+) -> Result<IssueDetails, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let route = format!("{}/issues/{}", repo_route(owner, repo)?, args.number);
+    let issue: Value = client.octocrab.get(&route, None::<&()>).await?;
+    let (_, comments) = client
+        .get_pages(&format!("{}/comments", route), &[], None, usize::MAX)
+        .await?;
+
+    Ok(IssueDetails {
+        number: issue["number"].as_u64().unwrap_or(args.number),
+        title: issue["title"].as_str().unwrap_or_default().to_string(),
+        body: string_field(&issue["body"]),
+        state: issue["state"].as_str().unwrap_or_default().to_string(),
+        author: login(&issue["user"]),
+        labels: label_names(&issue["labels"]),
+        assignees: issue["assignees"]
+            .as_array()
+            .map(|users| users.iter().map(login).collect())
+            .unwrap_or_default(),
+        created_at: issue["created_at"].as_str().unwrap_or_default().to_string(),
+        closed_at: string_field(&issue["closed_at"]),
+        comments: comments
+            .iter()
+            .map(|c| IssueComment {
+                author: login(&c["user"]),
+                body: c["body"].as_str().unwrap_or_default().to_string(),
+                created_at: c["created_at"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect(),
+    })
+}
 
-        // Fetch issue comments (general PR comments)
-        let issue_comments = self
-            .octocrab
-            .issues(owner, repo)
-            .list_comments(args.pr_number)
-            .send()
+// --- List Commits Between Refs ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListCommitsArgs {
+    /// Older ref: branch, tag or commit SHA
+    pub base: String,
+    /// Newer ref: branch, tag or commit SHA
+    pub head: String,
+    /// Maximum commits to return (default 100)
+    pub max_results: Option<usize>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub author: String,
+    pub date: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListCommitsResult {
+    /// ahead, behind, diverged or identical
+    pub status: String,
+    pub ahead_by: u64,
+    pub behind_by: u64,
+    pub total_commits: u64,
+    pub commits: Vec<CommitInfo>,
+}
+
+/// Lists the commits reachable from head but not from base (e.g. between two release tags), oldest first. Can optionally specify owner/repo.
+#[tool(name = "list_commits")]
+async fn list_commits(
+    client: &GitHubClient,
+    args: ListCommitsArgs,
+) -> Result<ListCommitsResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let route = format!(
+        "{}/compare/{}...{}",
+        repo_route(owner, repo)?,
+        ref_path(&args.base)?,
+        ref_path(&args.head)?
+    );
+    let (first, commits) = client
+        .get_pages(
+            &route,
+            &[],
+            Some("commits"),
+            args.max_results.unwrap_or(100),
+        )
+        .await?;
+
+    Ok(ListCommitsResult {
+        status: first["status"].as_str().unwrap_or_default().to_string(),
+        ahead_by: first["ahead_by"].as_u64().unwrap_or_default(),
+        behind_by: first["behind_by"].as_u64().unwrap_or_default(),
+        total_commits: first["total_commits"].as_u64().unwrap_or_default(),
+        commits: commits
+            .iter()
+            .map(|c| CommitInfo {
+                sha: c["sha"].as_str().unwrap_or_default().to_string(),
+                author: c["author"]["login"]
+                    .as_str()
+                    .or(c["commit"]["author"]["name"].as_str())
+                    .unwrap_or("unknown")
+                    .to_string(),
+                date: c["commit"]["author"]["date"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                message: c["commit"]["message"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+            .collect(),
+    })
+}
+
+// --- Check Runs ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetChecksArgs {
+    /// The pull request whose head commit to read checks for
+    pub pr_number: u64,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckRunInfo {
+    pub name: String,
+    /// queued, in_progress or completed
+    pub status: String,
+    /// success, failure, neutral, cancelled, skipped, timed_out or action_required, once completed
+    pub conclusion: Option<String>,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetChecksResult {
+    pub sha: String,
+    pub total: usize,
+    pub failed: usize,
+    pub check_runs: Vec<CheckRunInfo>,
+}
+
+/// Gets the CI check runs for a pull request's head commit with their status, conclusion and summary. Use get_workflow_run_logs to read the logs of failed GitHub Actions jobs. Can optionally specify owner/repo.
+#[tool(name = "get_check_runs", struct_name = "GetCheckRunsTool")]
+async fn get_check_runs(
+    client: &GitHubClient,
+    args: GetChecksArgs,
+) -> Result<GetChecksResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let sha = client
+        .pr_commit(owner, repo, args.pr_number, PRVersion::Head)
+        .await?;
+    let (_, runs) = client
+        .get_pages(
+            &format!(
+                "{}/commits/{}/check-runs",
+                repo_route(owner, repo)?,
+                path_segment(&sha)?
+            ),
+            &[],
+            Some("check_runs"),
+            usize::MAX,
+        )
+        .await?;
+
+    let check_runs: Vec<CheckRunInfo> = runs
+        .iter()
+        .map(|run| CheckRunInfo {
+            name: run["name"].as_str().unwrap_or_default().to_string(),
+            status: run["status"].as_str().unwrap_or_default().to_string(),
+            conclusion: string_field(&run["conclusion"]),
+            url: string_field(&run["html_url"]),
+            title: string_field(&run["output"]["title"]),
+            summary: string_field(&run["output"]["summary"]),
+        })
+        .collect();
+    Ok(GetChecksResult {
+        sha,
+        total: check_runs.len(),
+        failed: check_runs
+            .iter()
+            .filter(|run| is_failure(run.conclusion.as_deref()))
+            .count(),
+        check_runs,
+    })
+}
+
+/// Whether a check or job conclusion means it failed
+fn is_failure(conclusion: Option<&str>) -> bool {
+    matches!(
+        conclusion,
+        Some("failure" | "timed_out" | "action_required" | "startup_failure")
+    )
+}
+
+// --- Workflow Run Logs ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetRunLogsArgs {
+    /// Read the GitHub Actions runs of this pull request's head commit
+    pub pr_number: Option<u64>,
+    /// Read this workflow run instead
+    pub run_id: Option<u64>,
+    /// Only jobs that failed (default true)
+    pub failed_only: Option<bool>,
+    /// Lines kept from the end of each job log (default 200)
+    pub max_lines: Option<usize>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobLog {
+    pub id: u64,
+    pub name: String,
+    pub conclusion: Option<String>,
+    pub failed_steps: Vec<String>,
+    pub total_lines: usize,
+    /// The last `max_lines` lines of the log
+    pub log: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowRunLogs {
+    pub id: u64,
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub url: String,
+    pub jobs: Vec<JobLog>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetRunLogsResult {
+    pub runs: Vec<WorkflowRunLogs>,
+}
+
+/// Gets GitHub Actions workflow runs for a pull request (or one run by id) with the tail of each failed job's log and its failed steps, to diagnose CI failures. Can optionally specify owner/repo.
+#[tool(name = "get_workflow_run_logs", struct_name = "GetRunLogsTool")]
+async fn get_workflow_run_logs(
+    client: &GitHubClient,
+    args: GetRunLogsArgs,
+) -> Result<GetRunLogsResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let actions = format!("{}/actions", repo_route(owner, repo)?);
+    let runs = match (args.run_id, args.pr_number) {
+        (Some(run_id), _) => vec![
+            client
+                .octocrab
+                .get(format!("{}/runs/{}", actions, run_id), None::<&()>)
+                .await?,
+        ],
+        (None, Some(pr_number)) => {
+            let sha = client
+                .pr_commit(owner, repo, pr_number, PRVersion::Head)
+                .await?;
+            let (_, runs) = client
+                .get_pages(
+                    &format!("{}/runs", actions),
+                    &[("head_sha", sha)],
+                    Some("workflow_runs"),
+                    usize::MAX,
+                )
+                .await?;
+            runs
+        }
+        (None, None) => return Err("Give a pr_number or a run_id".into()),
+    };
+
+    let failed_only = args.failed_only.unwrap_or(true);
+    let max_lines = args.max_lines.unwrap_or(200);
+    let mut result = Vec::new();
+    for run in runs {
+        let run_id = run["id"].as_u64().unwrap_or_default();
+        let (_, jobs) = client
+            .get_pages(
+                &format!("{}/runs/{}/jobs", actions, run_id),
+                &[],
+                Some("jobs"),
+                usize::MAX,
+            )
             .await?;
 
-        for comment in issue_comments.items {
-            all_comments.push(PRComment {
-                author: comment.user.login,
-                body: comment.body.unwrap_or_default(),
-                created_at: comment.created_at.to_rfc3339(),
-                comment_type: "issue".to_string(),
+        let mut job_logs = Vec::new();
+        for job in jobs {
+            let conclusion = string_field(&job["conclusion"]);
+            if failed_only && !is_failure(conclusion.as_deref()) {
+                continue;
+            }
+            let job_id = job["id"].as_u64().unwrap_or_default();
+            let text = client
+                .get_text(&format!("{}/jobs/{}/logs", actions, job_id))
+                .await
+                .unwrap_or_else(|e| format!("[log unavailable: {}]", e));
+            let lines: Vec<&str> = text.lines().collect();
+            job_logs.push(JobLog {
+                id: job_id,
+                name: job["name"].as_str().unwrap_or_default().to_string(),
+                failed_steps: job["steps"]
+                    .as_array()
+                    .map(|steps| {
+                        steps
+                            .iter()
+                            .filter(|step| is_failure(step["conclusion"].as_str()))
+                            .filter_map(|step| string_field(&step["name"]))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                total_lines: lines.len(),
+                log: lines[lines.len().saturating_sub(max_lines)..].join("\n"),
+                conclusion,
             });
         }
 
-        // Fetch review comments (inline code comments)
-        let review_comments = self
-            .octocrab
-            .pulls(owner, repo)
-            .list_comments(Some(args.pr_number))
-            .send()
-            .await?;
+        if failed_only && job_logs.is_empty() {
+            continue;
+        }
+        result.push(WorkflowRunLogs {
+            id: run_id,
+            name: run["name"].as_str().unwrap_or_default().to_string(),
+            status: run["status"].as_str().unwrap_or_default().to_string(),
+            conclusion: string_field(&run["conclusion"]),
+            url: run["html_url"].as_str().unwrap_or_default().to_string(),
+            jobs: job_logs,
+        });
+    }
+    Ok(GetRunLogsResult { runs: result })
+}
 
-        for comment in review_comments.items {
-            let author = comment
-                .user
-                .map(|u| u.login)
-                .unwrap_or_else(|| "unknown".to_string());
-            all_comments.push(PRComment {
-                author,
-                body: comment.body,
-                created_at: comment.created_at.to_rfc3339(),
-                comment_type: "review".to_string(),
-            });
+// --- Blame ---
+
+const BLAME_QUERY: &str = r#"query($owner: String!, $repo: String!, $ref: String!, $path: String!) {
+  repository(owner: $owner, name: $repo) {
+    object(expression: $ref) {
+      ... on Commit {
+        blame(path: $path) {
+          ranges {
+            startingLine
+            endingLine
+            commit { oid messageHeadline author { name date } }
+          }
         }
+      }
+    }
+  }
+}"#;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetBlameArgs {
+    /// File path relative to the repository root
+    pub path: String,
+    /// First line of the range (default 1)
+    pub start_line: Option<u64>,
+    /// Last line of the range (default: end of file)
+    pub end_line: Option<u64>,
+    /// Branch, tag or commit SHA (default: the default branch)
+    pub git_ref: Option<String>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
 
-        // Sort by created_at
-        all_comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlameRange {
+    pub start_line: u64,
+    pub end_line: u64,
+    pub sha: String,
+    pub author: String,
+    pub date: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetBlameResult {
+    pub path: String,
+    pub ranges: Vec<BlameRange>,
+}
+
+/// Gets git blame for a file or a line range: which commit, author and date last changed each range of lines. Can optionally specify owner/repo.
+#[tool(name = "get_blame")]
+async fn get_blame(
+    client: &GitHubClient,
+    args: GetBlameArgs,
+) -> Result<GetBlameResult, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let response: Value = client
+        .octocrab
+        .graphql(&json!({
+            "query": BLAME_QUERY,
+            "variables": {
+                "owner": owner,
+                "repo": repo,
+                "ref": args.git_ref.as_deref().unwrap_or("HEAD"),
+                "path": args.path,
+            },
+        }))
+        .await?;
+    if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+        let messages: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| e["message"].as_str())
+            .collect();
+        return Err(format!("GitHub GraphQL error: {}", messages.join("; ")).into());
+    }
+    let ranges = response["data"]["repository"]["object"]["blame"]["ranges"]
+        .as_array()
+        .ok_or_else(|| format!("No blame for {} at that ref", args.path))?;
+
+    Ok(GetBlameResult {
+        ranges: blame_ranges(ranges, args.start_line, args.end_line),
+        path: args.path,
+    })
+}
+
+/// Blame ranges overlapping `start..=end`, clipped to it
+fn blame_ranges(ranges: &[Value], start: Option<u64>, end: Option<u64>) -> Vec<BlameRange> {
+    let start = start.unwrap_or(1);
+    let end = end.unwrap_or(u64::MAX);
+    ranges
+        .iter()
+        .filter_map(|range| {
+            let first = range["startingLine"].as_u64()?;
+            let last = range["endingLine"].as_u64()?;
+            if last < start || first > end {
+                return None;
+            }
+            let commit = &range["commit"];
+            Some(BlameRange {
+                start_line: first.max(start),
+                end_line: last.min(end),
+                sha: commit["oid"].as_str().unwrap_or_default().to_string(),
+                author: commit["author"]["name"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                date: commit["author"]["date"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                message: commit["messageHeadline"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+// --- Submit Pull Request Review ---
+
+/// Overall verdict of a review
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewEvent {
+    #[default]
+    Comment,
+    Approve,
+    RequestChanges,
+}
+
+/// Side of the diff a comment is anchored to: LEFT is the old version, RIGHT the new
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DiffSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewComment {
+    /// File path relative to the repository root
+    pub path: String,
+    /// Line number in the file the comment is anchored to; must be part of the diff
+    pub line: u64,
+    /// RIGHT (default) for added or unchanged lines, LEFT for deleted lines
+    pub side: Option<DiffSide>,
+    /// First line of a multi-line comment ending at `line`
+    pub start_line: Option<u64>,
+    /// The comment text (Markdown)
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SubmitReviewArgs {
+    /// The pull request number
+    pub pr_number: u64,
+    /// Summary comment of the review (Markdown)
+    pub body: String,
+    /// COMMENT (default), APPROVE or REQUEST_CHANGES
+    pub event: Option<ReviewEvent>,
+    /// Inline comments anchored to lines of the diff
+    #[serde(default)]
+    pub comments: Vec<ReviewComment>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitReviewResult {
+    pub review_id: u64,
+    pub state: String,
+    pub url: String,
+    pub comments: usize,
+}
+
+/// Submits a review on a pull request with a summary and optional inline comments anchored to diff lines. Can optionally specify owner/repo.
+#[tool(name = "submit_pull_request_review", struct_name = "SubmitReviewTool")]
+async fn submit_review(
+    client: &GitHubClient,
+    args: SubmitReviewArgs,
+) -> Result<WriteResult<SubmitReviewResult>, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let comments: Vec<Value> = args
+        .comments
+        .iter()
+        .map(|c| {
+            let side = c.side.unwrap_or(DiffSide::Right);
+            let mut comment = json!({
+                "path": c.path,
+                "line": c.line,
+                "side": side,
+                "body": c.body,
+            });
+            if let Some(start_line) = c.start_line {
+                comment["start_line"] = json!(start_line);
+                comment["start_side"] = json!(side);
+            }
+            comment
+        })
+        .collect();
+    let request = WriteRequest::post(
+        format!(
+            "{}/pulls/{}/reviews",
+            repo_route(owner, repo)?,
+            args.pr_number
+        ),
+        json!({
+            "body": args.body,
+            "event": args.event.unwrap_or_default(),
+            "comments": comments,
+        }),
+    );
+
+    let action = format!(
+        "Submit a review with {} inline comment(s) on {}/{}#{}",
+        comments.len(),
+        owner,
+        repo,
+        args.pr_number
+    );
+    if let Some(preview) = client
+        .confirm_write(&action, std::slice::from_ref(&request))
+        .await?
+    {
+        return Ok(WriteResult::DryRun(preview));
+    }
+    let review = client.send(&request).await?;
+    Ok(WriteResult::Done(SubmitReviewResult {
+        review_id: review["id"].as_u64().unwrap_or_default(),
+        state: review["state"].as_str().unwrap_or_default().to_string(),
+        url: review["html_url"].as_str().unwrap_or_default().to_string(),
+        comments: comments.len(),
+    }))
+}
+
+// --- Update Labels ---
 
-        let result = GetCommentsResult {
-            comments: all_comments,
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateLabelsArgs {
+    /// The issue or pull request number
+    pub number: u64,
+    /// Labels to add
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Labels to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLabelsResult {
+    /// Labels on the issue after the update
+    pub labels: Vec<String>,
+}
+
+/// Adds and/or removes labels on an issue or pull request. Can optionally specify owner/repo.
+#[tool(name = "update_labels")]
+async fn update_labels(
+    client: &GitHubClient,
+    args: UpdateLabelsArgs,
+) -> Result<WriteResult<UpdateLabelsResult>, Box<dyn Error + Send + Sync>> {
+    if args.add.is_empty() && args.remove.is_empty() {
+        return Err("Give at least one label to add or remove".into());
+    }
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let labels_path = format!("{}/issues/{}/labels", repo_route(owner, repo)?, args.number);
+
+    // Each request with a description of its change
+    let mut steps = Vec::new();
+    if !args.add.is_empty() {
+        steps.push(format!("added {}", args.add.join(", ")));
+    }
+    steps.extend(args.remove.iter().map(|label| format!("removed {}", label)));
+    let mut requests = Vec::new();
+    if !args.add.is_empty() {
+        requests.push(WriteRequest::post(
+            labels_path.clone(),
+            json!({ "labels": args.add }),
+        ));
+    }
+    for label in &args.remove {
+        requests.push(WriteRequest::delete(format!(
+            "{}/{}",
+            labels_path,
+            path_segment(label)?
+        )));
+    }
+
+    let action = format!("Update labels on {}/{}#{}", owner, repo, args.number);
+    if let Some(preview) = client.confirm_write(&action, &requests).await? {
+        return Ok(WriteResult::DryRun(preview));
+    }
+    // Each response lists the labels left after that request
+    let mut labels = Value::Null;
+    for (i, request) in requests.iter().enumerate() {
+        labels = match client.send(request).await {
+            Ok(labels) => labels,
+            Err(e) => {
+                let applied = match &steps[..i] {
+                    [] => "nothing".to_string(),
+                    applied => applied.join("; "),
+                };
+                return Err(format!(
+                    "Failed to update labels on {}/{}#{} ({}): {}; already applied: {}",
+                    owner, repo, args.number, steps[i], e, applied
+                )
+                .into());
+            }
         };
-        Ok(serde_json::to_value(result)?)
     }
+    Ok(WriteResult::Done(UpdateLabelsResult {
+        labels: label_names(&labels),
+    }))
+}
+
+// --- Request Reviewers ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RequestReviewersArgs {
+    /// The pull request number
+    pub pr_number: u64,
+    /// User logins to request a review from
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+    /// Team slugs to request a review from
+    #[serde(default)]
+    pub team_reviewers: Vec<String>,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequestReviewersResult {
+    pub requested_reviewers: Vec<String>,
+    pub requested_teams: Vec<String>,
+}
+
+/// Requests reviews on a pull request from users and/or teams. Can optionally specify owner/repo.
+#[tool(name = "request_reviewers")]
+async fn request_reviewers(
+    client: &GitHubClient,
+    args: RequestReviewersArgs,
+) -> Result<WriteResult<RequestReviewersResult>, Box<dyn Error + Send + Sync>> {
+    if args.reviewers.is_empty() && args.team_reviewers.is_empty() {
+        return Err("Give at least one reviewer or team".into());
+    }
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let request = WriteRequest::post(
+        format!(
+            "{}/pulls/{}/requested_reviewers",
+            repo_route(owner, repo)?,
+            args.pr_number
+        ),
+        json!({
+            "reviewers": args.reviewers,
+            "team_reviewers": args.team_reviewers,
+        }),
+    );
+
+    let action = format!("Request reviews on {}/{}#{}", owner, repo, args.pr_number);
+    if let Some(preview) = client
+        .confirm_write(&action, std::slice::from_ref(&request))
+        .await?
+    {
+        return Ok(WriteResult::DryRun(preview));
+    }
+    let pr = client.send(&request).await?;
+    let names = |key: &str, field: &str| -> Vec<String> {
+        pr[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i[field].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Ok(WriteResult::Done(RequestReviewersResult {
+        requested_reviewers: names("requested_reviewers", "login"),
+        requested_teams: names("requested_teams", "slug"),
+    }))
+}
+
+// --- Comment on Issue ---
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CommentArgs {
+    /// The issue or pull request number
+    pub number: u64,
+    /// The comment text (Markdown)
+    pub body: String,
+    /// Repository owner/org (optional)
+    pub owner: Option<String>,
+    /// Repository name (optional)
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentResult {
+    pub comment_id: u64,
+    pub url: String,
+}
+
+/// Posts a comment on an issue or pull request conversation. Can optionally specify owner/repo.
+#[tool(name = "comment_on_issue", struct_name = "CommentOnIssueTool")]
+async fn comment_on_issue(
+    client: &GitHubClient,
+    args: CommentArgs,
+) -> Result<WriteResult<CommentResult>, Box<dyn Error + Send + Sync>> {
+    let (owner, repo) = client.repo_or_default(&args.owner, &args.repo);
+    let request = WriteRequest::post(
+        format!(
+            "{}/issues/{}/comments",
+            repo_route(owner, repo)?,
+            args.number
+        ),
+        json!({ "body": args.body }),
+    );
+
+    let action = format!("Comment on {}/{}#{}", owner, repo, args.number);
+    if let Some(preview) = client
+        .confirm_write(&action, std::slice::from_ref(&request))
+        .await?
+    {
+        return Ok(WriteResult::DryRun(preview));
+    }
+    let comment = client.send(&request).await?;
+    Ok(WriteResult::Done(CommentResult {
+        comment_id: comment["id"].as_u64().unwrap_or_default(),
+        url: comment["html_url"].as_str().unwrap_or_default().to_string(),
+    }))
 }
 
 // --- Factory ---
 
-pub fn create_tools() -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
-    let token = env::var("GITHUB_TOKEN").map_err(|_| "GITHUB_TOKEN must be set")?;
-    let owner = env::var("GITHUB_ORG").map_err(|_| "GITHUB_ORG must be set")?;
-    let repo = env::var("GITHUB_REPO").map_err(|_| "GITHUB_REPO must be set")?;
+/// GitHub tools configured from the environment
+///
+/// Write tools ask `approval` before each change. Without one they refuse
+/// to write unless `GITHUB_ALLOW_WRITES` is set. `GITHUB_DRY_RUN` turns
+/// writes into previews.
+pub fn create_tools(
+    approval: Option<Arc<dyn HumanInput>>,
+) -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
+    create_tools_with(GitHubConfig::from_env()?, approval)
+}
 
-    let octocrab = Octocrab::builder().personal_token(token).build()?;
-    let octocrab = Arc::new(octocrab);
+/// GitHub tools using `config`
+pub fn create_tools_with(
+    config: GitHubConfig,
+    approval: Option<Arc<dyn HumanInput>>,
+) -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
+    let mut builder = Octocrab::builder().personal_token(config.token);
+    if let Some(base_url) = &config.base_url {
+        builder = builder.base_uri(base_url.as_str())?;
+    }
+    let client = GitHubClient {
+        octocrab: Arc::new(builder.build()?),
+        owner: config.owner,
+        repo: config.repo,
+        dry_run: config.dry_run,
+        allow_writes: config.allow_writes,
+        approval,
+    };
 
     Ok(vec![
-        Arc::new(FetchPRTool::new(
-            octocrab.clone(),
-            owner.clone(),
-            repo.clone(),
-        )),
-        Arc::new(GetDiffTool::new(
-            octocrab.clone(),
-            owner.clone(),
-            repo.clone(),
-        )),
-        Arc::new(GetPRCommentsTool::new(
-            octocrab.clone(),
-            owner.clone(),
-            repo.clone(),
-        )),
-        Arc::new(ListMergedPRsTool::new(octocrab, owner, repo)),
+        Arc::new(FetchPRTool::new(client.clone())),
+        Arc::new(GetDiffTool::new(client.clone())),
+        Arc::new(GetFileContentsTool::new(client.clone())),
+        Arc::new(GetPRCommentsTool::new(client.clone())),
+        Arc::new(ListMergedPRsTool::new(client.clone())),
+        Arc::new(SearchCodeTool::new(client.clone())),
+        Arc::new(ListIssuesTool::new(client.clone())),
+        Arc::new(GetIssueTool::new(client.clone())),
+        Arc::new(ListCommitsTool::new(client.clone())),
+        Arc::new(GetCheckRunsTool::new(client.clone())),
+        Arc::new(GetRunLogsTool::new(client.clone())),
+        Arc::new(GetBlameTool::new(client.clone())),
+        Arc::new(SubmitReviewTool::new(client.clone())),
+        Arc::new(UpdateLabelsTool::new(client.clone())),
+        Arc::new(RequestReviewersTool::new(client.clone())),
+        Arc::new(CommentOnIssueTool::new(client)),
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_patch() {
+        let patch = "@@ -1,3 +1,4 @@ fn main() {\n use std::io;\n-let a = 1;\n+let a = 2;\n+let b = 3;\n }\n\\ No newline at end of file\n@@ -20 +21,0 @@\n-gone";
+        let hunks = parse_patch(patch);
+        assert_eq!(hunks.len(), 2);
+
+        let first = &hunks[0];
+        assert_eq!(first.header, "@@ -1,3 +1,4 @@ fn main() {");
+        assert_eq!((first.old_start, first.old_lines), (1, 3));
+        assert_eq!((first.new_start, first.new_lines), (1, 4));
+        let numbered: Vec<_> = first
+            .lines
+            .iter()
+            .map(|l| (l.kind.as_str(), l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            numbered,
+            [
+                ("context", Some(1), Some(1)),
+                ("remove", Some(2), None),
+                ("add", None, Some(2)),
+                ("add", None, Some(3)),
+                ("context", Some(3), Some(4)),
+            ]
+        );
+        assert_eq!(first.lines[2].text, "let a = 2;");
+
+        let second = &hunks[1];
+        assert_eq!((second.old_start, second.old_lines), (20, 1));
+        assert_eq!((second.new_start, second.new_lines), (21, 0));
+        assert_eq!(second.lines[0].old_line, Some(20));
+    }
+
+    #[test]
+    fn test_path_selected() {
+        let patterns = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(path_selected("src/main.rs", &[], &[]));
+        assert!(path_selected("src/main.rs", &patterns(&["src/*.rs"]), &[]));
+        assert!(!path_selected("docs/a.md", &patterns(&["src/*"]), &[]));
+        assert!(!path_selected("Cargo.lock", &[], &patterns(&["*.lock"])));
+    }
+
+    #[test]
+    fn test_blame_ranges_are_clipped() {
+        let range = |first: u64, last: u64, oid: &str| {
+            json!({
+                "startingLine": first,
+                "endingLine": last,
+                "commit": {"oid": oid, "messageHeadline": "msg", "author": {"name": "Ada", "date": "2024-01-01"}}
+            })
+        };
+        let ranges = [range(1, 4, "a"), range(5, 9, "b"), range(10, 20, "c")];
+
+        let clipped = blame_ranges(&ranges, Some(3), Some(6));
+        let spans: Vec<_> = clipped
+            .iter()
+            .map(|r| (r.start_line, r.end_line, r.sha.as_str()))
+            .collect();
+        assert_eq!(spans, [(3, 4, "a"), (5, 6, "b")]);
+        assert_eq!(blame_ranges(&ranges, None, None).len(), 3);
+    }
+
+    #[test]
+    fn test_path_segment() {
+        assert_eq!(path_segment("bug").unwrap(), "bug");
+        assert_eq!(path_segment("needs review").unwrap(), "needs%20review");
+        assert_eq!(path_segment("area/ci").unwrap(), "area%2Fci");
+        assert_eq!(repo_route("org", "../x").unwrap(), "/repos/org/..%2Fx");
+        assert_eq!(repo_route("org", ".github").unwrap(), "/repos/org/.github");
+        assert!(repo_route("..", "x").is_err());
+        assert!(repo_route("org", ".").is_err());
+        assert!(ref_path("release/./x").is_err());
+        assert_eq!(file_path("src/main.rs").unwrap(), "src/main.rs");
+        assert!(file_path("../../../../user").is_err());
+        assert!(file_path("docs/../../x").is_err());
+        assert_eq!(ref_path("release/1.0").unwrap(), "release/1.0");
+        assert_eq!(ref_path("a b#c").unwrap(), "a%20b%23c");
+        assert!(ref_path("../../admin").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_review_schema() {
+        let config = GitHubConfig {
+            token: "token".to_string(),
+            owner: "org".to_string(),
+            repo: "repo".to_string(),
+            ..Default::default()
+        };
+        let tools = create_tools_with(config, None).unwrap();
+        let review = tools
+            .iter()
+            .find(|t| t.name() == "submit_pull_request_review")
+            .unwrap();
+        let schema = review.schema();
+        assert_eq!(schema["required"], json!(["body", "pr_number"]));
+        let comment = &schema["properties"]["comments"]["items"];
+        assert_eq!(
+            comment["properties"]["side"]["enum"],
+            json!(["LEFT", "RIGHT"])
+        );
+        assert_eq!(
+            schema["properties"]["event"]["enum"],
+            json!(["COMMENT", "APPROVE", "REQUEST_CHANGES"])
+        );
+    }
+}
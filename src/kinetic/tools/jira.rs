@@ -1,67 +1,13 @@
 // SPDX-License-Identifier: MIT
 
-use crate::adk::tool::Tool;
-use async_trait::async_trait;
-use once_cell::sync::Lazy;
+use crate::adk::tool::{tool, Tool};
 use reqwest::{Client, Method};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::env;
 use std::error::Error;
 
-// --- Static schemas ---
-
-static GET_ISSUE_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "issue_key": {
-                "type": "string",
-                "description": "The issue key (e.g. PROJ-123)"
-            }
-        },
-        "required": ["issue_key"]
-    })
-});
-
-static SEARCH_ISSUES_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "jql": {
-                "type": "string",
-                "description": "JQL query string"
-            },
-            "max_results": {
-                "type": "integer",
-                "description": "Max results to return"
-            }
-        },
-        "required": ["jql"]
-    })
-});
-
-static GET_PROJECT_ISSUES_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {
-            "project_key": {
-                "type": "string",
-                "description": "The project key (e.g. OSPRH)"
-            }
-        },
-        "required": ["project_key"]
-    })
-});
-
-static GET_ASSIGNED_ISSUES_SCHEMA: Lazy<Value> = Lazy::new(|| {
-    json!({
-        "type": "object",
-        "properties": {},
-        "required": []
-    })
-});
-
 // --- Jira Client Helper ---
 
 #[derive(Clone)]
@@ -141,294 +87,198 @@ impl JiraClient {
 
 // --- Get Issue Tool ---
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetIssueArgs {
+    /// The issue key (e.g. PROJ-123)
     pub issue_key: String,
 }
 
-pub struct GetIssueTool {
-    client: JiraClient,
-}
-
-impl GetIssueTool {
-    fn new(client: JiraClient) -> Self {
-        Self { client }
-    }
-}
-
-#[async_trait]
-impl Tool for GetIssueTool {
-    fn name(&self) -> &str {
-        "get_jira_issue"
-    }
-
-    fn description(&self) -> &str {
-        "Gets detailed information about a specific Jira issue by its key (e.g., 'PROJ-123')."
-    }
-
-    fn schema(&self) -> &Value {
-        &GET_ISSUE_SCHEMA
-    }
-
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: GetIssueArgs = serde_json::from_value(input)?;
-
-        let json = self
-            .client
-            .request(
-                Method::GET,
-                &format!("issue/{}?expand=renderedFields", args.issue_key),
-                None,
-            )
-            .await?;
-
-        let fields = json.get("fields").ok_or("Missing fields in response")?;
-
-        let comments: Vec<Value> = fields
-            .get("comment")
-            .and_then(|c| c.get("comments"))
-            .and_then(|c| c.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .map(|comment| {
-                        json!({
-                            "author": comment.get("author").and_then(|a| a.get("displayName")),
-                            "body": comment.get("body"),
-                            "created": comment.get("created"),
-                        })
+/// Gets detailed information about a specific Jira issue by its key (e.g., 'PROJ-123').
+#[tool(name = "get_jira_issue")]
+async fn get_issue(
+    client: &JiraClient,
+    args: GetIssueArgs,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let json = client
+        .request(
+            Method::GET,
+            &format!("issue/{}?expand=renderedFields", args.issue_key),
+            None,
+        )
+        .await?;
+
+    let fields = json.get("fields").ok_or("Missing fields in response")?;
+
+    let comments: Vec<Value> = fields
+        .get("comment")
+        .and_then(|c| c.get("comments"))
+        .and_then(|c| c.as_array())
+        .map(|arr| {
+            arr.iter()
+                .map(|comment| {
+                    json!({
+                        "author": comment.get("author").and_then(|a| a.get("displayName")),
+                        "body": comment.get("body"),
+                        "created": comment.get("created"),
                     })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let result = json!({
-            "key": json.get("key"),
-            "summary": fields.get("summary"),
-            "description": fields.get("description"),
-            "status": fields.get("status").and_then(|s| s.get("name")),
-            "assignee": fields.get("assignee").and_then(|a| a.get("displayName")),
-            "priority": fields.get("priority").and_then(|p| p.get("name")),
-            "issue_type": fields.get("issuetype").and_then(|t| t.get("name")),
-            "comments": comments,
-        });
-
-        Ok(result)
-    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let result = json!({
+        "key": json.get("key"),
+        "summary": fields.get("summary"),
+        "description": fields.get("description"),
+        "status": fields.get("status").and_then(|s| s.get("name")),
+        "assignee": fields.get("assignee").and_then(|a| a.get("displayName")),
+        "priority": fields.get("priority").and_then(|p| p.get("name")),
+        "issue_type": fields.get("issuetype").and_then(|t| t.get("name")),
+        "comments": comments,
+    });
+
+    Ok(result)
 }
 
 // --- Search Issues Tool ---
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SearchIssuesArgs {
+    /// JQL query string
     pub jql: String,
+    /// Max results to return
     #[serde(default)]
     pub max_results: Option<u32>,
 }
 
-pub struct SearchIssuesTool {
-    client: JiraClient,
-}
-
-impl SearchIssuesTool {
-    fn new(client: JiraClient) -> Self {
-        Self { client }
-    }
-}
-
-#[async_trait]
-impl Tool for SearchIssuesTool {
-    fn name(&self) -> &str {
-        "search_jira_issues"
-    }
-
-    fn description(&self) -> &str {
-        "Searches for Jira issues using JQL (Jira Query Language)."
-    }
-
-    fn schema(&self) -> &Value {
-        &SEARCH_ISSUES_SCHEMA
-    }
-
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: SearchIssuesArgs = serde_json::from_value(input)?;
-
-        let body = json!({
-            "jql": args.jql,
-            "maxResults": args.max_results.unwrap_or(10),
-            "fields": ["summary", "status", "assignee", "priority"]
-        });
+/// Searches for Jira issues using JQL (Jira Query Language).
+#[tool(name = "search_jira_issues")]
+async fn search_issues(
+    client: &JiraClient,
+    args: SearchIssuesArgs,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let body = json!({
+        "jql": args.jql,
+        "maxResults": args.max_results.unwrap_or(10),
+        "fields": ["summary", "status", "assignee", "priority"]
+    });
+
+    let json = client.request(Method::POST, "search", Some(body)).await?;
+
+    let issues = json
+        .get("issues")
+        .and_then(|i| i.as_array())
+        .ok_or("Missing issues in response")?;
+
+    let mapped_issues: Vec<Value> = issues
+        .iter()
+        .filter_map(|issue| {
+            let fields = issue.get("fields")?;
+            Some(json!({
+                "key": issue.get("key"),
+                "summary": fields.get("summary"),
+                "status": fields.get("status").and_then(|s| s.get("name")),
+            }))
+        })
+        .collect();
 
-        let json = self
-            .client
-            .request(Method::POST, "search", Some(body))
-            .await?;
-
-        let issues = json
-            .get("issues")
-            .and_then(|i| i.as_array())
-            .ok_or("Missing issues in response")?;
-
-        let mapped_issues: Vec<Value> = issues
-            .iter()
-            .filter_map(|issue| {
-                let fields = issue.get("fields")?;
-                Some(json!({
-                    "key": issue.get("key"),
-                    "summary": fields.get("summary"),
-                    "status": fields.get("status").and_then(|s| s.get("name")),
-                }))
-            })
-            .collect();
-
-        Ok(json!({
-            "total": json.get("total"),
-            "issues": mapped_issues
-        }))
-    }
+    Ok(json!({
+        "total": json.get("total"),
+        "issues": mapped_issues
+    }))
 }
 
 // --- Get Project Issues Tool ---
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetProjectIssuesArgs {
+    /// The project key (e.g. OSPRH)
     pub project_key: String,
 }
 
-pub struct GetProjectIssuesTool {
-    client: JiraClient,
-}
-
-impl GetProjectIssuesTool {
-    fn new(client: JiraClient) -> Self {
-        Self { client }
-    }
-}
-
-#[async_trait]
-impl Tool for GetProjectIssuesTool {
-    fn name(&self) -> &str {
-        "get_my_project_issues"
-    }
-
-    fn description(&self) -> &str {
-        "Fetches in-progress issues assigned to the current user for a specific project."
-    }
-
-    fn schema(&self) -> &Value {
-        &GET_PROJECT_ISSUES_SCHEMA
-    }
-
-    async fn execute(&self, input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let args: GetProjectIssuesArgs = serde_json::from_value(input)?;
-
-        let jql = format!(
-            "project = {} AND assignee = currentUser() AND statusCategory = 'In Progress' ORDER BY updated DESC",
-            args.project_key
-        );
-
-        let body = json!({
-            "jql": jql,
-            "maxResults": 10,
-            "fields": ["summary", "status", "priority", "updated"]
-        });
+/// Fetches in-progress issues assigned to the current user for a specific project.
+#[tool(name = "get_my_project_issues")]
+async fn get_project_issues(
+    client: &JiraClient,
+    args: GetProjectIssuesArgs,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let jql = format!(
+        "project = {} AND assignee = currentUser() AND statusCategory = 'In Progress' ORDER BY updated DESC",
+        args.project_key
+    );
+
+    let body = json!({
+        "jql": jql,
+        "maxResults": 10,
+        "fields": ["summary", "status", "priority", "updated"]
+    });
+
+    let json = client.request(Method::POST, "search", Some(body)).await?;
+
+    let issues = json
+        .get("issues")
+        .and_then(|i| i.as_array())
+        .ok_or("Missing issues in response")?;
+
+    let mapped_issues: Vec<Value> = issues
+        .iter()
+        .filter_map(|issue| {
+            let fields = issue.get("fields")?;
+            Some(json!({
+                "key": issue.get("key"),
+                "summary": fields.get("summary"),
+                "status": fields.get("status").and_then(|s| s.get("name")),
+                "priority": fields.get("priority").and_then(|p| p.get("name")),
+                "updated": fields.get("updated"),
+            }))
+        })
+        .collect();
 
-        let json = self
-            .client
-            .request(Method::POST, "search", Some(body))
-            .await?;
-
-        let issues = json
-            .get("issues")
-            .and_then(|i| i.as_array())
-            .ok_or("Missing issues in response")?;
-
-        let mapped_issues: Vec<Value> = issues
-            .iter()
-            .filter_map(|issue| {
-                let fields = issue.get("fields")?;
-                Some(json!({
-                    "key": issue.get("key"),
-                    "summary": fields.get("summary"),
-                    "status": fields.get("status").and_then(|s| s.get("name")),
-                    "priority": fields.get("priority").and_then(|p| p.get("name")),
-                    "updated": fields.get("updated"),
-                }))
-            })
-            .collect();
-
-        Ok(json!({
-            "total": json.get("total"),
-            "issues": mapped_issues
-        }))
-    }
+    Ok(json!({
+        "total": json.get("total"),
+        "issues": mapped_issues
+    }))
 }
 
 // --- Get Assigned Issues Tool ---
 
-pub struct GetAssignedIssuesTool {
-    client: JiraClient,
-}
-
-impl GetAssignedIssuesTool {
-    fn new(client: JiraClient) -> Self {
-        Self { client }
-    }
-}
-
-#[async_trait]
-impl Tool for GetAssignedIssuesTool {
-    fn name(&self) -> &str {
-        "get_assigned_issues"
-    }
-
-    fn description(&self) -> &str {
-        "Fetches all in-progress issues assigned to the current user across all projects."
-    }
-
-    fn schema(&self) -> &Value {
-        &GET_ASSIGNED_ISSUES_SCHEMA
-    }
-
-    async fn execute(&self, _input: Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let jql =
-            "assignee = currentUser() AND statusCategory = 'In Progress' ORDER BY updated DESC";
-
-        let body = json!({
-            "jql": jql,
-            "maxResults": 20,
-            "fields": ["summary", "status", "priority", "updated", "project"]
-        });
+/// Fetches all in-progress issues assigned to the current user across all projects.
+#[tool]
+async fn get_assigned_issues(client: &JiraClient) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let jql = "assignee = currentUser() AND statusCategory = 'In Progress' ORDER BY updated DESC";
+
+    let body = json!({
+        "jql": jql,
+        "maxResults": 20,
+        "fields": ["summary", "status", "priority", "updated", "project"]
+    });
+
+    let json = client.request(Method::POST, "search", Some(body)).await?;
+
+    let issues = json
+        .get("issues")
+        .and_then(|i| i.as_array())
+        .ok_or("Missing issues in response")?;
+
+    let mapped_issues: Vec<Value> = issues
+        .iter()
+        .filter_map(|issue| {
+            let fields = issue.get("fields")?;
+            Some(json!({
+                "key": issue.get("key"),
+                "project": fields.get("project").and_then(|p| p.get("name")),
+                "summary": fields.get("summary"),
+                "status": fields.get("status").and_then(|s| s.get("name")),
+                "priority": fields.get("priority").and_then(|p| p.get("name")),
+                "updated": fields.get("updated"),
+            }))
+        })
+        .collect();
 
-        let json = self
-            .client
-            .request(Method::POST, "search", Some(body))
-            .await?;
-
-        let issues = json
-            .get("issues")
-            .and_then(|i| i.as_array())
-            .ok_or("Missing issues in response")?;
-
-        let mapped_issues: Vec<Value> = issues
-            .iter()
-            .filter_map(|issue| {
-                let fields = issue.get("fields")?;
-                Some(json!({
-                    "key": issue.get("key"),
-                    "project": fields.get("project").and_then(|p| p.get("name")),
-                    "summary": fields.get("summary"),
-                    "status": fields.get("status").and_then(|s| s.get("name")),
-                    "priority": fields.get("priority").and_then(|p| p.get("name")),
-                    "updated": fields.get("updated"),
-                }))
-            })
-            .collect();
-
-        Ok(json!({
-            "total": json.get("total"),
-            "issues": mapped_issues
-        }))
-    }
+    Ok(json!({
+        "total": json.get("total"),
+        "issues": mapped_issues
+    }))
 }
 
 pub fn create_tools() -> Result<Vec<std::sync::Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
@@ -445,3 +295,31 @@ pub fn create_tools() -> Result<Vec<std::sync::Arc<dyn Tool>>, Box<dyn Error + S
         std::sync::Arc::new(GetAssignedIssuesTool::new(client)),
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_schemas() {
+        let client = JiraClient {
+            client: Client::new(),
+            base_url: "https://jira.example.com".to_string(),
+            email: None,
+            api_token: "token".to_string(),
+            use_bearer: true,
+        };
+
+        let search = SearchIssuesTool::new(client.clone());
+        assert_eq!(search.name(), "search_jira_issues");
+        assert_eq!(search.schema()["required"], json!(["jql"]));
+        assert_eq!(
+            search.schema()["properties"]["max_results"]["type"],
+            "integer"
+        );
+
+        let assigned = GetAssignedIssuesTool::new(client);
+        assert_eq!(assigned.name(), "get_assigned_issues");
+        assert_eq!(assigned.schema()["properties"], json!({}));
+    }
+}
@@ -5,14 +5,18 @@
 //! This module handles the creation of Agent instances from AgentDefinition
 //! configurations, including model instantiation and tool binding.
 
-use crate::adk::agent::{Agent, LLMAgent, ReActAgent};
+use crate::adk::agent::{
+    Agent, CoTAgent, LLMAgent, PlanExecuteAgent, ReActAgent, ReflectAgent, SubAgent,
+    SupervisorAgent, DEFAULT_MAX_DEPTH, DEFAULT_MAX_HANDOFFS,
+};
 use crate::adk::model::anthropic::AnthropicModel;
 use crate::adk::model::gemini::GeminiModel;
 use crate::adk::model::openai::OpenAIModel;
 use crate::adk::model::Model;
-use crate::adk::tool::Tool;
+use crate::adk::tool::{apply_output_policies, Tool};
 use crate::kinetic::workflow::registry::ToolRegistry;
-use crate::kinetic::workflow::types::AgentDefinition;
+use crate::kinetic::workflow::types::{AgentDefinition, ModelDefinition};
+use crate::kinetic::workflow::workflow_tool::WORKFLOW_TOOL_PREFIX;
 
 use std::env;
 use std::error::Error;
@@ -32,16 +36,93 @@ impl<'a> AgentFactory<'a> {
     pub async fn build(
         &self,
         def: &AgentDefinition,
+    ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
+        self.build_with_tools(def, Vec::new()).await
+    }
+
+    /// Build an agent with tools in addition to those named in its definition
+    pub async fn build_with_tools(
+        &self,
+        def: &AgentDefinition,
+        extra_tools: Vec<Arc<dyn Tool>>,
     ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
         let model = self.create_model(def)?;
-        let tools = self.collect_tools(def).await;
+        let mut tools = self.collect_tools(def).await?;
+        tools.extend(extra_tools);
+        let tools = self.limit_tool_output(def, &model, tools)?;
 
         let executor = def.executor.as_deref().unwrap_or("default");
         log::info!("Building agent '{}' with executor '{}'", def.name, executor);
 
+        match executor {
+            "reflect" => self.build_reflect_agent(def, model, tools),
+            "supervisor" => Err(format!(
+                "Agent '{}': supervisor agents must be built with their sub-agents \
+                 (use Builder or AgentFactory::build_supervisor)",
+                def.name
+            )
+            .into()),
+            _ => self.build_executor(executor, def, model, tools),
+        }
+    }
+
+    /// Build a supervisor agent that delegates to already-built sub-agents
+    pub async fn build_supervisor(
+        &self,
+        def: &AgentDefinition,
+        sub_agents: Vec<SubAgent>,
+        extra_tools: Vec<Arc<dyn Tool>>,
+    ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
+        let model = self.create_model(def)?;
+        let mut tools = self.collect_tools(def).await?;
+        tools.extend(extra_tools);
+        let tools = self.limit_tool_output(def, &model, tools)?;
+        log::info!(
+            "Building supervisor '{}' with {} sub-agents",
+            def.name,
+            sub_agents.len()
+        );
+
+        let max_handoffs = def.max_handoffs.unwrap_or(DEFAULT_MAX_HANDOFFS);
+        let mut coordinator_tools = tools.clone();
+        let max_depth = def.max_delegation_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+        coordinator_tools.extend(SupervisorAgent::delegation_tools(
+            &sub_agents,
+            max_handoffs,
+            max_depth,
+        )?);
+        let coordinator = self.build_llm_agent(
+            def,
+            def.name.clone(),
+            SupervisorAgent::coordinator_instruction(&def.instructions, &sub_agents),
+            model.clone(),
+            coordinator_tools,
+        );
+        Ok(Arc::new(
+            SupervisorAgent::new(
+                def.name.clone(),
+                def.description.clone(),
+                def.instructions.clone(),
+                model,
+                tools,
+                sub_agents,
+            )?
+            .with_coordinator(coordinator),
+        ))
+    }
+
+    /// Build an agent for one of the non-wrapping executors
+    fn build_executor(
+        &self,
+        executor: &str,
+        def: &AgentDefinition,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+    ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
         match executor {
             "react" => self.build_react_agent(def, model, tools),
             "cot" => self.build_cot_agent(def, model, tools),
+            "plan_execute" => self.build_plan_execute_agent(def, model, tools),
             _ => self.build_default_agent(def, model, tools),
         }
     }
@@ -51,16 +132,18 @@ impl<'a> AgentFactory<'a> {
         &self,
         def: &AgentDefinition,
     ) -> Result<Arc<dyn Model>, Box<dyn Error + Send + Sync>> {
-        // Get model name from definition, env var, or default
-        let model_name = def.model.model_name.clone().unwrap_or_else(|| {
-            env::var("MODEL_NAME")
-                .or_else(|_| env::var("GEMINI_MODEL"))
-                .unwrap_or_else(|_| "gemini-2.0-flash".to_string())
-        });
+        self.model_from(&def.model)
+    }
+
+    /// Create a model instance from a model definition
+    pub(crate) fn model_from(
+        &self,
+        model: &ModelDefinition,
+    ) -> Result<Arc<dyn Model>, Box<dyn Error + Send + Sync>> {
+        let model_name = model_name(model);
 
         // Infer provider from: explicit definition > MODEL_PROVIDER env > model name prefix
-        let provider = def
-            .model
+        let provider = model
             .provider
             .clone()
             .or_else(|| env::var("MODEL_PROVIDER").ok())
@@ -78,17 +161,74 @@ impl<'a> AgentFactory<'a> {
         }
     }
 
+    /// Apply the agent's `tool_output` policies to its tools
+    fn limit_tool_output(
+        &self,
+        def: &AgentDefinition,
+        model: &Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+    ) -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
+        let Some(config) = &def.tool_output else {
+            return Ok(tools);
+        };
+        // Only build the summary model if some policy will use it
+        let summarizer = match &config.summary_model {
+            _ if !config.summarizes() => None,
+            Some(summary_model) => Some(self.model_from(summary_model)?),
+            None => Some(model.clone()),
+        };
+        Ok(apply_output_policies(
+            tools,
+            &config.policy,
+            &config.tools,
+            summarizer,
+        ))
+    }
+
     /// Collect tools for an agent from the registry
-    async fn collect_tools(&self, def: &AgentDefinition) -> Vec<Arc<dyn Tool>> {
+    ///
+    /// Entries containing `*` (e.g. `everything:*`) add every matching tool.
+    /// Two different tools with the name the model sees are an error.
+    async fn collect_tools(
+        &self,
+        def: &AgentDefinition,
+    ) -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
         let mut tools: Vec<Arc<dyn Tool>> = Vec::new();
         for tool_name in &def.tools {
-            if let Some(tool) = self.registry.get(tool_name).await {
-                tools.push(tool.clone());
+            // Workflow references are resolved by the Builder
+            if tool_name.starts_with(WORKFLOW_TOOL_PREFIX) {
+                continue;
+            }
+            let matches = if tool_name.contains('*') {
+                self.registry.matching(tool_name).await
             } else {
+                self.registry
+                    .get(tool_name)
+                    .await
+                    .map(|tool| vec![(tool_name.clone(), tool)])
+                    .unwrap_or_default()
+            };
+            if matches.is_empty() {
                 log::warn!("Tool not found: {}", tool_name);
             }
+            for (registered_as, tool) in matches {
+                match tools.iter().find(|t| t.name() == tool.name()) {
+                    Some(existing) if Arc::ptr_eq(existing, &tool) => {}
+                    Some(_) => {
+                        return Err(format!(
+                            "Agent '{}': tool {} is named '{}' like another of its tools \
+                             (give its MCP server a different `prefix`)",
+                            def.name,
+                            registered_as,
+                            tool.name()
+                        )
+                        .into())
+                    }
+                    None => tools.push(tool),
+                }
+            }
         }
-        tools
+        Ok(tools)
     }
 
     fn build_default_agent(
@@ -97,15 +237,37 @@ impl<'a> AgentFactory<'a> {
         model: Arc<dyn Model>,
         tools: Vec<Arc<dyn Tool>>,
     ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
-        Ok(Arc::new(LLMAgent::new(
+        Ok(Arc::new(self.build_llm_agent(
+            def,
             def.name.clone(),
-            def.description.clone(),
             def.instructions.clone(),
             model,
             tools,
         )))
     }
 
+    /// Build an `LLMAgent` with the definition's tool-calling settings
+    fn build_llm_agent(
+        &self,
+        def: &AgentDefinition,
+        name: String,
+        instructions: String,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+    ) -> LLMAgent {
+        let mut agent = LLMAgent::new(name, def.description.clone(), instructions, model, tools);
+        if let Some(max_turns) = def.max_turns {
+            agent = agent.with_max_turns(max_turns);
+        }
+        if let Some(strategy) = def.on_max_turns {
+            agent = agent.with_exhaustion_strategy(strategy);
+        }
+        if let Some(limit) = def.max_parallel_tools {
+            agent = agent.with_max_parallel_tools(limit);
+        }
+        agent
+    }
+
     fn build_react_agent(
         &self,
         def: &AgentDefinition,
@@ -113,14 +275,66 @@ impl<'a> AgentFactory<'a> {
         tools: Vec<Arc<dyn Tool>>,
     ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
         let max_iterations = def.max_iterations.unwrap_or(10);
-        Ok(Arc::new(ReActAgent::new(
-            def.name.clone(),
-            def.description.clone(),
-            def.instructions.clone(),
-            model,
-            tools,
-            max_iterations,
-        )))
+        Ok(Arc::new(
+            ReActAgent::new(
+                def.name.clone(),
+                def.description.clone(),
+                def.instructions.clone(),
+                model,
+                tools,
+                max_iterations,
+            )
+            .with_mode(def.react_mode.unwrap_or_default()),
+        ))
+    }
+
+    fn build_plan_execute_agent(
+        &self,
+        def: &AgentDefinition,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+    ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
+        let max_steps = def.max_iterations.unwrap_or(10);
+        let step_executor = self.build_llm_agent(
+            def,
+            PlanExecuteAgent::executor_name(&def.name),
+            PlanExecuteAgent::step_instruction(&def.instructions),
+            model.clone(),
+            tools.clone(),
+        );
+        Ok(Arc::new(
+            PlanExecuteAgent::new(
+                def.name.clone(),
+                def.description.clone(),
+                def.instructions.clone(),
+                model,
+                tools,
+                max_steps,
+            )
+            .with_executor(step_executor),
+        ))
+    }
+
+    fn build_reflect_agent(
+        &self,
+        def: &AgentDefinition,
+        model: Arc<dyn Model>,
+        tools: Vec<Arc<dyn Tool>>,
+    ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
+        let reflection = def.reflection.clone().unwrap_or_default();
+        let inner_executor = reflection.executor.as_deref().unwrap_or("default");
+        if inner_executor == "reflect" {
+            return Err(format!(
+                "Agent '{}': reflection.executor cannot be 'reflect'",
+                def.name
+            )
+            .into());
+        }
+        let inner = self.build_executor(inner_executor, def, model.clone(), tools)?;
+        Ok(Arc::new(
+            ReflectAgent::new(def.name.clone(), def.instructions.clone(), model, inner)
+                .with_reflection(reflection),
+        ))
     }
 
     fn build_cot_agent(
@@ -129,19 +343,36 @@ impl<'a> AgentFactory<'a> {
         model: Arc<dyn Model>,
         tools: Vec<Arc<dyn Tool>>,
     ) -> Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
-        // Chain-of-Thought: Use standard LLMAgent with CoT-specific instructions
-        // The user should include CoT prompting in their instructions
-        log::info!("Using Chain-of-Thought executor (standard agent with CoT prompting)");
-        Ok(Arc::new(LLMAgent::new(
+        let path_executor = self.build_llm_agent(
+            def,
             def.name.clone(),
-            def.description.clone(),
-            def.instructions.clone(),
-            model,
-            tools,
-        )))
+            CoTAgent::reasoning_instruction(&def.instructions),
+            model.clone(),
+            tools.clone(),
+        );
+        Ok(Arc::new(
+            CoTAgent::new(
+                def.name.clone(),
+                def.description.clone(),
+                def.instructions.clone(),
+                model,
+                tools,
+            )
+            .with_executor(path_executor)
+            .with_self_consistency(def.self_consistency.unwrap_or_default()),
+        ))
     }
 }
 
+/// Model name from the definition, `MODEL_NAME`/`GEMINI_MODEL`, or the default
+pub(crate) fn model_name(model: &ModelDefinition) -> String {
+    model.model_name.clone().unwrap_or_else(|| {
+        env::var("MODEL_NAME")
+            .or_else(|_| env::var("GEMINI_MODEL"))
+            .unwrap_or_else(|_| "gemini-2.0-flash".to_string())
+    })
+}
+
 /// Infer the provider from the model name prefix
 pub fn infer_provider_from_model(model_name: &str) -> String {
     let name_lower = model_name.to_lowercase();
@@ -162,6 +393,65 @@ pub fn infer_provider_from_model(model_name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adk::tool::tool;
+    use serde_json::{json, Value};
+
+    /// Searches the docs
+    #[tool(name = "search")]
+    async fn docs_search() -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Ok(json!("docs"))
+    }
+
+    /// Searches the web
+    #[tool(name = "search")]
+    async fn web_search() -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Ok(json!("web"))
+    }
+
+    /// Fetches a doc
+    #[tool(name = "fetch")]
+    async fn docs_fetch() -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Ok(json!("doc"))
+    }
+
+    #[tokio::test]
+    async fn test_collect_tools_expands_wildcards() {
+        let registry = ToolRegistry::new();
+        registry
+            .register_as("docs:search", Arc::new(DocsSearchTool::new()))
+            .await;
+        registry
+            .register_as("docs:fetch", Arc::new(DocsFetchTool::new()))
+            .await;
+        registry
+            .register_as("web:search", Arc::new(WebSearchTool::new()))
+            .await;
+
+        let def: AgentDefinition = serde_yaml::from_str(
+            r#"
+name: Researcher
+description: Test
+instructions: Test
+tools: ["docs:*", "docs:search", "missing:*"]
+"#,
+        )
+        .unwrap();
+        let factory = AgentFactory::new(&registry);
+        let tools = factory.collect_tools(&def).await.unwrap();
+
+        // docs:search is listed twice but added once
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["fetch", "search"]);
+        assert_eq!(tools[1].execute(json!({})).await.unwrap(), "docs");
+
+        // web:search has the same name as docs:search
+        let def = AgentDefinition {
+            tools: vec!["docs:search".to_string(), "web:search".to_string()],
+            ..def
+        };
+        let err = factory.collect_tools(&def).await.err().unwrap();
+        assert!(err.to_string().contains("web:search"), "{}", err);
+    }
 
     #[test]
     fn test_infer_provider_gemini() {
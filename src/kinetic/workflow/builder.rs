@@ -5,19 +5,27 @@
 //! This module provides the high-level Builder that loads workflow definitions
 //! and constructs executable agent graphs.
 
-use crate::adk::agent::Agent;
-use crate::kinetic::mcp::manager::McpServiceManager;
-use crate::kinetic::workflow::agent_factory::AgentFactory;
+use crate::adk::agent::{Agent, SubAgent, DEFAULT_MAX_DEPTH};
+use crate::adk::tool::Tool;
+use crate::kinetic::mcp::manager::{McpConnection, McpServiceManager};
+use crate::kinetic::mcp::prompt::prompt_text;
+use crate::kinetic::mcp::resource::{parse_resource_ref, resource_text};
+use crate::kinetic::workflow::agent_factory::{model_name, AgentFactory};
 use crate::kinetic::workflow::graph::types::GraphWorkflowDef;
 use crate::kinetic::workflow::graph::{normalize_to_graph, CompiledNode, GraphAgent, WaitMode};
 use crate::kinetic::workflow::loader::WorkflowLoader;
-use crate::kinetic::workflow::registry::ToolRegistry;
-use crate::kinetic::workflow::types::{AgentConfig, McpServerConfig, WorkflowDefinition};
+use crate::kinetic::workflow::registry::{glob_match, ToolRegistry};
+use crate::kinetic::workflow::types::{
+    AgentConfig, AgentDefinition, McpServerConfig, WorkflowDefinition,
+};
+use crate::kinetic::workflow::workflow_tool::{workflow_tool, WORKFLOW_TOOL_PREFIX};
 
+use std::borrow::Cow;
 use std::error::Error;
 use std::sync::Arc;
 
 /// High-level builder for constructing workflows from YAML definitions
+#[derive(Clone)]
 pub struct Builder {
     loader: WorkflowLoader,
     registry: ToolRegistry,
@@ -83,10 +91,7 @@ impl Builder {
 
         for node_def in &graph_def.nodes {
             // Build the agent for this node
-            let agent = match &node_def.agent {
-                AgentConfig::Inline(agent_def) => factory.build(agent_def).await?,
-                AgentConfig::Reference(ref_def) => self.build_agent(&ref_def.file).await?,
-            };
+            let agent = self.build_agent_config(&factory, &node_def.agent).await?;
 
             // Convert depends_on
             let depends_on = node_def.depends_on.to_vec();
@@ -123,6 +128,87 @@ impl Builder {
         )))
     }
 
+    /// Build the agent for an inline definition or file reference
+    #[allow(clippy::type_complexity)]
+    fn build_agent_config<'a>(
+        &'a self,
+        factory: &'a AgentFactory<'a>,
+        config: &'a AgentConfig,
+    ) -> std::pin::Pin<
+        Box<
+            dyn std::future::Future<Output = Result<Arc<dyn Agent>, Box<dyn Error + Send + Sync>>>
+                + Send
+                + 'a,
+        >,
+    > {
+        Box::pin(async move {
+            match config {
+                AgentConfig::Inline(agent_def)
+                    if agent_def.executor.as_deref() == Some("supervisor") =>
+                {
+                    let agent_def = self.resolve_instructions(agent_def).await?;
+                    let mut sub_agents = Vec::with_capacity(agent_def.sub_agents.len());
+                    for sub_config in &agent_def.sub_agents {
+                        let (name, description, input_schema) = self.describe_agent(sub_config)?;
+                        let agent = self.build_agent_config(factory, sub_config).await?;
+                        sub_agents.push(
+                            SubAgent::new(&name, description, agent)
+                                .with_input_schema(input_schema),
+                        );
+                    }
+                    let workflow_tools = self.workflow_tools(&agent_def)?;
+                    factory
+                        .build_supervisor(&agent_def, sub_agents, workflow_tools)
+                        .await
+                }
+                AgentConfig::Inline(agent_def) => {
+                    let agent_def = self.resolve_instructions(agent_def).await?;
+                    let workflow_tools = self.workflow_tools(&agent_def)?;
+                    factory.build_with_tools(&agent_def, workflow_tools).await
+                }
+                AgentConfig::Reference(ref_def) => self.build_agent(&ref_def.file).await,
+            }
+        })
+    }
+
+    /// Name, description and input schema an agent is presented with as a tool
+    fn describe_agent(
+        &self,
+        config: &AgentConfig,
+    ) -> Result<(String, String, Option<serde_json::Value>), Box<dyn Error + Send + Sync>> {
+        match config {
+            AgentConfig::Inline(agent_def) => Ok(describe_definition(agent_def)),
+            AgentConfig::Reference(ref_def) => self.describe_workflow(&ref_def.file),
+        }
+    }
+
+    /// Name, description and input schema of the workflow at `path`
+    ///
+    /// Direct workflows are described by their agent, others by the workflow itself.
+    pub(crate) fn describe_workflow(
+        &self,
+        path: &str,
+    ) -> Result<(String, String, Option<serde_json::Value>), Box<dyn Error + Send + Sync>> {
+        let def = self.loader.load_workflow(path)?;
+        Ok(match &def.agent {
+            Some(agent_def) if def.kind == "Direct" => describe_definition(agent_def),
+            _ => (def.name.clone(), def.description.clone(), None),
+        })
+    }
+
+    /// Tools for the `workflow:<path>` entries in an agent's tool list
+    fn workflow_tools(
+        &self,
+        def: &AgentDefinition,
+    ) -> Result<Vec<Arc<dyn Tool>>, Box<dyn Error + Send + Sync>> {
+        let max_depth = def.max_workflow_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+        def.tools
+            .iter()
+            .filter_map(|name| name.strip_prefix(WORKFLOW_TOOL_PREFIX))
+            .map(|path| workflow_tool(self, path.trim(), max_depth))
+            .collect()
+    }
+
     /// Initialize MCP servers and register their tools
     async fn initialize_mcp_servers(&self, servers: &[McpServerConfig]) {
         for server_config in servers {
@@ -142,13 +228,22 @@ impl Builder {
         config: &McpServerConfig,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         use crate::kinetic::mcp::manager::McpServerConfig as ManagerConfig;
+        use crate::kinetic::mcp::resource::{ReadResourceTool, READ_RESOURCE_TOOL};
         use crate::kinetic::mcp::tool::McpTool;
 
         // Convert loader config to manager config
         let manager_config = ManagerConfig {
             name: config.name.clone(),
+            transport: config.transport,
             command: config.command.clone(),
             args: config.args.clone(),
+            env: config.env.clone(),
+            cwd: config.cwd.clone(),
+            url: config.url.clone(),
+            headers: config.headers.clone(),
+            bearer_token_env: config.bearer_token_env.clone(),
+            timeout_secs: config.timeout_secs,
+            roots: config.roots.clone(),
         };
 
         // Get or create the MCP service
@@ -157,28 +252,150 @@ impl Builder {
             .get_or_create_service(&manager_config)
             .await?;
 
+        if let Some(model) = &config.sampling_model {
+            let factory = AgentFactory::new(&self.registry);
+            service
+                .client()
+                .set_sampling_model(model_name(model), factory.model_from(model)?);
+        }
+
+        let capabilities = service.capabilities().await.unwrap_or_default();
+
         // List all tools from the service
-        let tools = {
-            let service_lock = service.read().await;
-            service_lock.list_all_tools().await?
+        let tools = if capabilities.tools.is_some() {
+            service.list_all_tools().await?
+        } else {
+            Vec::new()
         };
+        let has_read_resource = tools.iter().any(|t| t.name == READ_RESOURCE_TOOL);
+        let prefix = model_name_prefix(config);
 
         // Register each tool in the registry with namespaced name
         for tool in tools {
+            if !mcp_tool_selected(config, &tool.name) {
+                log::debug!("Skipping filtered MCP tool: {}:{}", config.name, tool.name);
+                continue;
+            }
             let tool_name = format!("{}:{}", config.name, tool.name);
             let mcp_tool = McpTool::new(
                 service.clone(),
                 tool.name.to_string(),
                 tool.description.unwrap_or_default().to_string(),
                 serde_json::to_value(&tool.input_schema).unwrap_or_default(),
-            );
+            )
+            .with_prefix(&prefix);
+
+            self.registry
+                .register_as(tool_name.clone(), Arc::new(mcp_tool))
+                .await;
+            log::info!("Registered MCP tool: {}", tool_name);
+        }
 
-            self.registry.register(Arc::new(mcp_tool)).await;
+        // Expose resource reads as a tool, unless the server has its own
+        if capabilities.resources.is_some()
+            && !has_read_resource
+            && mcp_tool_selected(config, READ_RESOURCE_TOOL)
+        {
+            let resources = service.list_all_resources().await.unwrap_or_else(|e| {
+                log::warn!("Failed to list resources of {}: {}", config.name, e);
+                Vec::new()
+            });
+            let tool = ReadResourceTool::new(service.clone(), &resources).with_prefix(&prefix);
+            let tool_name = format!("{}:{}", config.name, READ_RESOURCE_TOOL);
+            self.registry
+                .register_as(tool_name.clone(), Arc::new(tool))
+                .await;
             log::info!("Registered MCP tool: {}", tool_name);
         }
 
         Ok(())
     }
+
+    /// Instructions with the agent's MCP `prompt` and `context` resources filled in
+    async fn resolve_instructions<'d>(
+        &self,
+        def: &'d AgentDefinition,
+    ) -> Result<Cow<'d, AgentDefinition>, Box<dyn Error + Send + Sync>> {
+        if def.prompt.is_none() && def.context.is_empty() {
+            return Ok(Cow::Borrowed(def));
+        }
+
+        let mut sections = Vec::new();
+        if let Some(prompt) = &def.prompt {
+            let result = self
+                .mcp_service(&prompt.server)
+                .await?
+                .get_prompt(&prompt.name, &prompt.arguments)
+                .await
+                .map_err(|e| format!("Failed to get MCP prompt '{}': {}", prompt.name, e))?;
+            sections.push(prompt_text(&result));
+        }
+        if !def.instructions.is_empty() {
+            sections.push(def.instructions.clone());
+        }
+        for reference in &def.context {
+            let (server, uri) = parse_resource_ref(reference).ok_or_else(|| {
+                format!(
+                    "Invalid context entry '{}': expected mcp://<server>/<resource-uri>",
+                    reference
+                )
+            })?;
+            let result = self
+                .mcp_service(server)
+                .await?
+                .read_resource(uri)
+                .await
+                .map_err(|e| format!("Failed to read MCP resource '{}': {}", reference, e))?;
+            sections.push(format!("## {}\n\n{}", uri, resource_text(&result)));
+        }
+
+        let mut def = def.clone();
+        def.instructions = sections.join("\n\n");
+        Ok(Cow::Owned(def))
+    }
+
+    async fn mcp_service(
+        &self,
+        server: &str,
+    ) -> Result<Arc<McpConnection>, Box<dyn Error + Send + Sync>> {
+        self.mcp_manager
+            .get_service(server)
+            .await
+            .ok_or_else(|| format!("MCP server '{}' is not running", server).into())
+    }
+}
+
+/// Prefix of the tool names the model sees for a server's tools
+///
+/// Defaults to `<server>__`, so two servers offering `search` show up as
+/// `docs__search` and `web__search`; `:` is not allowed in provider tool names.
+fn model_name_prefix(config: &McpServerConfig) -> String {
+    match &config.prefix {
+        Some(prefix) => prefix.clone(),
+        None => format!("{}__", crate::adk::agent::tool_name(&config.name)),
+    }
+}
+
+/// Whether an MCP tool passes the server's `include` and `exclude` filters
+fn mcp_tool_selected(config: &McpServerConfig, tool_name: &str) -> bool {
+    let included = config.include.is_empty()
+        || config
+            .include
+            .iter()
+            .any(|pattern| glob_match(pattern, tool_name));
+    included
+        && !config
+            .exclude
+            .iter()
+            .any(|pattern| glob_match(pattern, tool_name))
+}
+
+fn describe_definition(def: &AgentDefinition) -> (String, String, Option<serde_json::Value>) {
+    (
+        def.name.clone(),
+        def.description.clone(),
+        def.input_schema.clone(),
+    )
 }
 
 #[cfg(test)]
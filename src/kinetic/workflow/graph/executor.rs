@@ -2,7 +2,7 @@
 
 //! Graph workflow executor
 
-use crate::adk::agent::Agent;
+use crate::adk::agent::{Agent, AgentEvent, AgentMessage};
 use crate::kinetic::workflow::condition;
 use crate::kinetic::workflow::state::WorkflowState;
 use async_trait::async_trait;
@@ -89,45 +89,38 @@ impl GraphAgent {
     async fn execute_node(
         &self,
         node: &CompiledNode,
-        input: &str,
-    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
         log::info!("Executing node: {}", node.id);
-        node.agent.run(input.to_string()).await
+        node.agent.run_message(input).await
     }
 
     /// Build input for a node based on its dependencies
     fn build_node_input(
         &self,
-        original_input: &str,
+        original_input: &AgentMessage,
         node: &CompiledNode,
-        state: &WorkflowState,
-    ) -> String {
-        if node.depends_on.is_empty() {
-            // No dependencies - use original input
-            return original_input.to_string();
-        }
-
+        node_outputs: &HashMap<String, AgentMessage>,
+    ) -> AgentMessage {
         // Has dependencies - use the last dependency's output
-        // For sequential workflows, this is the previous step's output
-        let last_dep = node.depends_on.last().unwrap();
-        let output_key = format!("output.{}", last_dep);
-
-        if let Some(dep_output) = state.get(&output_key) {
-            // Return the dependency's output as text
-            match dep_output {
-                serde_json::Value::String(s) => s.clone(),
-                _ => dep_output.to_string(),
-            }
-        } else {
-            // Dependency output not found, fall back to original input
-            original_input.to_string()
-        }
+        // For sequential workflows, this is the previous step's output.
+        // Without dependencies (or if the output is missing) use the original input.
+        node.depends_on
+            .last()
+            .and_then(|dep| node_outputs.get(dep))
+            .unwrap_or(original_input)
+            .clone()
     }
 
     /// Extract output values and update state
-    fn apply_outputs(&self, node: &CompiledNode, output: &str, state: &mut WorkflowState) {
-        // Try to parse output as JSON first
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(output) {
+    fn apply_outputs(&self, node: &CompiledNode, output: &AgentMessage, state: &mut WorkflowState) {
+        // Use structured data if the agent returned it, otherwise try to parse the text as JSON
+        let parsed = output
+            .data
+            .clone()
+            .or_else(|| serde_json::from_str::<serde_json::Value>(&output.text).ok());
+
+        if let Some(json) = parsed {
             // Store as JSON value (not escaped string)
             state.update(&format!("output.{}", node.id), json.clone());
 
@@ -141,7 +134,7 @@ impl GraphAgent {
             // Store as string value if not valid JSON
             state.update(
                 &format!("output.{}", node.id),
-                serde_json::Value::String(output.to_string()),
+                serde_json::Value::String(output.text.clone()),
             );
 
             if !node.outputs.is_empty() {
@@ -153,23 +146,53 @@ impl GraphAgent {
         }
     }
 
-    /// Format the final response from state as human-readable text
-    /// Only returns output from "terminal" nodes (nodes that aren't dependencies of others)
-    fn format_response(&self, state: &WorkflowState) -> String {
-        // Find terminal nodes - nodes that are NOT dependencies of any other node
-        let all_deps: HashSet<String> = self
+    /// Find terminal nodes - nodes that are NOT dependencies of any other node
+    fn terminal_nodes(&self) -> Vec<&str> {
+        let all_deps: HashSet<&str> = self
             .nodes
             .values()
-            .flat_map(|n| n.depends_on.iter().cloned())
+            .flat_map(|n| n.depends_on.iter().map(|d| d.as_str()))
             .collect();
 
-        let terminal_nodes: Vec<&str> = self
-            .nodes
-            .values()
-            .filter(|n| !all_deps.contains(&n.id))
-            .map(|n| n.id.as_str())
+        self.node_order
+            .iter()
+            .filter(|id| !all_deps.contains(id.as_str()))
+            .map(|id| id.as_str())
+            .collect()
+    }
+
+    /// Build the final output message from terminal node outputs
+    ///
+    /// The text is the formatted response; a single structured terminal output
+    /// is also kept as `data`, and terminal node metadata is merged.
+    fn build_output(
+        &self,
+        state: &WorkflowState,
+        node_outputs: &HashMap<String, AgentMessage>,
+    ) -> AgentMessage {
+        let mut output = AgentMessage::text(self.format_response(state));
+
+        let terminal: Vec<&AgentMessage> = self
+            .terminal_nodes()
+            .into_iter()
+            .filter_map(|id| node_outputs.get(id))
             .collect();
 
+        if let [single] = terminal.as_slice() {
+            output.data = single.data.clone();
+        }
+        for message in terminal {
+            output.metadata.extend(message.metadata.clone());
+        }
+
+        output
+    }
+
+    /// Format the final response from state as human-readable text
+    /// Only returns output from "terminal" nodes (nodes that aren't dependencies of others)
+    fn format_response(&self, state: &WorkflowState) -> String {
+        let terminal_nodes = self.terminal_nodes();
+
         let state_json = state.to_json();
 
         // Collect outputs only from terminal nodes
@@ -265,10 +288,29 @@ impl Agent for GraphAgent {
     }
 
     async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(self.run_message(AgentMessage::text(input)).await?.text)
+    }
+
+    async fn run_stream(
+        &self,
+        input: String,
+        tx: tokio::sync::mpsc::Sender<AgentEvent>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .run_message_stream(AgentMessage::text(input), tx)
+            .await?
+            .text)
+    }
+
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
         let mut state = WorkflowState::empty();
-        state.update("input", serde_json::Value::String(input.clone()));
+        state.update("input", input.to_value());
 
         let mut completed: HashSet<String> = HashSet::new();
+        let mut node_outputs: HashMap<String, AgentMessage> = HashMap::new();
         let mut iteration = 0;
         let max_iterations = 100; // Safety limit
 
@@ -301,11 +343,12 @@ impl Agent for GraphAgent {
                 // Build input for this node:
                 // - If node has dependencies, pass the last dependency's output
                 // - Otherwise, pass the original input
-                let node_input = self.build_node_input(&input, node, &state);
+                let node_input = self.build_node_input(&input, node, &node_outputs);
 
-                match self.execute_node(node, &node_input).await {
+                match self.execute_node(node, node_input).await {
                     Ok(output) => {
                         self.apply_outputs(node, &output, &mut state);
+                        node_outputs.insert(node_id.to_string(), output);
                         completed.insert(node_id.to_string());
                         log::info!("Node {} completed", node_id);
                     }
@@ -323,19 +366,19 @@ impl Agent for GraphAgent {
         }
 
         // Return formatted response
-        Ok(self.format_response(&state))
+        Ok(self.build_output(&state, &node_outputs))
     }
 
-    async fn run_stream(
+    async fn run_message_stream(
         &self,
-        input: String,
-        tx: tokio::sync::mpsc::Sender<crate::adk::agent::AgentEvent>,
-    ) -> Result<String, Box<dyn Error + Send + Sync>> {
-        use crate::adk::agent::AgentEvent;
+        input: AgentMessage,
+        tx: tokio::sync::mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
         let mut state = WorkflowState::empty();
-        state.update("input", serde_json::Value::String(input.clone()));
+        state.update("input", input.to_value());
 
         let mut completed: HashSet<String> = HashSet::new();
+        let mut node_outputs: HashMap<String, AgentMessage> = HashMap::new();
         let max_iterations = 100;
 
         for iteration in 1..=max_iterations {
@@ -369,7 +412,7 @@ impl Agent for GraphAgent {
 
             for node_id in ready {
                 let node = &self.nodes[node_id];
-                let node_input = self.build_node_input(&input, node, &state);
+                let node_input = self.build_node_input(&input, node, &node_outputs);
 
                 // Create a proxy channel to intercept Answer events from sub-agents
                 let (node_tx, mut node_rx) = tokio::sync::mpsc::channel(100);
@@ -396,10 +439,11 @@ impl Agent for GraphAgent {
                     }
                 });
 
-                // Use run_stream for the node's agent
-                match node.agent.run_stream(node_input, node_tx).await {
+                // Use run_message_stream for the node's agent
+                match node.agent.run_message_stream(node_input, node_tx).await {
                     Ok(output) => {
                         self.apply_outputs(node, &output, &mut state);
+                        node_outputs.insert(node_id.to_string(), output);
                         completed.insert(node_id.to_string());
                         log::info!("Node {} completed", node_id);
                     }
@@ -421,9 +465,9 @@ impl Agent for GraphAgent {
             }
         }
 
-        let response = self.format_response(&state);
-        let _ = tx.send(AgentEvent::Answer(response.clone())).await;
-        Ok(response)
+        let output = self.build_output(&state, &node_outputs);
+        let _ = tx.send(AgentEvent::Answer(output.text.clone())).await;
+        Ok(output)
     }
 }
 
@@ -638,6 +682,67 @@ mod tests {
         assert_eq!(a_input, "my original input");
     }
 
+    /// Mock agent that returns structured output and captures its structured input
+    struct StructuredAgent {
+        output: AgentMessage,
+        captured: Arc<Mutex<Option<AgentMessage>>>,
+    }
+
+    #[async_trait]
+    impl Agent for StructuredAgent {
+        fn name(&self) -> &str {
+            "structured"
+        }
+
+        async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+            Ok(input)
+        }
+
+        async fn run_message(
+            &self,
+            input: AgentMessage,
+        ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+            *self.captured.lock().unwrap() = Some(input);
+            Ok(self.output.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_structured_output_flows_downstream() {
+        let agent_a = Arc::new(StructuredAgent {
+            output: AgentMessage::json(json!({"issues": [1, 2]}))
+                .with_metadata("source", json!("jira")),
+            captured: Arc::new(Mutex::new(None)),
+        });
+        let captured_b = Arc::new(Mutex::new(None));
+        let agent_b = Arc::new(StructuredAgent {
+            output: AgentMessage::json(json!({"count": 2})),
+            captured: captured_b.clone(),
+        });
+
+        let graph = GraphAgent::new(
+            "structured".to_string(),
+            "test".to_string(),
+            vec![
+                make_node("a", agent_a, vec![]),
+                make_node("b", agent_b, vec!["a"]),
+            ],
+        );
+
+        let output = graph
+            .run_message(AgentMessage::json(json!({"project": "OSPRH"})))
+            .await
+            .unwrap();
+
+        // B receives A's data and metadata unchanged
+        let b_input = captured_b.lock().unwrap().clone().unwrap();
+        assert_eq!(b_input.data, Some(json!({"issues": [1, 2]})));
+        assert_eq!(b_input.metadata["source"], json!("jira"));
+
+        // Single terminal node keeps its structured output
+        assert_eq!(output.data, Some(json!({"count": 2})));
+    }
+
     #[test]
     fn test_extract_json_path() {
         let json = json!({
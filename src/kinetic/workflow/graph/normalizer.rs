@@ -182,6 +182,8 @@ mod tests {
             name: name.to_string(),
             description: format!("{} description", name),
             instructions: format!("{} instructions", name),
+            prompt: None,
+            context: vec![],
             executor: None,
             model: ModelDefinition {
                 provider: None,
@@ -193,6 +195,18 @@ mod tests {
             memory: None,
             workflow: None,
             max_iterations: None,
+            react_mode: None,
+            max_turns: None,
+            on_max_turns: None,
+            max_parallel_tools: None,
+            self_consistency: None,
+            reflection: None,
+            sub_agents: vec![],
+            max_handoffs: None,
+            max_delegation_depth: None,
+            input_schema: None,
+            max_workflow_depth: None,
+            tool_output: None,
         }
     }
 
@@ -10,6 +10,7 @@ use std::fs;
 use std::path::Path;
 
 /// Loads workflow definitions from YAML files
+#[derive(Clone)]
 pub struct WorkflowLoader;
 
 impl WorkflowLoader {
@@ -42,7 +43,10 @@ impl Default for WorkflowLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::kinetic::workflow::types::AgentConfig;
+    use crate::adk::agent::ExhaustionStrategy;
+    use crate::adk::tool::TruncateMode;
+    use crate::kinetic::mcp::McpTransport;
+    use crate::kinetic::workflow::types::{AgentConfig, ToolOutputDefinition};
 
     #[test]
     fn test_parse_direct_workflow() {
@@ -114,6 +118,144 @@ workflow:
         assert_eq!(workflow.max_iterations, Some(5));
     }
 
+    #[test]
+    fn test_parse_turn_limits() {
+        let yaml = r#"
+kind: Direct
+name: Pager
+description: "Pages through issues"
+
+agent:
+  name: Pager
+  description: "Test"
+  instructions: "Test"
+  tools: []
+  max_turns: 30
+  on_max_turns: final_answer
+"#;
+        let def = WorkflowLoader::parse_yaml(yaml).unwrap();
+        let agent = def.agent.unwrap();
+        assert_eq!(agent.max_turns, Some(30));
+        assert_eq!(agent.on_max_turns, Some(ExhaustionStrategy::FinalAnswer));
+    }
+
+    #[test]
+    fn test_parse_reflection() {
+        let yaml = r#"
+kind: Direct
+name: Writer
+description: "Writes with review"
+
+agent:
+  name: Writer
+  description: "Test"
+  instructions: "Test"
+  executor: reflect
+  reflection:
+    max_rounds: 2
+    critic_instructions: "Check tone"
+  tools: []
+"#;
+        let def = WorkflowLoader::parse_yaml(yaml).unwrap();
+        let reflection = def.agent.unwrap().reflection.unwrap();
+        assert_eq!(reflection.max_rounds, Some(2));
+        assert_eq!(
+            reflection.critic_instructions.as_deref(),
+            Some("Check tone")
+        );
+        assert!(reflection.executor.is_none());
+    }
+
+    #[test]
+    fn test_parse_tool_output() {
+        let yaml = r#"
+kind: Direct
+name: Reviewer
+description: "Reviews PRs"
+
+agent:
+  name: Reviewer
+  description: "Test"
+  instructions: "Test"
+  tool_output:
+    max_chars: 8000
+    truncate: head
+    tools:
+      search_jira_issues:
+        fields: [total, issues.key]
+      get_pull_request_diff:
+        max_tokens: 4000
+        summarize: true
+  tools: []
+"#;
+        let def = WorkflowLoader::parse_yaml(yaml).unwrap();
+        let tool_output = def.agent.unwrap().tool_output.unwrap();
+        assert_eq!(tool_output.policy.max_chars, Some(8000));
+        assert_eq!(tool_output.policy.truncate, Some(TruncateMode::Head));
+        let diff = tool_output.tools["get_pull_request_diff"].merged(&tool_output.policy);
+        assert_eq!(diff.limit(), Some(8000));
+        assert_eq!(diff.summarize, Some(true));
+        assert!(tool_output.summarizes());
+        assert_eq!(
+            tool_output.tools["search_jira_issues"].fields,
+            Some(vec!["total".to_string(), "issues.key".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_tool_output_without_summaries() {
+        let yaml = r#"
+max_chars: 8000
+tools:
+  get_pull_request_diff:
+    summarize: false
+summary_model:
+  model_name: gemini-2.0-flash-lite
+"#;
+        let tool_output: ToolOutputDefinition = serde_yaml::from_str(yaml).unwrap();
+        assert!(!tool_output.summarizes());
+    }
+
+    #[test]
+    fn test_parse_supervisor() {
+        let yaml = r#"
+kind: Direct
+name: Router
+description: "Routes requests"
+
+agent:
+  name: Router
+  description: "Test"
+  instructions: "Test"
+  executor: supervisor
+  max_handoffs: 1
+  max_delegation_depth: 2
+  sub_agents:
+    - file: agents/jira_fetcher.yaml
+    - name: Summarizer
+      description: "Summarizes text"
+      instructions: "Summarize"
+      input_schema:
+        type: object
+        properties:
+          text: { type: string }
+      tools: []
+  tools: []
+"#;
+        let def = WorkflowLoader::parse_yaml(yaml).unwrap();
+        let agent = def.agent.unwrap();
+        assert_eq!(agent.max_handoffs, Some(1));
+        assert_eq!(agent.max_delegation_depth, Some(2));
+        assert_eq!(agent.sub_agents.len(), 2);
+        assert!(
+            matches!(&agent.sub_agents[0], AgentConfig::Reference(r) if r.file == "agents/jira_fetcher.yaml")
+        );
+        match &agent.sub_agents[1] {
+            AgentConfig::Inline(sub) => assert!(sub.input_schema.is_some()),
+            other => panic!("expected inline sub-agent, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_model_with_provider() {
         let yaml = r#"
@@ -174,6 +316,13 @@ mcp_servers:
   - name: "myserver"
     command: "npx"
     args: ["-y", "some-package"]
+    env:
+      API_TOKEN: "${MY_TOKEN}"
+    cwd: "./tools"
+    timeout_secs: 30
+    include: ["read_*"]
+    exclude: ["read_secret"]
+    prefix: "my_"
 
 agent:
   name: MCPTest
@@ -189,6 +338,70 @@ agent:
         assert_eq!(def.mcp_servers[0].name, "myserver");
         assert_eq!(def.mcp_servers[0].command, "npx");
         assert_eq!(def.mcp_servers[0].args, vec!["-y", "some-package"]);
+        assert_eq!(def.mcp_servers[0].env["API_TOKEN"], "${MY_TOKEN}");
+        assert_eq!(def.mcp_servers[0].cwd.as_deref(), Some("./tools"));
+        assert_eq!(def.mcp_servers[0].timeout_secs, Some(30));
+        assert_eq!(def.mcp_servers[0].include, vec!["read_*"]);
+        assert_eq!(def.mcp_servers[0].exclude, vec!["read_secret"]);
+        assert_eq!(def.mcp_servers[0].prefix.as_deref(), Some("my_"));
+    }
+
+    #[test]
+    fn test_parse_remote_mcp_server() {
+        let yaml = r#"
+kind: Direct
+name: MCPTest
+description: "Test MCP"
+
+mcp_servers:
+  - name: "team"
+    transport: http
+    url: "https://mcp.example.com/mcp"
+    headers:
+      X-Team: platform
+    bearer_token_env: TEAM_MCP_TOKEN
+
+agent:
+  name: MCPTest
+  description: "Test"
+  instructions: "Test"
+  tools: []
+"#;
+        let def = WorkflowLoader::parse_yaml(yaml).unwrap();
+        let server = &def.mcp_servers[0];
+        assert_eq!(server.transport, McpTransport::Http);
+        assert_eq!(server.url.as_deref(), Some("https://mcp.example.com/mcp"));
+        assert_eq!(server.headers["X-Team"], "platform");
+        assert_eq!(server.bearer_token_env.as_deref(), Some("TEAM_MCP_TOKEN"));
+        assert!(server.command.is_empty());
+    }
+
+    #[test]
+    fn test_parse_mcp_prompt_and_context() {
+        let yaml = r#"
+kind: Direct
+name: Reviewer
+description: "Test"
+
+agent:
+  name: Reviewer
+  description: "Test"
+  prompt:
+    server: docs
+    name: review
+    arguments:
+      topic: "the diff"
+  context: ["mcp://docs/file:///guide.md"]
+  tools: []
+"#;
+        let def = WorkflowLoader::parse_yaml(yaml).unwrap();
+        let agent = def.agent.unwrap();
+        assert!(agent.instructions.is_empty());
+        let prompt = agent.prompt.unwrap();
+        assert_eq!(prompt.server, "docs");
+        assert_eq!(prompt.name, "review");
+        assert_eq!(prompt.arguments["topic"], "the diff");
+        assert_eq!(agent.context, vec!["mcp://docs/file:///guide.md"]);
     }
 
     #[test]
@@ -8,3 +8,4 @@ pub mod loader;
 pub mod registry;
 pub mod state;
 pub mod types;
+pub mod workflow_tool;
@@ -22,10 +22,49 @@ impl ToolRegistry {
         tools.insert(tool.name().to_string(), tool);
     }
 
+    /// Register a tool under `name` instead of its own name
+    pub async fn register_as(&self, name: impl Into<String>, tool: Arc<dyn Tool>) {
+        let mut tools = self.tools.write().await;
+        tools.insert(name.into(), tool);
+    }
+
     pub async fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
         let tools = self.tools.read().await;
         tools.get(name).cloned()
     }
+
+    /// Tools whose registered name matches a `*` wildcard pattern, sorted by name
+    pub async fn matching(&self, pattern: &str) -> Vec<(String, Arc<dyn Tool>)> {
+        let tools = self.tools.read().await;
+        let mut matches: Vec<_> = tools
+            .iter()
+            .filter(|(name, _)| glob_match(pattern, name))
+            .map(|(name, tool)| (name.clone(), tool.clone()))
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0));
+        matches
+    }
+}
+
+/// Match `name` against `pattern`, where `*` matches any run of characters
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
 }
 
 impl Default for ToolRegistry {
@@ -134,6 +173,49 @@ mod tests {
         assert!(retrieved.is_some());
     }
 
+    #[tokio::test]
+    async fn test_matching_wildcard() {
+        let registry = ToolRegistry::new();
+        registry
+            .register_as("docs:search", Arc::new(MockTool::new("search")))
+            .await;
+        registry
+            .register_as("docs:fetch", Arc::new(MockTool::new("fetch")))
+            .await;
+        registry
+            .register_as("web:search", Arc::new(MockTool::new("search")))
+            .await;
+
+        let names = |matches: Vec<(String, Arc<dyn Tool>)>| {
+            matches
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(registry.matching("docs:*").await),
+            vec!["docs:fetch", "docs:search"]
+        );
+        assert_eq!(
+            names(registry.matching("*:search").await),
+            vec!["docs:search", "web:search"]
+        );
+        assert!(registry.matching("jira:*").await.is_empty());
+        assert!(registry.get("search").await.is_none());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("read_*", "read_file"));
+        assert!(glob_match("*_file", "read_file"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exactly"));
+        assert!(!glob_match("read_*", "write_file"));
+        assert!(!glob_match("ab*ba", "aba"));
+    }
+
     #[tokio::test]
     async fn test_registry_is_clone() {
         let registry = ToolRegistry::new();
@@ -5,6 +5,9 @@
 //! This module contains all the data structures used for parsing
 //! workflow and agent YAML configuration files.
 
+use crate::adk::agent::{ExhaustionStrategy, ReActMode, Reflection, SelfConsistency};
+use crate::adk::tool::OutputPolicy;
+use crate::kinetic::mcp::McpTransport;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -92,8 +95,47 @@ impl GraphDependsOn {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct McpServerConfig {
     pub name: String,
+    /// "stdio" (default) runs `command`; "http" and "sse" connect to `url`
+    #[serde(default)]
+    pub transport: McpTransport,
+    #[serde(default)]
     pub command: String,
+    #[serde(default)]
     pub args: Vec<String>,
+    /// Environment for the command; values may reference `${VAR}`
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory for the command
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Server URL for the http and sse transports
+    #[serde(default)]
+    pub url: Option<String>,
+    /// HTTP headers sent with every request
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Environment variable holding a bearer token for the server
+    #[serde(default)]
+    pub bearer_token_env: Option<String>,
+    /// Timeout in seconds for connecting and for each request (default 60)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Only register tools matching one of these patterns (`*` wildcards)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Skip tools matching any of these patterns
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Prefix for the tool names the model sees, e.g. `gh_` (default: `<name>__`)
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Directories offered to the server as roots
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// Model for sampling requests made outside an agent's tool call; during
+    /// a call the calling agent's model answers
+    #[serde(default)]
+    pub sampling_model: Option<ModelDefinition>,
 }
 
 /// Agent definition
@@ -101,16 +143,100 @@ pub struct McpServerConfig {
 pub struct AgentDefinition {
     pub name: String,
     pub description: String,
+    /// System instructions; may be left out when `prompt` is set
+    #[serde(default)]
     pub instructions: String,
-    /// Executor type: "default" (turn-based), "react" (Thought-Action-Observation), "cot" (Chain-of-Thought)
+    /// MCP prompt rendered in front of `instructions`
+    #[serde(default)]
+    pub prompt: Option<McpPromptReference>,
+    /// Resources appended to the instructions, as `mcp://<server>/<resource-uri>`
+    #[serde(default)]
+    pub context: Vec<String>,
+    /// Executor type: "default" (turn-based), "react" (Thought-Action-Observation),
+    /// "cot" (Chain-of-Thought), "plan_execute" (plan, execute steps, re-plan),
+    /// "reflect" (critique and revise drafts), "supervisor" (delegate to `sub_agents`)
     pub executor: Option<String>,
     #[serde(default)]
     pub model: ModelDefinition,
+    /// Tool names; `workflow:<path>` exposes another workflow as a tool
     pub tools: Vec<String>,
     pub memory: Option<MemoryDefinition>,
     pub workflow: Option<WorkflowReference>,
-    /// Maximum iterations for ReAct executor (default: 10)
+    /// Maximum iterations for ReAct executor, or steps for plan_execute (default: 10)
     pub max_iterations: Option<u32>,
+    /// Action protocol for ReAct executor: "native" function calling (default) or "text"
+    #[serde(default)]
+    pub react_mode: Option<ReActMode>,
+    /// Maximum model turns for the default executor (default: 10)
+    #[serde(default)]
+    pub max_turns: Option<u32>,
+    /// Behaviour when `max_turns` is reached: "error" (default), "final_answer", "summary"
+    #[serde(default)]
+    pub on_max_turns: Option<ExhaustionStrategy>,
+    /// Maximum tool calls from one model turn executed concurrently (default: 4)
+    #[serde(default)]
+    pub max_parallel_tools: Option<usize>,
+    /// Sample several reasoning paths and vote on the answer (cot executor only)
+    #[serde(default)]
+    pub self_consistency: Option<SelfConsistency>,
+    /// Critic loop settings (reflect executor only)
+    #[serde(default)]
+    pub reflection: Option<Reflection>,
+    /// Agents the supervisor executor can delegate to
+    #[serde(default)]
+    pub sub_agents: Vec<AgentConfig>,
+    /// Peer handoffs allowed within one delegation (supervisor executor only, default: 3)
+    #[serde(default)]
+    pub max_handoffs: Option<u32>,
+    /// Maximum nesting of delegations, counting agent tool calls made by
+    /// sub-agents (supervisor executor only, default: 3)
+    #[serde(default)]
+    pub max_delegation_depth: Option<u32>,
+    /// JSON schema for arguments when this agent is called as a tool
+    #[serde(default)]
+    pub input_schema: Option<serde_json::Value>,
+    /// Maximum nesting of `workflow:` tool calls made by this agent (default: 3)
+    #[serde(default)]
+    pub max_workflow_depth: Option<u32>,
+    /// Limits on tool results before they enter the history
+    #[serde(default)]
+    pub tool_output: Option<ToolOutputDefinition>,
+}
+
+/// An MCP prompt used as agent instructions
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct McpPromptReference {
+    /// Name of an entry in `mcp_servers`
+    pub server: String,
+    /// Prompt name on that server
+    pub name: String,
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+/// Tool output policy for an agent
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ToolOutputDefinition {
+    /// Policy for every tool of the agent
+    #[serde(flatten)]
+    pub policy: OutputPolicy,
+    /// Per-tool policies; unset fields fall back to the agent-wide policy
+    #[serde(default)]
+    pub tools: HashMap<String, OutputPolicy>,
+    /// Model for `summarize: true` (default: the agent's model)
+    #[serde(default)]
+    pub summary_model: Option<ModelDefinition>,
+}
+
+impl ToolOutputDefinition {
+    /// Whether the agent-wide or any per-tool policy asks for summaries
+    pub fn summarizes(&self) -> bool {
+        self.policy.summarize == Some(true)
+            || self
+                .tools
+                .values()
+                .any(|p| p.merged(&self.policy).summarize == Some(true))
+    }
 }
 
 /// Composite workflow definition
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT
+
+//! Workflows as tools
+//!
+//! Entries like `workflow:agents/jira_fetcher.yaml` in an agent's `tools:`
+//! list expose another workflow as a tool. The referenced workflow is built
+//! on its first call rather than up front, so workflows may reference each
+//! other (or themselves); nesting is bounded by the `AgentTool` depth limit.
+
+use crate::adk::agent::{Agent, AgentEvent, AgentMessage, AgentTool};
+use crate::adk::tool::Tool;
+use crate::kinetic::workflow::builder::Builder;
+use async_trait::async_trait;
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::{mpsc, OnceCell};
+
+/// Prefix marking a workflow reference in a `tools:` list
+pub const WORKFLOW_TOOL_PREFIX: &str = "workflow:";
+
+/// Build a tool for the workflow at `path`
+pub(crate) fn workflow_tool(
+    builder: &Builder,
+    path: &str,
+    max_depth: u32,
+) -> Result<Arc<dyn Tool>, Box<dyn Error + Send + Sync>> {
+    let (name, description, input_schema) = builder.describe_workflow(path)?;
+    let agent = Arc::new(LazyWorkflow {
+        name: name.clone(),
+        path: path.to_string(),
+        builder: builder.clone(),
+        agent: OnceCell::new(),
+    });
+
+    let mut tool = AgentTool::new(&name, description, agent).with_max_depth(max_depth);
+    if let Some(schema) = input_schema {
+        tool = tool.with_input_schema(schema);
+    }
+    Ok(Arc::new(tool))
+}
+
+/// Agent built from a workflow file on first use
+struct LazyWorkflow {
+    name: String,
+    path: String,
+    builder: Builder,
+    agent: OnceCell<Arc<dyn Agent>>,
+}
+
+impl LazyWorkflow {
+    async fn agent(&self) -> Result<&Arc<dyn Agent>, Box<dyn Error + Send + Sync>> {
+        self.agent
+            .get_or_try_init(|| async {
+                log::info!("Building workflow tool {} from {}", self.name, self.path);
+                self.builder.build_agent(&self.path).await
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Agent for LazyWorkflow {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, input: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.agent().await?.run(input).await
+    }
+
+    async fn run_stream(
+        &self,
+        input: String,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.agent().await?.run_stream(input, tx).await
+    }
+
+    async fn run_message(
+        &self,
+        input: AgentMessage,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        self.agent().await?.run_message(input).await
+    }
+
+    async fn run_message_stream(
+        &self,
+        input: AgentMessage,
+        tx: mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentMessage, Box<dyn Error + Send + Sync>> {
+        self.agent().await?.run_message_stream(input, tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinetic::mcp::manager::McpServiceManager;
+    use crate::kinetic::workflow::registry::ToolRegistry;
+
+    #[test]
+    fn test_workflow_tool_describes_referenced_agent() {
+        let builder = Builder::new(ToolRegistry::new(), Arc::new(McpServiceManager::new()));
+        let tool = workflow_tool(&builder, "agents/jira_details_fetcher.yaml", 2).unwrap();
+        assert_eq!(tool.name(), "JiraDetailsFetcher");
+        assert!(tool.description().contains("Jira"));
+        assert_eq!(tool.schema()["required"], serde_json::json!(["task"]));
+    }
+
+    #[test]
+    fn test_workflow_tool_missing_file() {
+        let builder = Builder::new(ToolRegistry::new(), Arc::new(McpServiceManager::new()));
+        assert!(workflow_tool(&builder, "agents/does_not_exist.yaml", 2).is_err());
+    }
+}
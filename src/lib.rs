@@ -1,4 +1,7 @@
 // SPDX-License-Identifier: MIT
 
+// Lets `#[tool]` expansions refer to `::kinetic_rs` from inside this crate too
+extern crate self as kinetic_rs;
+
 pub mod adk;
 pub mod kinetic;
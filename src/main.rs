@@ -2,12 +2,18 @@
 
 use clap::{Parser, Subcommand};
 use dotenv::dotenv;
-use kinetic_rs::adk::agent::{Agent, LLMAgent};
+use kinetic_rs::adk::agent::{Agent, AgentMessage, LLMAgent};
+use kinetic_rs::adk::model::Part;
+use kinetic_rs::kinetic::human::{ConsoleInput, HumanInput};
+use kinetic_rs::kinetic::mcp::manager::McpServiceManager;
+use kinetic_rs::kinetic::mcp::server::{discover_workflows, WorkflowServer};
 
+use kinetic_rs::kinetic::session::{FileSessionStore, SessionStore};
 use kinetic_rs::kinetic::tools::{github, jira, search};
 use kinetic_rs::kinetic::workflow::builder::Builder;
 use kinetic_rs::kinetic::workflow::registry::ToolRegistry;
 
+use std::io::Write;
 use std::sync::Arc;
 
 #[derive(Parser, Debug)]
@@ -28,6 +34,10 @@ enum Commands {
         /// The model to use
         #[arg(short, long, default_value = "gemini-1.5-flash")]
         model: String,
+
+        /// Attach an image, PDF or other file (repeatable)
+        #[arg(long = "attach", value_name = "PATH")]
+        attachments: Vec<String>,
     },
     /// Run a workflow from a file
     Workflow {
@@ -36,8 +46,26 @@ enum Commands {
         file: String,
 
         /// Input to the workflow
+        #[arg(short, long, required_unless_present = "input_json")]
+        input: Option<String>,
+
+        /// Structured JSON input to the workflow
+        #[arg(long, conflicts_with = "input")]
+        input_json: Option<String>,
+
+        /// Attach an image, PDF or other file (repeatable)
+        #[arg(long = "attach", value_name = "PATH")]
+        attachments: Vec<String>,
+    },
+    /// Chat interactively with a workflow, keeping history in a session
+    Chat {
+        /// Path to the workflow file (defaults to the session's workflow)
+        #[arg(required_unless_present = "session")]
+        workflow: Option<String>,
+
+        /// Resume an existing session instead of starting a new one
         #[arg(short, long)]
-        input: String,
+        session: Option<String>,
     },
     /// Start the REST API server
     Serve {
@@ -45,6 +73,20 @@ enum Commands {
         #[arg(short, long, default_value_t = 3000)]
         port: u16,
     },
+    /// Serve workflows and agents as MCP tools (stdio unless --port is given)
+    McpServe {
+        /// Serve Streamable HTTP at /mcp on this port instead of stdio
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Address to serve HTTP on; use 0.0.0.0 to accept other hosts
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Directory of workflow or agent files to serve (repeatable)
+        #[arg(long = "dir", value_name = "DIR", default_values_t = ["examples".to_string(), "agents".to_string()])]
+        dirs: Vec<String>,
+    },
 }
 
 #[tokio::main]
@@ -53,11 +95,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     env_logger::init();
 
     let args = Args::parse();
+    let mut mcp_manager = McpServiceManager::new();
+    // MCP servers asking the user for input, and GitHub writes needing
+    // approval, are answered on the terminal
+    let console = match args.command {
+        Commands::Serve { .. } | Commands::McpServe { .. } => None,
+        _ => Some(Arc::new(ConsoleInput::new())),
+    };
+    if let Some(console) = &console {
+        mcp_manager = mcp_manager.with_human_input(console.clone());
+    }
+    let mcp_manager = Arc::new(mcp_manager);
+
+    let result = tokio::select! {
+        result = run(args.command, mcp_manager.clone(), console) => result,
+        _ = shutdown_signal() => Ok(()),
+    };
+
+    // Stop MCP servers so their child processes don't outlive us
+    mcp_manager.shutdown().await;
+    result
+}
 
-    match args.command {
+/// Wait for Ctrl-C, or SIGTERM from a process supervisor
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => log::warn!("Cannot listen for SIGTERM: {}", e),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+async fn run(
+    command: Commands,
+    mcp_manager: Arc<McpServiceManager>,
+    console: Option<Arc<ConsoleInput>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let human = console
+        .clone()
+        .map(|console| console as Arc<dyn HumanInput>);
+    match command {
         Commands::Run {
             prompt,
             model: model_name,
+            attachments,
         } => {
             // Infer provider
             let provider = std::env::var("MODEL_PROVIDER")
@@ -95,57 +186,170 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 vec![],
             );
 
+            let message = AgentMessage::text(prompt.clone())
+                .with_attachments(load_attachments(&attachments).await?);
+
             println!("Sending prompt: {}", prompt);
-            let response = agent.run(prompt).await?;
-            println!("Response: {}", response);
+            let response = agent.run_message(message).await?;
+            println!("Response: {}", response.text);
         }
-        Commands::Workflow { file, input } => {
-            let registry = ToolRegistry::new();
-
-            // Register native tools
-            match search::BraveSearchTool::new() {
-                Ok(search_tool) => {
-                    log::info!("Registered tool: brave_search");
-                    registry.register(Arc::new(search_tool)).await;
-                }
-                Err(e) => log::warn!("Failed to load search tools: {}", e),
-            }
+        Commands::Workflow {
+            file,
+            input,
+            input_json,
+            attachments,
+        } => {
+            let builder = Builder::new(build_registry(human).await, mcp_manager);
+            let agent = builder.build_agent(&file).await?;
 
-            match github::create_tools() {
-                Ok(github_tools) => {
-                    for tool in github_tools {
-                        log::info!("Registered tool: {}", tool.name());
-                        registry.register(tool).await;
-                    }
-                }
-                Err(e) => log::warn!("Failed to load GitHub tools: {}", e),
+            let message = match input_json {
+                Some(json) => AgentMessage::json(
+                    serde_json::from_str(&json)
+                        .map_err(|e| format!("Invalid --input-json: {}", e))?,
+                ),
+                None => AgentMessage::text(input.unwrap_or_default()),
             }
+            .with_attachments(load_attachments(&attachments).await?);
 
-            match jira::create_tools() {
-                Ok(jira_tools) => {
-                    for tool in jira_tools {
-                        log::info!("Registered tool: {}", tool.name());
-                        registry.register(tool).await;
+            println!("Running workflow: {}", agent.name());
+            let response = agent.run_message(message).await?;
+            println!("Response: {}", response.text);
+        }
+        Commands::Chat { workflow, session } => {
+            let store = FileSessionStore::from_env();
+            let mut session = match (session, workflow) {
+                (Some(id), workflow) => {
+                    let session = store
+                        .get(&id)
+                        .await?
+                        .ok_or_else(|| format!("Session not found: {}", id))?;
+                    if let Some(workflow) = workflow.filter(|w| !same_file(w, &session.workflow)) {
+                        return Err(format!(
+                            "Session {} belongs to workflow {}, not {}",
+                            id, session.workflow, workflow
+                        )
+                        .into());
                     }
+                    session
                 }
-                Err(e) => log::warn!("Failed to load Jira tools: {}", e),
-            }
+                (None, Some(workflow)) => store.create(&workflow).await?,
+                (None, None) => return Err("Give a workflow file or --session".into()),
+            };
 
-            // Create MCP service manager
-            let mcp_manager = Arc::new(kinetic_rs::kinetic::mcp::manager::McpServiceManager::new());
+            let builder = Builder::new(build_registry(human).await, mcp_manager);
+            let agent = builder.build_agent(&session.workflow).await?;
 
-            // Build workflow
-            let builder = Builder::new(registry, mcp_manager);
-            let agent = builder.build_agent(&file).await?;
+            println!(
+                "Chatting with {} (session {}). Type /exit to quit.",
+                agent.name(),
+                session.id
+            );
 
-            println!("Running workflow: {}", agent.name());
-            let response = agent.run(input).await?;
-            println!("Response: {}", response);
+            // Read through the console that answers approval prompts, so a
+            // line typed ahead isn't swallowed by a second stdin buffer
+            let console = console.unwrap_or_default();
+            loop {
+                print!("> ");
+                std::io::stdout().flush()?;
+
+                let Some(line) = console.read_line().await? else {
+                    break;
+                };
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                if line == "/exit" || line == "/quit" {
+                    break;
+                }
+
+                match session.send(agent.as_ref(), line.to_string()).await {
+                    Ok(response) => println!("{}", response),
+                    Err(e) => eprintln!("Error: {}", e),
+                }
+                store.save(&session).await?;
+            }
+
+            println!("Session saved: {}", session.id);
         }
         Commands::Serve { port } => {
-            kinetic_rs::kinetic::server::serve(port).await?;
+            kinetic_rs::kinetic::server::serve(port, mcp_manager).await?;
+        }
+        Commands::McpServe { port, host, dirs } => {
+            let builder = Builder::new(build_registry(human).await, mcp_manager);
+            let mut server = WorkflowServer::new(builder, &discover_workflows(&dirs));
+            // HTTP clients must send this as a bearer token when set
+            if let Some(token) = std::env::var("MCP_SERVE_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty())
+            {
+                server = server.with_token(token);
+            }
+            log::info!("Serving {} workflows over MCP", server.tool_names().len());
+            // stdout carries the protocol in stdio mode, so nothing else is printed
+            match port {
+                Some(port) => server.serve_http(&host, port).await?,
+                None => server.serve_stdio().await?,
+            }
         }
     }
 
     Ok(())
 }
+
+/// Whether two workflow paths name the same file
+fn same_file(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Load `--attach` paths as content parts
+async fn load_attachments(
+    paths: &[String],
+) -> Result<Vec<Part>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut parts = Vec::with_capacity(paths.len());
+    for path in paths {
+        parts.push(Part::attachment(path).await?);
+    }
+    Ok(parts)
+}
+
+/// Create a tool registry with all native tools whose credentials are configured
+///
+/// GitHub write tools ask `human` for approval before each change.
+async fn build_registry(human: Option<Arc<dyn HumanInput>>) -> ToolRegistry {
+    let registry = ToolRegistry::new();
+
+    // Register native tools
+    match search::BraveSearchTool::new() {
+        Ok(search_tool) => {
+            log::info!("Registered tool: brave_search");
+            registry.register(Arc::new(search_tool)).await;
+        }
+        Err(e) => log::warn!("Failed to load search tools: {}", e),
+    }
+
+    match github::create_tools(human) {
+        Ok(github_tools) => {
+            for tool in github_tools {
+                log::info!("Registered tool: {}", tool.name());
+                registry.register(tool).await;
+            }
+        }
+        Err(e) => log::warn!("Failed to load GitHub tools: {}", e),
+    }
+
+    match jira::create_tools() {
+        Ok(jira_tools) => {
+            for tool in jira_tools {
+                log::info!("Registered tool: {}", tool.name());
+                registry.register(tool).await;
+            }
+        }
+        Err(e) => log::warn!("Failed to load Jira tools: {}", e),
+    }
+
+    registry
+}
@@ -0,0 +1,653 @@
+// SPDX-License-Identifier: MIT
+
+//! GitHub tools against a local mock of the GitHub API
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::http::{header, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use base64::Engine;
+use kinetic_rs::adk::tool::Tool;
+use kinetic_rs::kinetic::human::{HumanInput, HumanRequest, HumanResponse};
+use kinetic_rs::kinetic::tools::github::{create_tools_with, GitHubConfig};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+/// Requests received by the mock: method, path and JSON body
+type Recorded = Arc<Mutex<Vec<(String, String, Value)>>>;
+
+/// Changed file as listed by `GET /repos/{owner}/{repo}/pulls/{number}/files`
+fn diff_entry(filename: &str, patch: Option<&str>) -> Value {
+    let mut entry = json!({
+        "sha": "abc",
+        "filename": filename,
+        "status": "modified",
+        "additions": 1,
+        "deletions": 1,
+        "changes": 2,
+        "blob_url": null,
+        "raw_url": null,
+        "contents_url": format!("https://api.github.com/repos/org/repo/contents/{}", filename),
+    });
+    if let Some(patch) = patch {
+        entry["patch"] = json!(patch);
+    }
+    entry
+}
+
+/// Canned GitHub API response for a request to the mock at `base_url`
+fn respond(base_url: &str, method: &Method, uri: &Uri) -> Response {
+    let path = uri.path();
+    let query = uri.query().unwrap_or_default();
+    let body = match (method.as_str(), path) {
+        ("GET", "/repos/org/repo/pulls/5/files") if query.contains("page=2") => json!([
+            diff_entry("src/lib.rs", Some("@@ -1 +1 @@\n-old\n+new")),
+            diff_entry("logo.png", None),
+        ]),
+        ("GET", "/repos/org/repo/pulls/5/files") => {
+            let next = format!(
+                "<{}/repos/org/repo/pulls/5/files?per_page=100&page=2>; rel=\"next\"",
+                base_url
+            );
+            let files = json!([
+                diff_entry("Cargo.lock", Some("@@ -1 +1 @@\n-a\n+b")),
+                diff_entry(
+                    "src/main.rs",
+                    Some("@@ -10,2 +10,2 @@ fn main\n keep\n-x\n+y")
+                ),
+            ]);
+            return ([(header::LINK, next)], axum::Json(files)).into_response();
+        }
+        ("GET", "/repos/org/repo/pulls") => {
+            assert!(query.contains("state=closed") && query.contains("sort=updated"));
+            let hours_ago = |h: i64| (chrono::Utc::now() - chrono::Duration::hours(h)).to_rfc3339();
+            let pr = |n: u64, merged: Option<i64>, updated: i64| {
+                json!({
+                    "number": n,
+                    "title": format!("PR {}", n),
+                    "user": {"login": "ada"},
+                    "updated_at": hours_ago(updated),
+                    "merged_at": merged.map(hours_ago),
+                    "merge_commit_sha": merged.map(|_| format!("merge{}", n)),
+                })
+            };
+            let page = query.split('&').find_map(|p| p.strip_prefix("page="));
+            let prs: Vec<Value> = if page == Some("1") {
+                // Odd numbers were merged, even ones closed unmerged
+                (1..=100)
+                    .map(|n| pr(n, (n % 2 == 1).then_some(2), 1))
+                    .collect()
+            } else if page == Some("2") {
+                let mut prs = vec![pr(101, Some(2), 1), pr(102, Some(30 * 24), 1)];
+                prs.extend((103..=200).map(|n| pr(n, Some(20 * 24), 20 * 24)));
+                prs
+            } else {
+                return (StatusCode::INTERNAL_SERVER_ERROR, "read past the cutoff").into_response();
+            };
+            json!(prs)
+        }
+        ("GET", path)
+            if path.starts_with("/repos/org/repo/pulls/") && !path.ends_with("/files") =>
+        {
+            let number = path.rsplit('/').next().unwrap();
+            json!({
+                "number": number.parse::<u64>().unwrap(),
+                "head": {"sha": "headsha"},
+                "base": {"sha": "basesha"},
+            })
+        }
+        ("GET", "/search/code") if query.contains("repo%3Aorg%2Frepo") => json!({
+            "total_count": 1,
+            "items": [{
+                "path": "src/lib.rs",
+                "html_url": "https://github.com/org/repo/blob/main/src/lib.rs",
+                "repository": {"full_name": "org/repo"}
+            }]
+        }),
+        ("GET", "/search/code") => json!({"total_count": 0, "items": []}),
+        ("GET", "/repos/org/repo/issues") => json!([
+            {"number": 7, "title": "Crash on start", "state": "open", "user": {"login": "ada"},
+             "labels": [{"name": "bug"}], "comments": 1,
+             "created_at": "2024-05-01T10:00:00Z", "updated_at": "2024-05-02T10:00:00Z"},
+            {"number": 8, "title": "Fix crash", "state": "open", "user": {"login": "bob"},
+             "labels": [], "pull_request": {"url": "https://api.github.com/repos/org/repo/pulls/8"}}
+        ]),
+        ("GET", "/repos/org/repo/issues/7") => json!({
+            "number": 7, "title": "Crash on start", "body": "It crashes", "state": "open",
+            "user": {"login": "ada"}, "labels": [{"name": "bug"}],
+            "assignees": [{"login": "bob"}], "created_at": "2024-05-01T10:00:00Z", "closed_at": null
+        }),
+        ("GET", "/repos/org/repo/issues/7/comments") => json!([
+            {"user": {"login": "bob"}, "body": "Looking", "created_at": "2024-05-01T11:00:00Z"}
+        ]),
+        ("GET", "/repos/org/repo/compare/v1...v2") => json!({
+            "status": "ahead", "ahead_by": 2, "behind_by": 0, "total_commits": 2,
+            "commits": [
+                {"sha": "c1", "author": {"login": "ada"},
+                 "commit": {"message": "Add parser", "author": {"name": "Ada", "date": "2024-05-01T10:00:00Z"}}},
+                {"sha": "c2", "author": null,
+                 "commit": {"message": "Fix parser", "author": {"name": "Bob", "date": "2024-05-02T10:00:00Z"}}}
+            ]
+        }),
+        ("GET", "/repos/org/repo/commits/headsha/check-runs") => json!({
+            "total_count": 2,
+            "check_runs": [
+                {"name": "build", "status": "completed", "conclusion": "success"},
+                {"name": "test", "status": "completed", "conclusion": "failure",
+                 "html_url": "https://github.com/org/repo/runs/2",
+                 "output": {"title": "1 test failed", "summary": "parser::tests failed"}}
+            ]
+        }),
+        ("GET", "/repos/org/repo/actions/runs") if query.contains("head_sha=headsha") => json!({
+            "workflow_runs": [{"id": 11, "name": "CI", "status": "completed", "conclusion": "failure",
+                               "html_url": "https://github.com/org/repo/actions/runs/11"}]
+        }),
+        ("GET", "/repos/org/repo/actions/runs/11/jobs") => json!({
+            "jobs": [
+                {"id": 21, "name": "build", "conclusion": "success"},
+                {"id": 22, "name": "test", "conclusion": "failure",
+                 "steps": [{"name": "Checkout", "conclusion": "success"},
+                           {"name": "Run tests", "conclusion": "failure"}]}
+            ]
+        }),
+        ("GET", "/repos/org/repo/actions/jobs/22/logs") => {
+            // Logs are served from a redirect target
+            let location = format!("{}/logs/22.txt", base_url);
+            return (StatusCode::FOUND, [(header::LOCATION, location)]).into_response();
+        }
+        ("GET", "/logs/22.txt") => {
+            let log: Vec<String> = (1..=300).map(|i| format!("line {}", i)).collect();
+            return log.join("\n").into_response();
+        }
+        ("POST", "/graphql") => json!({
+            "data": {"repository": {"object": {"blame": {"ranges": [
+                {"startingLine": 1, "endingLine": 5,
+                 "commit": {"oid": "c1", "messageHeadline": "Add parser",
+                            "author": {"name": "Ada", "date": "2024-05-01T10:00:00Z"}}},
+                {"startingLine": 6, "endingLine": 9,
+                 "commit": {"oid": "c2", "messageHeadline": "Fix parser",
+                            "author": {"name": "Bob", "date": "2024-05-02T10:00:00Z"}}}
+            ]}}}}
+        }),
+        ("GET", "/repos/org/repo/contents/src/main.rs") => {
+            let text = if query.contains("headsha") {
+                "fn main() {\n    y();\n}\n"
+            } else {
+                "fn main() {\n    x();\n}\n"
+            };
+            json!({
+                "type": "file",
+                "size": text.len(),
+                "encoding": "base64",
+                "content": base64::engine::general_purpose::STANDARD.encode(text),
+            })
+        }
+        (_, path) if path.ends_with("/reviews") => {
+            json!({"id": 80, "state": "COMMENTED", "html_url": "https://github.com/org/repo/pull/5#review-80"})
+        }
+        ("DELETE", path) if path.ends_with("/labels/missing") => {
+            let body = json!({"message": "Label does not exist"});
+            return (StatusCode::NOT_FOUND, axum::Json(body)).into_response();
+        }
+        (_, path) if path.ends_with("/labels") => {
+            json!([{"name": "bug"}, {"name": "needs review"}, {"name": "area/ci"}])
+        }
+        (_, path) if path.contains("/labels/") => json!([{"name": "bug"}]),
+        (_, path) if path.ends_with("/requested_reviewers") => {
+            json!({"number": 5, "requested_reviewers": [{"login": "ada"}], "requested_teams": [{"slug": "core"}]})
+        }
+        _ => json!({"id": 9, "html_url": "https://github.com/org/repo/issues/5#issuecomment-9"}),
+    };
+    axum::Json(body).into_response()
+}
+
+/// Mock GitHub API recording every request and answering with `respond`
+async fn mock_github() -> (String, Recorded) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let recorded: Recorded = Arc::new(Mutex::new(Vec::new()));
+    let requests = recorded.clone();
+    let url = base_url.clone();
+    let router = Router::new().fallback(move |method: Method, uri: Uri, body: Bytes| {
+        let requests = requests.clone();
+        let url = url.clone();
+        async move {
+            let json = serde_json::from_slice(&body).unwrap_or(Value::Null);
+            requests
+                .lock()
+                .unwrap()
+                .push((method.to_string(), uri.path().to_string(), json));
+            respond(&url, &method, &uri)
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    (base_url, recorded)
+}
+
+/// Config for the mock at `base_url`, with writes allowed
+fn config(base_url: &str) -> GitHubConfig {
+    GitHubConfig {
+        token: "token".to_string(),
+        owner: "org".to_string(),
+        repo: "repo".to_string(),
+        base_url: Some(base_url.to_string()),
+        dry_run: false,
+        allow_writes: true,
+    }
+}
+
+fn tool(
+    base_url: &str,
+    dry_run: bool,
+    approval: Option<Arc<dyn HumanInput>>,
+    name: &str,
+) -> Arc<dyn Tool> {
+    tool_with(
+        GitHubConfig {
+            dry_run,
+            ..config(base_url)
+        },
+        approval,
+        name,
+    )
+}
+
+fn tool_with(
+    config: GitHubConfig,
+    approval: Option<Arc<dyn HumanInput>>,
+    name: &str,
+) -> Arc<dyn Tool> {
+    create_tools_with(config, approval)
+        .unwrap()
+        .into_iter()
+        .find(|t| t.name() == name)
+        .unwrap()
+}
+
+/// User who always gives the same answer, remembering what was asked
+struct ScriptedApprover {
+    response: HumanResponse,
+    asked: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl HumanInput for ScriptedApprover {
+    async fn ask(
+        &self,
+        request: &HumanRequest,
+    ) -> Result<HumanResponse, Box<dyn Error + Send + Sync>> {
+        assert_eq!(request.source, "github");
+        self.asked.lock().unwrap().push(request.message.clone());
+        Ok(self.response.clone())
+    }
+}
+
+fn approver(response: HumanResponse) -> Arc<ScriptedApprover> {
+    Arc::new(ScriptedApprover {
+        response,
+        asked: Mutex::new(Vec::new()),
+    })
+}
+
+#[tokio::test]
+async fn test_submit_review_with_inline_comments() {
+    let (base_url, recorded) = mock_github().await;
+    let review = tool(&base_url, false, None, "submit_pull_request_review");
+
+    let result = review
+        .execute(json!({
+            "pr_number": 5,
+            "body": "A few issues",
+            "event": "REQUEST_CHANGES",
+            "comments": [
+                {"path": "src/lib.rs", "line": 12, "body": "Handle the error"},
+                {"path": "src/old.rs", "line": 3, "side": "LEFT", "start_line": 1, "body": "Why remove this?"}
+            ]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(result["review_id"], 80);
+    assert_eq!(result["state"], "COMMENTED");
+    assert_eq!(result["comments"], 2);
+
+    let requests = recorded.lock().unwrap();
+    let (method, path, body) = &requests[0];
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/repos/org/repo/pulls/5/reviews");
+    assert_eq!(body["event"], "REQUEST_CHANGES");
+    assert_eq!(
+        body["comments"][0],
+        json!({"path": "src/lib.rs", "line": 12, "side": "RIGHT", "body": "Handle the error"})
+    );
+    assert_eq!(body["comments"][1]["start_line"], 1);
+    assert_eq!(body["comments"][1]["start_side"], "LEFT");
+}
+
+#[tokio::test]
+async fn test_update_labels_request_reviewers_and_comment() {
+    let (base_url, recorded) = mock_github().await;
+
+    let result = tool(&base_url, false, None, "update_labels")
+        .execute(json!({"number": 5, "add": ["bug"], "remove": ["needs review", "area/ci"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["labels"], json!(["bug"]));
+
+    let result = tool(&base_url, false, None, "request_reviewers")
+        .execute(json!({"pr_number": 5, "reviewers": ["ada"], "team_reviewers": ["core"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["requested_reviewers"], json!(["ada"]));
+    assert_eq!(result["requested_teams"], json!(["core"]));
+
+    let result = tool(&base_url, false, None, "comment_on_issue")
+        .execute(json!({"number": 5, "body": "Thanks!", "repo": "other"}))
+        .await
+        .unwrap();
+    assert_eq!(result["comment_id"], 9);
+
+    let requests: Vec<(String, String)> = recorded
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(method, path, _)| (method.clone(), path.clone()))
+        .collect();
+    let expected = [
+        ("POST", "/repos/org/repo/issues/5/labels"),
+        ("DELETE", "/repos/org/repo/issues/5/labels/needs%20review"),
+        ("DELETE", "/repos/org/repo/issues/5/labels/area%2Fci"),
+        ("POST", "/repos/org/repo/pulls/5/requested_reviewers"),
+        ("POST", "/repos/org/other/issues/5/comments"),
+    ];
+    assert_eq!(
+        requests,
+        expected.map(|(m, p)| (m.to_string(), p.to_string()))
+    );
+    assert_eq!(recorded.lock().unwrap()[0].2, json!({"labels": ["bug"]}));
+}
+
+#[tokio::test]
+async fn test_label_failure_reports_applied_changes() {
+    let (base_url, recorded) = mock_github().await;
+    let err = tool(&base_url, false, None, "update_labels")
+        .execute(json!({"number": 5, "add": ["bug"], "remove": ["missing", "area/ci"]}))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("(removed missing)"), "{}", err);
+    assert!(err.contains("already applied: added bug"), "{}", err);
+    assert_eq!(recorded.lock().unwrap().len(), 2, "stops at the failure");
+}
+
+#[tokio::test]
+async fn test_writes_without_approver_are_refused() {
+    let (base_url, recorded) = mock_github().await;
+    let config = GitHubConfig {
+        allow_writes: false,
+        ..config(&base_url)
+    };
+    let err = tool_with(config, None, "comment_on_issue")
+        .execute(json!({"number": 5, "body": "Thanks!", "owner": "org/../../user"}))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("GITHUB_ALLOW_WRITES"), "{}", err);
+    assert!(recorded.lock().unwrap().is_empty());
+
+    // Owner and repo from the model are encoded into one path segment each
+    let comment = tool(&base_url, true, None, "comment_on_issue");
+    let preview = comment
+        .execute(json!({"number": 5, "body": "Thanks!", "owner": "org/../../user"}))
+        .await
+        .unwrap();
+    assert_eq!(
+        preview["requests"][0]["path"],
+        "/repos/org%2F..%2F..%2Fuser/repo/issues/5/comments"
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_sends_nothing() {
+    let (base_url, recorded) = mock_github().await;
+    let approval = approver(HumanResponse::Accept(json!({})));
+    let comment = tool(&base_url, true, Some(approval.clone()), "comment_on_issue");
+
+    let result = comment
+        .execute(json!({"number": 5, "body": "Thanks!"}))
+        .await
+        .unwrap();
+    assert_eq!(result["dry_run"], true);
+    assert_eq!(result["requests"][0]["method"], "POST");
+    assert_eq!(
+        result["requests"][0]["path"],
+        "/repos/org/repo/issues/5/comments"
+    );
+    assert_eq!(result["requests"][0]["body"], json!({"body": "Thanks!"}));
+
+    assert!(recorded.lock().unwrap().is_empty());
+    assert!(approval.asked.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_writes_wait_for_approval() {
+    let (base_url, recorded) = mock_github().await;
+
+    let declined = approver(HumanResponse::Decline);
+    let err = tool(&base_url, false, Some(declined.clone()), "comment_on_issue")
+        .execute(json!({"number": 5, "body": "Thanks!"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("declined"), "{}", err);
+    assert!(recorded.lock().unwrap().is_empty());
+    let asked = declined.asked.lock().unwrap()[0].clone();
+    assert!(asked.starts_with("Comment on org/repo#5"), "{}", asked);
+    assert!(asked.contains("Thanks!"));
+
+    let accepted = approver(HumanResponse::Accept(json!({})));
+    tool(&base_url, false, Some(accepted), "comment_on_issue")
+        .execute(json!({"number": 5, "body": "Thanks!"}))
+        .await
+        .unwrap();
+    assert_eq!(recorded.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_diff_pages_through_all_files() {
+    let (base_url, recorded) = mock_github().await;
+    let diff = tool(&base_url, false, None, "get_pull_request_diff");
+
+    let result = diff
+        .execute(json!({"pr_number": 5, "exclude": ["*.lock"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["total_files"], 4);
+    let paths: Vec<&str> = result["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["src/main.rs", "src/lib.rs", "logo.png"]);
+
+    let main = &result["files"][0];
+    assert_eq!(main["status"], "modified");
+    assert_eq!(main["hunks"][0]["new_start"], 10);
+    assert_eq!(
+        main["hunks"][0]["lines"][2],
+        json!({"kind": "add", "new_line": 11, "text": "y"})
+    );
+    let logo = &result["files"][2];
+    assert_eq!(logo["hunks"], json!([]));
+    assert!(logo["note"].as_str().unwrap().contains("get_file_contents"));
+    assert_eq!(recorded.lock().unwrap().len(), 2);
+
+    let result = diff
+        .execute(json!({"pr_number": 5, "include": ["src/*"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["files"].as_array().unwrap().len(), 2);
+    assert_eq!(result["additions"], 2);
+}
+
+#[tokio::test]
+async fn test_file_contents_at_head_and_base() {
+    let (base_url, _) = mock_github().await;
+    let contents = tool(&base_url, false, None, "get_file_contents");
+
+    let head = contents
+        .execute(json!({"path": "src/main.rs", "pr_number": 5}))
+        .await
+        .unwrap();
+    assert_eq!(head["git_ref"], "headsha");
+    assert_eq!(head["total_lines"], 3);
+    assert_eq!(head["content"], "fn main() {\n    y();\n}");
+
+    let base = contents
+        .execute(json!({
+            "path": "src/main.rs",
+            "pr_number": 5,
+            "version": "base",
+            "start_line": 2,
+            "end_line": 2
+        }))
+        .await
+        .unwrap();
+    assert_eq!(base["git_ref"], "basesha");
+    assert_eq!(base["content"], "    x();");
+    assert_eq!(
+        (base["start_line"].clone(), base["end_line"].clone()),
+        (json!(2), json!(2))
+    );
+}
+
+#[tokio::test]
+async fn test_dot_segments_are_refused() {
+    let (base_url, recorded) = mock_github().await;
+    let contents = tool(&base_url, false, None, "get_file_contents");
+
+    for args in [
+        json!({"path": "../../../../user", "git_ref": "main"}),
+        json!({"owner": "..", "repo": "..", "path": "user", "git_ref": "main"}),
+        json!({"path": "docs/./../../x", "git_ref": "main"}),
+    ] {
+        let err = contents.execute(args).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid"), "{}", err);
+    }
+    assert!(recorded.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_merged_prs_stop_at_the_cutoff() {
+    let (base_url, recorded) = mock_github().await;
+    let result = tool(&base_url, false, None, "list_merged_prs")
+        .execute(json!({"days": 7}))
+        .await
+        .unwrap();
+
+    let prs = result["prs"].as_array().unwrap();
+    let numbers: Vec<u64> = prs.iter().map(|p| p["number"].as_u64().unwrap()).collect();
+    let expected: Vec<u64> = (1..=99).step_by(2).chain([101]).collect();
+    assert_eq!(numbers, expected);
+    assert_eq!(prs[0]["merge_sha"], "merge1");
+    assert_eq!(prs[50]["merge_sha"], "merge101");
+    assert_eq!(recorded.lock().unwrap().len(), 2, "one request per page");
+}
+
+#[tokio::test]
+async fn test_search_code_issues_and_commits() {
+    let (base_url, _) = mock_github().await;
+
+    let result = tool(&base_url, false, None, "search_code")
+        .execute(json!({"query": "parse_patch"}))
+        .await
+        .unwrap();
+    assert_eq!(result["total_count"], 1);
+    assert_eq!(result["matches"][0]["path"], "src/lib.rs");
+    assert_eq!(result["matches"][0]["repository"], "org/repo");
+
+    let result = tool(&base_url, false, None, "list_issues")
+        .execute(json!({"labels": ["bug"]}))
+        .await
+        .unwrap();
+    let issues = result["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1, "pull requests are left out");
+    assert_eq!(issues[0]["number"], 7);
+    assert_eq!(issues[0]["labels"], json!(["bug"]));
+
+    let result = tool(&base_url, false, None, "get_issue")
+        .execute(json!({"number": 7}))
+        .await
+        .unwrap();
+    assert_eq!(result["body"], "It crashes");
+    assert_eq!(result["assignees"], json!(["bob"]));
+    assert_eq!(result["comments"][0]["body"], "Looking");
+
+    let result = tool(&base_url, false, None, "list_commits")
+        .execute(json!({"base": "v1", "head": "v2"}))
+        .await
+        .unwrap();
+    assert_eq!(result["ahead_by"], 2);
+    assert_eq!(result["commits"][0]["author"], "ada");
+    assert_eq!(result["commits"][1]["author"], "Bob");
+    assert_eq!(result["commits"][1]["message"], "Fix parser");
+}
+
+#[tokio::test]
+async fn test_checks_and_failed_job_logs() {
+    let (base_url, _) = mock_github().await;
+
+    let result = tool(&base_url, false, None, "get_check_runs")
+        .execute(json!({"pr_number": 5}))
+        .await
+        .unwrap();
+    assert_eq!(result["sha"], "headsha");
+    assert_eq!(
+        (result["total"].clone(), result["failed"].clone()),
+        (json!(2), json!(1))
+    );
+    assert_eq!(result["check_runs"][1]["title"], "1 test failed");
+
+    let result = tool(&base_url, false, None, "get_workflow_run_logs")
+        .execute(json!({"pr_number": 5, "max_lines": 2}))
+        .await
+        .unwrap();
+    let jobs = result["runs"][0]["jobs"].as_array().unwrap();
+    assert_eq!(jobs.len(), 1, "only the failed job");
+    assert_eq!(jobs[0]["name"], "test");
+    assert_eq!(jobs[0]["failed_steps"], json!(["Run tests"]));
+    assert_eq!(jobs[0]["total_lines"], 300);
+    assert_eq!(jobs[0]["log"], "line 299\nline 300");
+
+    let err = tool(&base_url, false, None, "get_workflow_run_logs")
+        .execute(json!({}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("pr_number"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_blame_for_line_range() {
+    let (base_url, recorded) = mock_github().await;
+    let result = tool(&base_url, false, None, "get_blame")
+        .execute(json!({"path": "src/lib.rs", "start_line": 4, "end_line": 7}))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result["ranges"],
+        json!([
+            {"start_line": 4, "end_line": 5, "sha": "c1", "author": "Ada",
+             "date": "2024-05-01T10:00:00Z", "message": "Add parser"},
+            {"start_line": 6, "end_line": 7, "sha": "c2", "author": "Bob",
+             "date": "2024-05-02T10:00:00Z", "message": "Fix parser"}
+        ])
+    );
+    let variables = recorded.lock().unwrap()[0].2["variables"].clone();
+    assert_eq!(variables["path"], "src/lib.rs");
+    assert_eq!(variables["ref"], "HEAD");
+}
@@ -44,6 +44,7 @@ impl MockModel {
         Content {
             role: "model".to_string(),
             parts: vec![Part::FunctionCall {
+                id: None,
                 name: tool_name.to_string(),
                 args,
                 thought_signature: None,
@@ -0,0 +1,396 @@
+// SPDX-License-Identifier: MIT
+
+//! Requests MCP servers send back to kinetic: sampling, roots, elicitation
+//! and progress notifications
+
+use async_trait::async_trait;
+use axum::Router;
+use kinetic_rs::adk::agent::AgentEvent;
+use kinetic_rs::adk::model::{Content as ModelContent, GenerationConfig, Model, Part};
+use kinetic_rs::adk::tool::{with_events, with_model, Tool};
+use kinetic_rs::kinetic::human::{HumanInput, HumanRequest, HumanResponse};
+use kinetic_rs::kinetic::mcp::manager::{McpConnection, McpServerConfig, McpServiceManager};
+use kinetic_rs::kinetic::mcp::tool::McpTool;
+use kinetic_rs::kinetic::mcp::McpTransport;
+use kinetic_rs::kinetic::workflow::builder::Builder;
+use kinetic_rs::kinetic::workflow::registry::ToolRegistry;
+use rmcp::handler::server::ServerHandler;
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, CreateElicitationRequestParam,
+    CreateMessageRequestParam, ElicitationAction, ElicitationSchema, ListToolsResult,
+    PaginatedRequestParam, ProgressNotificationParam, Role, SamplingMessage, ServerCapabilities,
+    ServerInfo,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::ErrorData as McpError;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// MCP server whose tools call back into the client
+#[derive(Clone)]
+struct CallbackServer;
+
+fn internal(e: impl std::fmt::Display) -> McpError {
+    McpError::internal_error(e.to_string(), None)
+}
+
+impl ServerHandler for CallbackServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let schema = Arc::new(json!({"type": "object"}).as_object().unwrap().clone());
+        Ok(ListToolsResult {
+            tools: ["summarize", "roots", "greet", "index"]
+                .into_iter()
+                .map(|name| rmcp::model::Tool::new(name, name, schema.clone()))
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let text = match request.name.as_ref() {
+            "summarize" => {
+                let result = context
+                    .peer
+                    .create_message(CreateMessageRequestParam {
+                        messages: vec![SamplingMessage {
+                            role: Role::User,
+                            content: Content::text("Summarize kinetic"),
+                        }],
+                        model_preferences: None,
+                        system_prompt: Some("Be brief.".to_string()),
+                        include_context: None,
+                        temperature: None,
+                        max_tokens: 50,
+                        stop_sequences: None,
+                        metadata: None,
+                    })
+                    .await
+                    .map_err(internal)?;
+                let text = result.message.content.as_text().unwrap().text.clone();
+                format!("{} via {}", text, result.model)
+            }
+            "roots" => {
+                let roots = context.peer.list_roots().await.map_err(internal)?;
+                roots
+                    .roots
+                    .iter()
+                    .map(|r| r.uri.clone())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+            "greet" => {
+                let schema = ElicitationSchema::builder()
+                    .required_string("name")
+                    .build()
+                    .map_err(internal)?;
+                let result = context
+                    .peer
+                    .create_elicitation(CreateElicitationRequestParam {
+                        message: "Who should I greet?".to_string(),
+                        requested_schema: schema,
+                    })
+                    .await
+                    .map_err(internal)?;
+                match result.action {
+                    ElicitationAction::Accept => {
+                        format!(
+                            "Hello {}",
+                            result.content.unwrap()["name"].as_str().unwrap()
+                        )
+                    }
+                    ElicitationAction::Decline => "declined".to_string(),
+                    ElicitationAction::Cancel => "cancelled".to_string(),
+                }
+            }
+            "index" => {
+                let token = context.meta.get_progress_token().unwrap();
+                for step in 1..=2 {
+                    context
+                        .peer
+                        .notify_progress(ProgressNotificationParam {
+                            progress_token: token.clone(),
+                            progress: step as f64,
+                            total: Some(2.0),
+                            message: Some(format!("step {}", step)),
+                        })
+                        .await
+                        .map_err(internal)?;
+                }
+                // Let the notifications land before the result
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                "indexed".to_string()
+            }
+            other => return Err(McpError::invalid_params(other.to_string(), None)),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+}
+
+/// Model answering with a fixed prefix and the last user text
+struct EchoModel;
+
+#[async_trait]
+impl Model for EchoModel {
+    async fn generate_content(
+        &self,
+        history: &[ModelContent],
+        _config: Option<&GenerationConfig>,
+        _tools: Option<&[Arc<dyn Tool>]>,
+    ) -> Result<ModelContent, Box<dyn Error + Send + Sync>> {
+        assert_eq!(history[0].role, "system");
+        let last = match &history.last().unwrap().parts[0] {
+            Part::Text(text) => text.clone(),
+            _ => String::new(),
+        };
+        Ok(ModelContent {
+            role: "model".to_string(),
+            parts: vec![Part::Text(format!("summary of '{}'", last))],
+        })
+    }
+}
+
+/// Model standing in for the agent calling a tool
+struct CallerModel;
+
+#[async_trait]
+impl Model for CallerModel {
+    async fn generate_content(
+        &self,
+        _history: &[ModelContent],
+        _config: Option<&GenerationConfig>,
+        _tools: Option<&[Arc<dyn Tool>]>,
+    ) -> Result<ModelContent, Box<dyn Error + Send + Sync>> {
+        Ok(ModelContent {
+            role: "model".to_string(),
+            parts: vec![Part::Text("from the caller".to_string())],
+        })
+    }
+
+    fn name(&self) -> &str {
+        "caller-model"
+    }
+}
+
+/// User who always gives the same answer
+struct ScriptedHuman(HumanResponse);
+
+#[async_trait]
+impl HumanInput for ScriptedHuman {
+    async fn ask(
+        &self,
+        request: &HumanRequest,
+    ) -> Result<HumanResponse, Box<dyn Error + Send + Sync>> {
+        assert_eq!(request.source, "callbacks");
+        assert_eq!(request.message, "Who should I greet?");
+        assert_eq!(request.schema["properties"]["name"]["type"], "string");
+        Ok(self.0.clone())
+    }
+}
+
+async fn callback_server() -> SocketAddr {
+    let service = StreamableHttpService::new(
+        || Ok(CallbackServer),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+    let router = Router::new().nest_service("/mcp", service);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    addr
+}
+
+fn config(addr: SocketAddr) -> McpServerConfig {
+    McpServerConfig {
+        name: "callbacks".to_string(),
+        transport: McpTransport::Http,
+        url: Some(format!("http://{}/mcp", addr)),
+        ..Default::default()
+    }
+}
+
+async fn call(service: &Arc<McpConnection>, name: &str) -> Result<String, String> {
+    let tool = McpTool::new(
+        service.clone(),
+        name.to_string(),
+        String::new(),
+        json!({"type": "object"}),
+    );
+    let result: Value = tool.execute(json!({})).await.map_err(|e| e.to_string())?;
+    Ok(result.as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_sampling_uses_configured_model() {
+    let addr = callback_server().await;
+    let manager = McpServiceManager::new();
+    let service = manager.get_or_create_service(&config(addr)).await.unwrap();
+
+    // No model yet: the server's request fails
+    assert!(call(&service, "summarize").await.is_err());
+
+    service
+        .client()
+        .set_sampling_model("echo-model", Arc::new(EchoModel));
+    assert_eq!(
+        call(&service, "summarize").await.unwrap(),
+        "summary of 'Summarize kinetic' via echo-model"
+    );
+
+    // During an agent's tool call, the agent's model answers instead
+    let result = with_model(Arc::new(CallerModel), call(&service, "summarize")).await;
+    assert_eq!(result.unwrap(), "from the caller via caller-model");
+}
+
+#[tokio::test]
+async fn test_roots_are_listed() {
+    let addr = callback_server().await;
+    let dir = std::env::temp_dir();
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            roots: vec![dir.to_string_lossy().to_string()],
+            ..config(addr)
+        })
+        .await
+        .unwrap();
+
+    let expected = format!("file://{}", std::fs::canonicalize(&dir).unwrap().display());
+    assert_eq!(call(&service, "roots").await.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_elicitation_goes_to_human_input() {
+    let addr = callback_server().await;
+
+    let manager = McpServiceManager::new().with_human_input(Arc::new(ScriptedHuman(
+        HumanResponse::Accept(json!({"name": "Ada"})),
+    )));
+    let service = manager.get_or_create_service(&config(addr)).await.unwrap();
+    assert_eq!(call(&service, "greet").await.unwrap(), "Hello Ada");
+
+    let manager =
+        McpServiceManager::new().with_human_input(Arc::new(ScriptedHuman(HumanResponse::Cancel)));
+    let service = manager.get_or_create_service(&config(addr)).await.unwrap();
+    assert_eq!(call(&service, "greet").await.unwrap(), "cancelled");
+}
+
+#[tokio::test]
+async fn test_progress_is_forwarded_as_log_events() {
+    let addr = callback_server().await;
+    let manager = McpServiceManager::new();
+    let service = manager.get_or_create_service(&config(addr)).await.unwrap();
+
+    let (tx, mut rx) = mpsc::channel(16);
+    let result = with_events(Some(tx), call(&service, "index")).await;
+    assert_eq!(result.unwrap(), "indexed");
+
+    let mut logs = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AgentEvent::Log(line) = event {
+            logs.push(line);
+        }
+    }
+    assert_eq!(
+        logs,
+        ["callbacks:index: 1/2 step 1", "callbacks:index: 2/2 step 2"]
+    );
+
+    // Without a listening agent the call still works
+    assert_eq!(call(&service, "index").await.unwrap(), "indexed");
+}
+
+/// OpenAI-compatible endpoint: the agent (sent tools) calls the summarize
+/// tool and then answers with its result; sampling requests get "short"
+async fn fake_openai() -> SocketAddr {
+    let openai = Router::new().route(
+        "/chat/completions",
+        axum::routing::post(|axum::Json(body): axum::Json<Value>| async move {
+            let messages = body["messages"].as_array().unwrap();
+            let message = if body.get("tools").is_none() {
+                json!({"role": "assistant", "content": "short"})
+            } else if let Some(result) = messages.iter().find(|m| m["role"] == "tool") {
+                json!({"role": "assistant", "content": result["content"]})
+            } else {
+                json!({"role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "callbacks__summarize", "arguments": "{}"}
+                }]})
+            };
+            axum::Json(json!({"choices": [{"message": message}]}))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, openai).await.unwrap();
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_calling_agents_model_serves_sampling() {
+    let addr = callback_server().await;
+    let openai_addr = fake_openai().await;
+    std::env::set_var("OPENAI_API_KEY", "test");
+    std::env::set_var("OPENAI_BASE_URL", format!("http://{}", openai_addr));
+
+    let yaml = format!(
+        r#"
+kind: Direct
+name: Summarizer
+description: "Test"
+mcp_servers:
+  - name: "callbacks"
+    transport: http
+    url: "http://{addr}/mcp"
+agent:
+  name: Summarizer
+  description: "Test"
+  instructions: "Summarize."
+  model:
+    provider: openai
+    model_name: gpt-4o-mini
+  tools: ["callbacks:summarize"]
+"#
+    );
+    let path =
+        std::env::temp_dir().join(format!("kinetic-mcp-sampling-{}.yaml", std::process::id()));
+    std::fs::write(&path, yaml).unwrap();
+
+    let registry = ToolRegistry::new();
+    let builder = Builder::new(registry.clone(), Arc::new(McpServiceManager::new()));
+    let agent = builder.build_agent(path.to_str().unwrap()).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let answer = agent.run("Go".to_string()).await.unwrap();
+    assert_eq!(answer, "short via gpt-4o-mini");
+
+    // Outside an agent's call there is no model to sample with
+    let tool = registry.get("callbacks:summarize").await.unwrap();
+    assert!(tool.execute(json!({})).await.is_err());
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT
+
+//! Workflows served as MCP tools, called through kinetic's own MCP client
+
+use axum::Router;
+use kinetic_rs::adk::agent::AgentEvent;
+use kinetic_rs::adk::tool::{with_events, Tool};
+use kinetic_rs::kinetic::mcp::manager::{McpServerConfig, McpServiceManager};
+use kinetic_rs::kinetic::mcp::server::WorkflowServer;
+use kinetic_rs::kinetic::mcp::tool::McpTool;
+use kinetic_rs::kinetic::mcp::McpTransport;
+use kinetic_rs::kinetic::workflow::builder::Builder;
+use kinetic_rs::kinetic::workflow::registry::ToolRegistry;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+async fn serve(router: Router) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    addr
+}
+
+/// OpenAI-compatible endpoint answering "Looks good" and recording requests
+async fn fake_openai() -> (SocketAddr, Arc<Mutex<Vec<Value>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let router = Router::new().route(
+        "/chat/completions",
+        axum::routing::post(move |axum::Json(body): axum::Json<Value>| {
+            let recorded = recorded.clone();
+            async move {
+                recorded.lock().unwrap().push(body);
+                axum::Json(json!({
+                    "choices": [{"message": {"role": "assistant", "content": "Looks good"}}]
+                }))
+            }
+        }),
+    );
+    (serve(router).await, requests)
+}
+
+#[tokio::test]
+async fn test_workflow_called_over_mcp() {
+    let (openai_addr, requests) = fake_openai().await;
+    std::env::set_var("OPENAI_API_KEY", "test");
+    std::env::set_var("OPENAI_BASE_URL", format!("http://{}", openai_addr));
+
+    let path = std::env::temp_dir().join(format!("kinetic-mcp-serve-{}.yaml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+kind: Direct
+name: Reviewer
+description: "Reviews workflows"
+agent:
+  name: PRReviewer
+  description: "Reviews a pull request"
+  instructions: "Review the PR."
+  input_schema:
+    type: object
+    properties:
+      pr_number: {type: integer}
+    required: [pr_number]
+  model:
+    provider: openai
+    model_name: gpt-4o
+  tools: []
+"#,
+    )
+    .unwrap();
+
+    let builder = Builder::new(ToolRegistry::new(), Arc::new(McpServiceManager::new()));
+    let server = WorkflowServer::new(builder, &[path.to_string_lossy().to_string()]);
+    let addr = serve(server.router()).await;
+
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "kinetic".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let tools = service.list_all_tools().await.unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "PRReviewer");
+    assert_eq!(
+        tools[0].description.as_deref(),
+        Some("Reviews a pull request")
+    );
+    assert_eq!(tools[0].input_schema["required"], json!(["pr_number"]));
+
+    let tool = McpTool::new(
+        service,
+        "PRReviewer".to_string(),
+        String::new(),
+        json!({"type": "object"}),
+    );
+    let (tx, mut rx) = mpsc::channel(64);
+    let result = with_events(Some(tx), tool.execute(json!({"pr_number": 42})))
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(result, "Looks good");
+
+    let body = requests.lock().unwrap()[0].clone();
+    let user = body["messages"][1]["content"].to_string();
+    assert!(user.contains("42"), "{}", user);
+
+    let mut logs = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AgentEvent::Log(line) = event {
+            logs.push(line);
+        }
+    }
+    assert!(
+        logs.iter()
+            .any(|l| l.starts_with("kinetic:PRReviewer: ") && l.contains("Executing agents")),
+        "{:?}",
+        logs
+    );
+}
+
+#[tokio::test]
+async fn test_http_requires_token_when_set() {
+    let builder = Builder::new(ToolRegistry::new(), Arc::new(McpServiceManager::new()));
+    let server = WorkflowServer::new(builder, &["agents/pr_reviewer.yaml".to_string()])
+        .with_token("secret".to_string());
+    let addr = serve(server.router()).await;
+    let url = format!("http://{}/mcp", addr);
+
+    let status = reqwest::Client::new()
+        .post(&url)
+        .bearer_auth("wrong")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "kinetic".to_string(),
+            transport: McpTransport::Http,
+            url: Some(url),
+            headers: [("Authorization".to_string(), "Bearer secret".to_string())].into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let tools = service.list_all_tools().await.unwrap();
+    assert_eq!(tools[0].name, "PRReviewer");
+}
@@ -0,0 +1,627 @@
+// SPDX-License-Identifier: MIT
+
+//! MCP over HTTP transports, connection lifecycle, resources and prompts,
+//! against an in-process MCP server
+
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+use kinetic_rs::adk::agent::AgentMessage;
+use kinetic_rs::adk::tool::Tool;
+use kinetic_rs::kinetic::mcp::manager::{McpServerConfig, McpServerState, McpServiceManager};
+use kinetic_rs::kinetic::mcp::prompt::prompt_text;
+use kinetic_rs::kinetic::mcp::resource::resource_text;
+use kinetic_rs::kinetic::mcp::tool::McpTool;
+use kinetic_rs::kinetic::mcp::McpTransport;
+use kinetic_rs::kinetic::workflow::builder::Builder;
+use kinetic_rs::kinetic::workflow::registry::ToolRegistry;
+use rmcp::handler::server::ServerHandler;
+use rmcp::model::{
+    AnnotateAble, CallToolRequestParam, CallToolResult, Content, GetPromptRequestParam,
+    GetPromptResult, ListPromptsResult, ListResourcesResult, ListToolsResult,
+    PaginatedRequestParam, Prompt, PromptMessage, PromptMessageRole, RawResource,
+    ReadResourceRequestParam, ReadResourceResult, ResourceContents, ServerCapabilities, ServerInfo,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::transport::sse_server::{SseServer, SseServerConfig};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::ErrorData as McpError;
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const TOKEN: &str = "test-secret";
+
+/// MCP server stand-in with an `echo` tool, a `mem://guide` resource and a
+/// `review` prompt
+#[derive(Clone)]
+struct EchoServer;
+
+impl ServerHandler for EchoServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult {
+            resources: vec![RawResource::new("mem://guide", "guide").no_annotation()],
+            ..Default::default()
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        match request.uri.as_str() {
+            "mem://guide" => Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text("Always be brief.", "mem://guide")],
+            }),
+            uri => Err(McpError::resource_not_found(uri.to_string(), None)),
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult {
+            prompts: vec![Prompt::new("review", Some("Review something"), None)],
+            ..Default::default()
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        let topic = request
+            .arguments
+            .and_then(|args| args.get("topic").and_then(|t| t.as_str()).map(String::from))
+            .unwrap_or_default();
+        Ok(GetPromptResult {
+            description: None,
+            messages: vec![PromptMessage::new_text(
+                PromptMessageRole::User,
+                format!("Review {} carefully.", topic),
+            )],
+        })
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let schema = json!({
+            "type": "object",
+            "properties": {"text": {"type": "string"}},
+            "required": ["text"]
+        });
+        Ok(ListToolsResult {
+            tools: vec![rmcp::model::Tool::new(
+                "echo",
+                "Echoes the text back",
+                Arc::new(schema.as_object().unwrap().clone()),
+            )],
+            ..Default::default()
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let text = request
+            .arguments
+            .and_then(|args| args.get("text").and_then(|t| t.as_str()).map(String::from))
+            .unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+}
+
+/// Reject requests without the expected bearer token
+async fn require_token(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let expected = format!("Bearer {}", TOKEN);
+    match request.headers().get("authorization") {
+        Some(value) if value == expected.as_str() => Ok(next.run(request).await),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+async fn serve(router: Router) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    addr
+}
+
+fn streamable_http_router() -> Router {
+    let service = StreamableHttpService::new(
+        || Ok(EchoServer),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+    Router::new().nest_service("/mcp", service)
+}
+
+async fn streamable_http_server() -> SocketAddr {
+    serve(streamable_http_router().layer(middleware::from_fn(require_token))).await
+}
+
+async fn sse_server() -> SocketAddr {
+    let (sse, router) = SseServer::new(SseServerConfig {
+        bind: "127.0.0.1:0".parse().unwrap(),
+        sse_path: "/sse".to_string(),
+        post_path: "/message".to_string(),
+        ct: Default::default(),
+        sse_keep_alive: None,
+    });
+    let addr = serve(router).await;
+    sse.with_service(|| EchoServer);
+    addr
+}
+
+async fn call_echo(config: McpServerConfig) {
+    let manager = McpServiceManager::new();
+    let service = manager.get_or_create_service(&config).await.unwrap();
+
+    let tools = service.list_all_tools().await.unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "echo");
+
+    let tool = McpTool::new(
+        service,
+        "echo".to_string(),
+        String::new(),
+        json!({"type": "object"}),
+    );
+    let result = tool.execute(json!({"text": "hello"})).await.unwrap();
+    assert_eq!(result, "hello");
+}
+
+#[tokio::test]
+async fn test_streamable_http_transport_with_bearer_token() {
+    let addr = streamable_http_server().await;
+    std::env::set_var("KINETIC_TEST_MCP_TOKEN", TOKEN);
+
+    call_echo(McpServerConfig {
+        name: "remote".to_string(),
+        transport: McpTransport::Http,
+        url: Some(format!("http://{}/mcp", addr)),
+        bearer_token_env: Some("KINETIC_TEST_MCP_TOKEN".to_string()),
+        ..Default::default()
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_streamable_http_transport_rejected_without_token() {
+    let addr = streamable_http_server().await;
+    let manager = McpServiceManager::new();
+    let result = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "remote".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            ..Default::default()
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_sse_transport_with_headers() {
+    let addr = sse_server().await;
+    let mut headers = HashMap::new();
+    headers.insert("X-Team".to_string(), "platform".to_string());
+
+    call_echo(McpServerConfig {
+        name: "legacy".to_string(),
+        transport: McpTransport::Sse,
+        url: Some(format!("http://{}/sse", addr)),
+        headers,
+        ..Default::default()
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_remote_transport_requires_url() {
+    let manager = McpServiceManager::new();
+    let err = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "remote".to_string(),
+            transport: McpTransport::Http,
+            ..Default::default()
+        })
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("has no url"));
+}
+
+#[tokio::test]
+async fn test_reconnects_after_server_restart() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, streamable_http_router())
+            .await
+            .unwrap();
+    });
+
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "flaky".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    service.list_all_tools().await.unwrap();
+
+    // The restarted server has forgotten our session
+    server.abort();
+    let _ = server.await;
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, streamable_http_router())
+            .await
+            .unwrap();
+    });
+
+    let tools = service.list_all_tools().await.unwrap();
+    assert_eq!(tools.len(), 1);
+
+    let status = manager.status().await;
+    assert_eq!(status[0].state, McpServerState::Running);
+    assert_eq!(status[0].restarts, 1);
+}
+
+#[tokio::test]
+async fn test_status_answers_while_reconnecting() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, streamable_http_router())
+            .await
+            .unwrap();
+    });
+
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "gone".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    service.list_all_tools().await.unwrap();
+
+    // The server does not come back, so reconnecting backs off
+    server.abort();
+    let _ = server.await;
+    let listing = tokio::spawn({
+        let service = service.clone();
+        async move {
+            service
+                .list_all_tools()
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let status = tokio::time::timeout(std::time::Duration::from_secs(1), service.status())
+        .await
+        .expect("status blocked during reconnect");
+    assert_eq!(status.state, McpServerState::Reconnecting);
+    listing.abort();
+}
+
+#[tokio::test]
+async fn test_same_name_with_other_config_is_refused() {
+    let addr = serve(streamable_http_router()).await;
+    let config = McpServerConfig {
+        name: "shared".to_string(),
+        transport: McpTransport::Http,
+        url: Some(format!("http://{}/mcp", addr)),
+        ..Default::default()
+    };
+    let manager = McpServiceManager::new();
+    let first = manager.get_or_create_service(&config).await.unwrap();
+    let again = manager.get_or_create_service(&config).await.unwrap();
+    assert!(Arc::ptr_eq(&first, &again));
+
+    let mut other = config.clone();
+    other
+        .headers
+        .insert("X-Team".to_string(), "other".to_string());
+    let err = manager.get_or_create_service(&other).await.err().unwrap();
+    assert!(
+        err.to_string().contains("different configuration"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_status_and_shutdown() {
+    let addr = serve(streamable_http_router()).await;
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "status".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    service.list_all_tools().await.unwrap();
+
+    let status = manager.status().await;
+    assert_eq!(status.len(), 1);
+    assert_eq!(status[0].name, "status");
+    assert_eq!(status[0].state, McpServerState::Running);
+    assert_eq!(status[0].tool_count, 1);
+
+    manager.shutdown().await;
+    assert!(manager.status().await.is_empty());
+    assert_eq!(service.status().await.state, McpServerState::Stopped);
+    let err = service.list_all_tools().await.unwrap_err();
+    assert!(err.to_string().contains("stopped"));
+}
+
+#[tokio::test]
+async fn test_connect_timeout() {
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut sockets = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            sockets.push(socket);
+        }
+    });
+
+    let manager = McpServiceManager::new();
+    let err = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "silent".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            timeout_secs: Some(1),
+            ..Default::default()
+        })
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("Timed out"));
+}
+
+#[tokio::test]
+async fn test_builder_registers_namespaced_tools() {
+    let addr = serve(streamable_http_router()).await;
+    let yaml = format!(
+        r#"
+kind: Direct
+name: Namespaced
+description: "Test"
+mcp_servers:
+  - name: "remote"
+    transport: http
+    url: "http://{addr}/mcp"
+    prefix: "remote_"
+  - name: "filtered"
+    transport: http
+    url: "http://{addr}/mcp"
+    exclude: ["ec*"]
+agent:
+  name: Namespaced
+  description: "Test"
+  instructions: "Test"
+  model:
+    provider: gemini
+    model_name: gemini-2.0-flash
+  tools: ["remote:*"]
+"#
+    );
+    let path = std::env::temp_dir().join(format!("kinetic-mcp-{}.yaml", std::process::id()));
+    std::fs::write(&path, yaml).unwrap();
+    std::env::set_var("GOOGLE_API_KEY", "test");
+
+    let registry = ToolRegistry::new();
+    let builder = Builder::new(registry.clone(), Arc::new(McpServiceManager::new()));
+    builder.build_agent(path.to_str().unwrap()).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(registry.get("echo").await.is_none());
+    assert!(registry.get("filtered:echo").await.is_none());
+    let tool = registry.get("remote:echo").await.unwrap();
+    assert_eq!(tool.name(), "remote_echo");
+    let result = tool.execute(json!({"text": "hi"})).await.unwrap();
+    assert_eq!(result, "hi");
+}
+
+#[tokio::test]
+async fn test_servers_sharing_a_tool_name() {
+    let addr = serve(streamable_http_router()).await;
+    let workflow = |web_prefix: &str| {
+        format!(
+            r#"
+kind: Direct
+name: Shared
+description: "Test"
+mcp_servers:
+  - name: "docs"
+    transport: http
+    url: "http://{addr}/mcp"
+  - name: "web-search"
+    transport: http
+    url: "http://{addr}/mcp"
+    {web_prefix}
+agent:
+  name: Shared
+  description: "Test"
+  instructions: "Test"
+  model:
+    provider: gemini
+    model_name: gemini-2.0-flash
+  tools: ["docs:*", "web-search:*"]
+"#
+        )
+    };
+    let path = std::env::temp_dir().join(format!("kinetic-mcp-shared-{}.yaml", std::process::id()));
+    std::env::set_var("GOOGLE_API_KEY", "test");
+
+    std::fs::write(&path, workflow("")).unwrap();
+    let registry = ToolRegistry::new();
+    let builder = Builder::new(registry.clone(), Arc::new(McpServiceManager::new()));
+    builder.build_agent(path.to_str().unwrap()).await.unwrap();
+    let docs = registry.get("docs:echo").await.unwrap();
+    let web = registry.get("web-search:echo").await.unwrap();
+    assert_eq!(docs.name(), "docs__echo");
+    assert_eq!(web.name(), "web-search__echo");
+
+    // Prefixes that give both the same name fail the build
+    std::fs::write(&path, workflow(r#"prefix: "docs__""#)).unwrap();
+    let builder = Builder::new(ToolRegistry::new(), Arc::new(McpServiceManager::new()));
+    let err = builder
+        .build_agent(path.to_str().unwrap())
+        .await
+        .err()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(err.to_string().contains("docs__echo"), "{}", err);
+}
+
+/// OpenAI-compatible endpoint that records request bodies and answers "done"
+async fn fake_openai() -> (SocketAddr, Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+    let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let router = Router::new().route(
+        "/chat/completions",
+        axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+            let recorded = recorded.clone();
+            async move {
+                recorded.lock().unwrap().push(body);
+                axum::Json(json!({
+                    "choices": [{"message": {"role": "assistant", "content": "done"}}]
+                }))
+            }
+        }),
+    );
+    (serve(router).await, requests)
+}
+
+#[tokio::test]
+async fn test_resources_and_prompts() {
+    let addr = serve(streamable_http_router()).await;
+    let manager = McpServiceManager::new();
+    let service = manager
+        .get_or_create_service(&McpServerConfig {
+            name: "docs".to_string(),
+            transport: McpTransport::Http,
+            url: Some(format!("http://{}/mcp", addr)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let resources = service.list_all_resources().await.unwrap();
+    assert_eq!(resources[0].uri, "mem://guide");
+    let guide = service.read_resource("mem://guide").await.unwrap();
+    assert_eq!(resource_text(&guide), "Always be brief.");
+    assert!(service.read_resource("mem://missing").await.is_err());
+
+    let prompts = service.list_all_prompts().await.unwrap();
+    assert_eq!(prompts[0].name, "review");
+    let arguments = HashMap::from([("topic".to_string(), "the diff".to_string())]);
+    let prompt = service.get_prompt("review", &arguments).await.unwrap();
+    assert_eq!(prompt_text(&prompt), "Review the diff carefully.");
+}
+
+#[tokio::test]
+async fn test_agent_instructions_from_prompt_and_context() {
+    let addr = serve(streamable_http_router()).await;
+    let (openai_addr, requests) = fake_openai().await;
+    std::env::set_var("OPENAI_API_KEY", "test");
+    std::env::set_var("OPENAI_BASE_URL", format!("http://{}", openai_addr));
+
+    let yaml = format!(
+        r#"
+kind: Direct
+name: Reviewer
+description: "Test"
+mcp_servers:
+  - name: "docs"
+    transport: http
+    url: "http://{addr}/mcp"
+agent:
+  name: Reviewer
+  description: "Test"
+  prompt:
+    server: docs
+    name: review
+    arguments:
+      topic: "the diff"
+  instructions: "Reply in English."
+  context: ["mcp://docs/mem://guide"]
+  model:
+    provider: openai
+    model_name: gpt-4o
+  tools: ["docs:read_resource"]
+"#
+    );
+    let path = std::env::temp_dir().join(format!("kinetic-mcp-prompt-{}.yaml", std::process::id()));
+    std::fs::write(&path, yaml).unwrap();
+
+    let registry = ToolRegistry::new();
+    let builder = Builder::new(registry.clone(), Arc::new(McpServiceManager::new()));
+    let agent = builder.build_agent(path.to_str().unwrap()).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let response = agent.run_message(AgentMessage::text("go")).await.unwrap();
+    assert_eq!(response.text, "done");
+
+    let body = requests.lock().unwrap()[0].clone();
+    let system = body["messages"][0]["content"].as_str().unwrap().to_string();
+    assert_eq!(
+        system,
+        "Review the diff carefully.\n\nReply in English.\n\n## mem://guide\n\nAlways be brief."
+    );
+    assert_eq!(body["tools"][0]["function"]["name"], "docs__read_resource");
+
+    let tool = registry.get("docs:read_resource").await.unwrap();
+    assert!(tool.description().contains("mem://guide"));
+    let result = tool.execute(json!({"uri": "mem://guide"})).await.unwrap();
+    assert_eq!(result["contents"], "Always be brief.");
+}